@@ -0,0 +1,153 @@
+//! In-place insertion of chunks before IEND.
+//!
+//! Only the tail of the file is rewritten: the new chunk goes where IEND
+//! was, followed by a fresh IEND and whatever data used to trail it. The old
+//! tail is saved to a journal next to the file first, so an interrupted
+//! append can be rolled back by `recover`.
+
+use std::convert::{TryFrom, TryInto};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use anyhow::anyhow;
+
+use crate::Result;
+use crate::chunk::{self, Chunk};
+use crate::chunk_type::ChunkType;
+use crate::png::Png;
+
+const JOURNAL_MAGIC: &[u8; 8] = b"PNGMEJNL";
+const JOURNAL_HEADER_LENGTH: usize = 24;
+
+/// Path of the journal kept while `path` is being modified.
+pub fn journal_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut name = path.as_ref().as_os_str().to_owned();
+    name.push(".pngme-journal");
+    PathBuf::from(name)
+}
+
+/// Inserts `chunk` right before IEND, rewriting only the tail of the file.
+/// A journal left over by an interrupted append is rolled back first.
+///
+/// If the chunk walk does not end on a well-formed IEND, the file is parsed
+/// and rewritten as a whole instead.
+pub fn append_chunk<P: AsRef<Path>>(path: P, chunk: &Chunk) -> Result<()> {
+    let path = path.as_ref();
+    recover(path)?;
+
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let file_len = file.metadata()?.len();
+    let iend = match find_iend(&mut file, file_len)? {
+        Some(iend) => iend,
+        None => {
+            drop(file);
+            return rewrite(path, chunk);
+        },
+    };
+    let mut old_tail = Vec::new();
+    file.seek(SeekFrom::Start(iend))?;
+    file.read_to_end(&mut old_tail)?;
+    if !old_tail.starts_with(&Chunk::new(ChunkType::IEND, Vec::new()).as_bytes()) {
+        drop(file);
+        return rewrite(path, chunk);
+    }
+
+    write_journal(path, file_len, iend, &old_tail)?;
+
+    let mut new_tail = chunk.as_bytes();
+    new_tail.extend_from_slice(&old_tail);
+    file.seek(SeekFrom::Start(iend))?;
+    file.write_all(&new_tail)?;
+    file.set_len(iend + new_tail.len() as u64)?;
+    file.sync_all()?;
+
+    fs::remove_file(journal_path(path))?;
+    Ok(())
+}
+
+/// Appends `chunk` by parsing and rewriting the whole file.
+fn rewrite(path: &Path, chunk: &Chunk) -> Result<()> {
+    let mut png = Png::try_from(fs::read(path)?.as_slice())?;
+    if png.positions_of(&ChunkType::IEND).is_empty() {
+        return Err(anyhow!("png has no IEND chunk"));
+    }
+    png.append_chunk(chunk.clone());
+    fs::write(path, png.as_bytes())?;
+    Ok(())
+}
+
+/// Rolls back an append to `path` that was interrupted, using its journal.
+/// Returns whether anything had to be restored.
+pub fn recover<P: AsRef<Path>>(path: P) -> Result<bool> {
+    let path = path.as_ref();
+    let journal = match fs::read(journal_path(path)) {
+        Ok(journal) => journal,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+
+    // A journal that was not completely written means the file itself was
+    // never touched, so it is simply discarded.
+    if let Some((file_len, offset, tail)) = parse_journal(&journal) {
+        let mut file = OpenOptions::new().write(true).open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(tail)?;
+        file.set_len(file_len)?;
+        file.sync_all()?;
+    }
+    fs::remove_file(journal_path(path))?;
+    Ok(true)
+}
+
+/// Walks the chunk headers of `file` to the offset of its IEND chunk, or
+/// `None` if the walk runs off the end of the file without finding one.
+fn find_iend(file: &mut File, file_len: u64) -> Result<Option<u64>> {
+    let mut header = [0u8; 8];
+    file.seek(SeekFrom::Start(0))?;
+    file.read_exact(&mut header).map_err(|_| anyhow!("invalid header"))?;
+    if header != Png::STANDARD_HEADER {
+        return Err(anyhow!("invalid header"));
+    }
+
+    let mut offset = header.len() as u64;
+    while offset + 12 <= file_len {
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let chunk_type = ChunkType::try_from([header[4], header[5], header[6], header[7]])?;
+        if chunk_type == ChunkType::IEND {
+            return Ok(Some(offset));
+        }
+        offset += 12 + length;
+    }
+    Ok(None)
+}
+
+fn write_journal(path: &Path, file_len: u64, offset: u64, tail: &[u8]) -> Result<()> {
+    let mut journal = Vec::with_capacity(JOURNAL_HEADER_LENGTH + tail.len() + 4);
+    journal.extend_from_slice(JOURNAL_MAGIC);
+    journal.extend_from_slice(&file_len.to_be_bytes());
+    journal.extend_from_slice(&offset.to_be_bytes());
+    journal.extend_from_slice(tail);
+    journal.extend_from_slice(&chunk::CRC32.checksum(&journal).to_be_bytes());
+
+    let mut file = File::create(journal_path(path))?;
+    file.write_all(&journal)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Returns the original length, the tail offset and the tail saved in a
+/// journal, or `None` if the journal is incomplete.
+fn parse_journal(journal: &[u8]) -> Option<(u64, u64, &[u8])> {
+    if journal.len() < JOURNAL_HEADER_LENGTH + 4 || !journal.starts_with(JOURNAL_MAGIC) {
+        return None;
+    }
+    let (body, crc) = journal.split_at(journal.len() - 4);
+    if chunk::CRC32.checksum(body).to_be_bytes() != crc {
+        return None;
+    }
+    let file_len = u64::from_be_bytes(body[8..16].try_into().ok()?);
+    let offset = u64::from_be_bytes(body[16..24].try_into().ok()?);
+    Some((file_len, offset, &body[JOURNAL_HEADER_LENGTH..]))
+}
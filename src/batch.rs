@@ -0,0 +1,216 @@
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use anyhow::anyhow;
+
+use crate::Result;
+
+/// How a single file was handled by a batch job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<T = String> {
+    /// The job ran; `T` is what it has to report for the file.
+    Succeeded(T),
+    /// The file was not processed, for the given reason.
+    Skipped(String),
+}
+
+/// The result of processing one input.
+pub struct FileResult<T = String> {
+    pub path: PathBuf,
+    pub outcome: Result<Outcome<T>>,
+}
+
+/// A serializable view of a `FileResult`, for commands that print one
+/// report per input.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FileReport<T> {
+    pub path: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub report: Option<T>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub skipped: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub error: Option<String>,
+}
+
+impl<T: Clone> FileReport<T> {
+    pub fn new(result: &FileResult<T>) -> Self {
+        let (report, skipped, error) = match &result.outcome {
+            Ok(Outcome::Succeeded(report)) => (Some(report.clone()), None, None),
+            Ok(Outcome::Skipped(reason)) => (None, Some(reason.clone()), None),
+            Err(e) => (None, None, Some(e.to_string())),
+        };
+        FileReport { path: result.path.display().to_string(), report, skipped, error }
+    }
+}
+
+impl<T: Display> Display for FileReport<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (&self.report, &self.skipped, &self.error) {
+            (Some(report), _, _) => write!(f, "{}: {}", self.path, report),
+            (_, Some(reason), _) => write!(f, "{}: skipped, {}", self.path, reason),
+            (_, _, error) => write!(f, "{}: failed, {}", self.path, error.as_deref().unwrap_or("unknown error")),
+        }
+    }
+}
+
+/// Options controlling how inputs are expanded and processed.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchOptions {
+    /// Descend into directories, picking up every `.png` below them.
+    pub recursive: bool,
+    /// Number of worker threads.
+    pub jobs: usize,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            recursive: false,
+            jobs: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+/// Counts of files per outcome.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+impl Summary {
+    pub fn new<T>(results: &[FileResult<T>]) -> Self {
+        results.iter().fold(Summary::default(), |mut summary, result| {
+            match result.outcome {
+                Ok(Outcome::Succeeded(_)) => summary.succeeded += 1,
+                Ok(Outcome::Skipped(_)) => summary.skipped += 1,
+                Err(_) => summary.failed += 1,
+            }
+            summary
+        })
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} succeeded, {} failed, {} skipped", self.succeeded, self.failed, self.skipped)
+    }
+}
+
+/// Expands `inputs` (paths, glob patterns or directories) and runs `job` on
+/// every resulting file using a pool of worker threads. Failures, panics
+/// included, are collected rather than stopping the batch. Results keep the
+/// input order.
+pub fn run<T, F>(inputs: &[String], options: &BatchOptions, job: F) -> Vec<FileResult<T>>
+    where T: Send, F: Fn(&Path) -> Result<Outcome<T>> + Sync
+{
+    let mut slots: Vec<Option<FileResult<T>>> = Vec::new();
+    let mut pending = Vec::new();
+    for target in expand(inputs, options.recursive) {
+        match target {
+            Ok(path) => {
+                pending.push((slots.len(), path));
+                slots.push(None);
+            },
+            Err((path, outcome)) => slots.push(Some(FileResult { path, outcome })),
+        }
+    }
+
+    let next = AtomicUsize::new(0);
+    let slots = Mutex::new(slots);
+    thread::scope(|scope| {
+        for _ in 0..options.jobs.clamp(1, pending.len().max(1)) {
+            scope.spawn(|| {
+                while let Some((slot, path)) = pending.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let outcome = run_job(&job, path);
+                    let result = FileResult { path: path.clone(), outcome };
+                    slots.lock().expect("a worker panicked")[*slot] = Some(result);
+                }
+            });
+        }
+    });
+    slots.into_inner()
+        .expect("a worker panicked")
+        .into_iter()
+        .map(|slot| slot.expect("every input is processed"))
+        .collect()
+}
+
+/// Runs `job` on `path`, turning a panic into a failure of that file.
+fn run_job<T, F>(job: &F, path: &Path) -> Result<Outcome<T>>
+    where F: Fn(&Path) -> Result<Outcome<T>>
+{
+    panic::catch_unwind(AssertUnwindSafe(|| job(path))).unwrap_or_else(|payload| {
+        let message = payload.downcast_ref::<&str>().copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown cause");
+        Err(anyhow!("panicked: {}", message))
+    })
+}
+
+/// Files to process, or inputs that are already decided (skipped or failed).
+type Target<T> = std::result::Result<PathBuf, (PathBuf, Result<Outcome<T>>)>;
+
+fn expand<T>(inputs: &[String], recursive: bool) -> Vec<Target<T>> {
+    let mut targets = Vec::new();
+    for input in inputs {
+        if !is_pattern(input) {
+            targets.append(&mut expand_path(PathBuf::from(input), recursive));
+            continue;
+        }
+        match glob::glob(input) {
+            Ok(paths) => {
+                let before = targets.len();
+                for path in paths {
+                    match path {
+                        Ok(path) => targets.append(&mut expand_path(path, recursive)),
+                        Err(e) => targets.push(Err((e.path().to_path_buf(), Err(anyhow!("{}", e.error()))))),
+                    }
+                }
+                if targets.len() == before {
+                    targets.push(Err((PathBuf::from(input), Err(anyhow!("no files match")))));
+                }
+            },
+            Err(e) => targets.push(Err((PathBuf::from(input), Err(anyhow!("invalid pattern: {}", e))))),
+        }
+    }
+    targets
+}
+
+/// Whether `input` is a glob pattern rather than a plain path.
+pub fn is_pattern(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
+
+fn expand_path<T>(path: PathBuf, recursive: bool) -> Vec<Target<T>> {
+    if !path.is_dir() {
+        return vec![Ok(path)];
+    }
+    if !recursive {
+        return vec![Err((path, Ok(Outcome::Skipped("is a directory, use --recursive".to_string()))))];
+    }
+    let mut files = Vec::new();
+    if let Err(e) = walk(&path, &mut files) {
+        return vec![Err((path, Err(e)))];
+    }
+    files.sort();
+    files.into_iter().map(Ok).collect()
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
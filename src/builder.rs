@@ -0,0 +1,183 @@
+//! Building spec-valid pngs from an image header, typed chunks and pixel
+//! rows, for fixtures and synthetic images.
+
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{
+    Background, Chromaticities, Cicp, Gamma, IccProfile, Ihdr, Palette, PhysicalDimensions, RenderingIntent, Srgb,
+    Text, Time, Transparency,
+};
+use crate::image::RawImage;
+use crate::png::Png;
+use crate::validate::{self, BEFORE_IDAT, COLOR_CHUNKS, PALETTE_CHUNKS};
+
+/// Fluent builder for a `Png`.
+///
+/// Chunks can be added in any order; `build` puts them where the spec
+/// requires. Setting a chunk that may only appear once replaces the previous
+/// one. Errors from the typed constructors are kept and returned by `build`,
+/// so calls can be chained.
+///
+/// ```
+/// use pngme::builder::PngBuilder;
+/// use pngme::chunks::{ColorType, Ihdr, RenderingIntent};
+///
+/// let png = PngBuilder::new(Ihdr::new(2, 1, 8, ColorType::Grayscale).unwrap())
+///     .text("Author", "x")
+///     .srgb(RenderingIntent::Perceptual)
+///     .row(&[0, 255])
+///     .build()
+///     .unwrap();
+/// assert_eq!(png.chunks().len(), 5);
+/// ```
+pub struct PngBuilder {
+    ihdr: Ihdr,
+    palette: Option<Palette>,
+    chunks: Vec<Chunk>,
+    data: Vec<u8>,
+    error: Option<Error>,
+}
+
+impl PngBuilder {
+    pub fn new(ihdr: Ihdr) -> Self {
+        PngBuilder { ihdr, palette: None, chunks: Vec::new(), data: Vec::new(), error: None }
+    }
+
+    fn fail(mut self, error: Error) -> Self {
+        self.error.get_or_insert(error);
+        self
+    }
+
+    fn with<T>(self, value: Result<T>, to_chunk: impl FnOnce(T) -> Result<Chunk>) -> Self {
+        match value.and_then(to_chunk) {
+            Ok(chunk) => self.set(chunk),
+            Err(e) => self.fail(e),
+        }
+    }
+
+    /// Adds `chunk`, replacing any chunk of the same type.
+    pub fn set(mut self, chunk: Chunk) -> Self {
+        self.chunks.retain(|other| other.chunk_type() != chunk.chunk_type());
+        self.chunks.push(chunk);
+        self
+    }
+
+    /// Adds `chunk`, keeping any others of the same type.
+    pub fn chunk(mut self, chunk: Chunk) -> Self {
+        self.chunks.push(chunk);
+        self
+    }
+
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+        self
+    }
+
+    pub fn transparency(self, transparency: Transparency) -> Self {
+        self.set(transparency.to_chunk())
+    }
+
+    pub fn background(self, background: Background) -> Self {
+        self.set(background.to_chunk())
+    }
+
+    /// Adds a tEXt chunk.
+    pub fn text(self, keyword: &str, text: &str) -> Self {
+        match Text::new(keyword, text).and_then(|text| text.to_chunk()) {
+            Ok(chunk) => self.chunk(chunk),
+            Err(e) => self.fail(e),
+        }
+    }
+
+    /// The image gamma as stored in gAMA, such as 0.45455 for content
+    /// encoded for a 2.2 display gamma.
+    pub fn gamma(self, gamma: f64) -> Self {
+        self.with(Gamma::new(gamma), |gamma| gamma.to_chunk())
+    }
+
+    pub fn chromaticities(self, chromaticities: Chromaticities) -> Self {
+        self.with(Ok(chromaticities), |chrm| chrm.to_chunk())
+    }
+
+    pub fn srgb(self, rendering_intent: RenderingIntent) -> Self {
+        self.set(Srgb::new(rendering_intent).to_chunk())
+    }
+
+    pub fn icc_profile(self, name: &str, profile: Vec<u8>) -> Self {
+        self.with(IccProfile::new(name, profile), |icc| icc.to_chunk())
+    }
+
+    pub fn cicp(self, cicp: Cicp) -> Self {
+        self.with(cicp.validate().map(|_| cicp), |cicp| Ok(cicp.to_chunk()))
+    }
+
+    pub fn physical_dimensions(self, dimensions: PhysicalDimensions) -> Self {
+        self.set(dimensions.to_chunk())
+    }
+
+    /// Records the same resolution on both axes, in dots per inch.
+    pub fn dpi(self, dpi: f64) -> Self {
+        self.with(PhysicalDimensions::from_dpi(dpi, dpi), |phys| Ok(phys.to_chunk()))
+    }
+
+    pub fn time(self, time: Time) -> Self {
+        self.with(time.validate().map(|_| time), |time| Ok(time.to_chunk()))
+    }
+
+    /// Appends one row of unfiltered pixel data, `ihdr.row_bytes(width)`
+    /// bytes long, with samples packed as the header describes.
+    pub fn row(mut self, row: &[u8]) -> Self {
+        let expected = self.ihdr.row_bytes(self.ihdr.width);
+        if row.len() != expected {
+            return self.fail(anyhow!("pixel rows must be {} bytes, got {}", expected, row.len()));
+        }
+        self.data.extend_from_slice(row);
+        self
+    }
+
+    pub fn rows<'a, I: IntoIterator<Item = &'a [u8]>>(self, rows: I) -> Self {
+        rows.into_iter().fold(self, |builder, row| builder.row(row))
+    }
+
+    /// Assembles the png, checking the header, palette and pixel data
+    /// against each other and the result against `validate`.
+    pub fn build(self) -> Result<Png> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        self.ihdr.validate()?;
+        if self.ihdr.is_interlaced() {
+            return Err(anyhow!("the builder only writes non-interlaced images"));
+        }
+        let rows = self.data.len() / self.ihdr.row_bytes(self.ihdr.width).max(1);
+        if rows != self.ihdr.height as usize {
+            return Err(anyhow!("image is {} rows high, got {} rows of pixels", self.ihdr.height, rows));
+        }
+        if let Some(palette) = &self.palette {
+            palette.validate_for(&self.ihdr)?;
+        }
+
+        let is_any = |types: &[ChunkType], chunk: &Chunk| types.contains(&chunk.chunk_type());
+        let mut chunks = vec![self.ihdr.to_chunk()];
+        chunks.extend(self.chunks.iter().filter(|chunk| is_any(&COLOR_CHUNKS, chunk)).cloned());
+        chunks.extend(self.palette.as_ref().map(Palette::to_chunk));
+        chunks.extend(self.chunks.iter().filter(|chunk| is_any(&PALETTE_CHUNKS, chunk)).cloned());
+        chunks.extend(self.chunks.iter().filter(|chunk| is_any(&BEFORE_IDAT, chunk)).cloned());
+        chunks.extend(self.chunks.iter()
+            .filter(|chunk| ![&COLOR_CHUNKS[..], &PALETTE_CHUNKS, &BEFORE_IDAT].iter().any(|types| is_any(types, chunk)))
+            .cloned());
+        let image = RawImage { ihdr: self.ihdr, data: self.data };
+        chunks.push(Chunk::new(ChunkType::IDAT, image.encode()));
+        chunks.push(Chunk::new(ChunkType::IEND, Vec::new()));
+
+        let png = Png::from_chunks(chunks);
+        let problems = validate::validate(&png);
+        if !problems.is_empty() {
+            return Err(anyhow!("built png is invalid: {}", problems.join(", ")));
+        }
+        Ok(png)
+    }
+}
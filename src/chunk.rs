@@ -1,15 +1,14 @@
 use std::convert::TryFrom;
-use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::io::{BufReader, Read};
 use crc::{Crc, CRC_32_ISO_HDLC};
 use anyhow::anyhow;
 
-use crate::{Error, Result};
+use crate::Result;
 use crate::chunk_type::ChunkType;
 
-const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
+pub(crate) const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
 
+#[derive(Debug, Clone)]
 pub struct Chunk {
     length: u32,
     chunk_type: ChunkType,
@@ -19,15 +18,23 @@ pub struct Chunk {
 
 impl Chunk {
     pub fn new(chunk_type: ChunkType, data: Vec<u8>) -> Self {
-        let mut digest = CRC32.digest_with_initial(u32::MAX);
-        digest.update(chunk_type.bytes().as_ref());
-        digest.update(data.as_slice());
+        let crc = compute_crc(&chunk_type, &data);
+        Chunk {
+            length: data.len() as u32,
+            chunk_type,
+            data,
+            crc,
+        }
+    }
 
+    /// Builds a chunk that keeps `crc` as its stored CRC even if it does not
+    /// match the data, as found in damaged files.
+    pub(crate) fn with_stored_crc(chunk_type: ChunkType, data: Vec<u8>, crc: u32) -> Self {
         Chunk {
             length: data.len() as u32,
             chunk_type,
             data,
-            crc: digest.finalize(),
+            crc,
         }
     }
 
@@ -47,6 +54,16 @@ impl Chunk {
         self.crc
     }
 
+    /// The CRC computed over the chunk type and data, which may differ from
+    /// the stored `crc` when the chunk was read from a damaged file.
+    pub fn computed_crc(&self) -> u32 {
+        compute_crc(&self.chunk_type, &self.data)
+    }
+
+    pub fn is_crc_valid(&self) -> bool {
+        self.crc == self.computed_crc()
+    }
+
     pub fn data_as_string(&self) -> Result<String> {
         match String::from_utf8(self.data.clone()) {
             Ok(str) => Ok(str),
@@ -64,10 +81,20 @@ impl Chunk {
     }
 }
 
+pub(crate) fn compute_crc(chunk_type: &ChunkType, data: &[u8]) -> u32 {
+    let mut digest = CRC32.digest_with_initial(u32::MAX);
+    digest.update(chunk_type.bytes().as_ref());
+    digest.update(data);
+    digest.finalize()
+}
+
 impl TryFrom<&[u8]> for Chunk {
     type Error = anyhow::Error;
 
     fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() < 12 {
+            return Err(anyhow!("chunk is only {} bytes long", value.len()));
+        }
         let length = match <[u8; 4]>::try_from(&value[..4]) {
             Ok(val) => u32::from_be_bytes(val),
             Err(e) => return Err(e.into()),
@@ -76,6 +103,9 @@ impl TryFrom<&[u8]> for Chunk {
             Ok(val) => ChunkType::try_from(val)?,
             Err(e) => return Err(e.into()),
         };
+        if value.len() < 12 + length {
+            return Err(anyhow!("chunk {} is truncated", chunk_type));
+        }
         let crc = match <[u8;4]>::try_from(&value[8+length..]) {
             Ok(v) => u32::from_be_bytes(v),
             Err(_) => return Err(anyhow!("invalid crc length")),
@@ -98,4 +128,96 @@ impl Display for Chunk {
         writeln!(f, "}}",)?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+/*
+Serde representation of a chunk, meant to be read and edited by humans:
+well-known chunks are decoded into their fields, anything else keeps its
+data as UTF-8 text when printable and base64 otherwise. Hex is accepted on
+input as well.
+ */
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use std::convert::TryFrom;
+    use anyhow::anyhow;
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde::de::Error as _;
+
+    use crate::Result;
+    use crate::chunk::Chunk;
+    use crate::chunk_type::ChunkType;
+    use crate::chunks::{Ihdr, Text};
+
+    #[derive(Serialize, Deserialize)]
+    struct ChunkRepr {
+        #[serde(rename = "type")]
+        chunk_type: ChunkType,
+        #[serde(flatten)]
+        body: Body,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Body {
+        Ihdr(Ihdr),
+        Text(Text),
+        Utf8 { utf8: String },
+        Hex { hex: String },
+        Base64 { base64: String },
+    }
+
+    fn is_printable(s: &str) -> bool {
+        s.chars().all(|ch| !ch.is_control() || ch == '\n' || ch == '\r' || ch == '\t')
+    }
+
+    impl From<&Chunk> for ChunkRepr {
+        fn from(chunk: &Chunk) -> Self {
+            let chunk_type = chunk.chunk_type();
+            let typed = match chunk_type {
+                ChunkType::IHDR => Ihdr::try_from(chunk.data()).ok().map(Body::Ihdr),
+                ChunkType::TEXT => Text::try_from(chunk.data()).ok().map(Body::Text),
+                _ => None,
+            };
+            let body = typed.unwrap_or_else(|| match std::str::from_utf8(chunk.data()) {
+                Ok(s) if is_printable(s) => Body::Utf8 { utf8: s.to_string() },
+                _ => Body::Base64 { base64: BASE64.encode(chunk.data()) },
+            });
+            ChunkRepr { chunk_type, body }
+        }
+    }
+
+    impl TryFrom<ChunkRepr> for Chunk {
+        type Error = anyhow::Error;
+
+        fn try_from(repr: ChunkRepr) -> Result<Self> {
+            let data = match repr.body {
+                Body::Ihdr(ihdr) if repr.chunk_type == ChunkType::IHDR => {
+                    ihdr.validate()?;
+                    ihdr.as_bytes()
+                },
+                Body::Text(text) if repr.chunk_type == ChunkType::TEXT => text.as_bytes()?,
+                Body::Ihdr(_) | Body::Text(_) => {
+                    return Err(anyhow!("decoded fields do not match chunk type {}", repr.chunk_type));
+                },
+                Body::Utf8 { utf8 } => utf8.into_bytes(),
+                Body::Hex { hex } => hex::decode(hex.split_whitespace().collect::<String>())?,
+                Body::Base64 { base64 } => BASE64.decode(base64.split_whitespace().collect::<String>())?,
+            };
+            Ok(Chunk::new(repr.chunk_type, data))
+        }
+    }
+
+    impl Serialize for Chunk {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            ChunkRepr::from(self).serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Chunk {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+            let repr = ChunkRepr::deserialize(deserializer)?;
+            Chunk::try_from(repr).map_err(D::Error::custom)
+        }
+    }
+}
@@ -1,21 +1,61 @@
 use std::convert::TryFrom;
 use std::fmt::{self, Formatter};
-use std::io::Stderr;
 use std::str::FromStr;
 use anyhow::anyhow;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
 
 use crate::{Error, Result};
 /*
 Chunk type structure spec
 http://www.libpng.org/pub/png/spec/1.2/PNG-Structure.html
  */
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ChunkType([u8; 4]);
 
 
 const MASK: u8 = 32;
 
+/// Private chunk types that well-known software or pngme itself already
+/// writes, so generated types never collide with them.
+const KNOWN_PRIVATE: [&[u8; 4]; 8] = [b"caNv", b"meSg", b"npLb", b"npOl", b"npTc", b"ruSt", b"skMf", b"vpAg"];
+
 impl ChunkType {
+    pub const IHDR: ChunkType = ChunkType(*b"IHDR");
+    pub const PLTE: ChunkType = ChunkType(*b"PLTE");
+    pub const IDAT: ChunkType = ChunkType(*b"IDAT");
+    pub const IEND: ChunkType = ChunkType(*b"IEND");
+    pub const BKGD: ChunkType = ChunkType(*b"bKGD");
+    pub const CHRM: ChunkType = ChunkType(*b"cHRM");
+    pub const CICP: ChunkType = ChunkType(*b"cICP");
+    pub const CLLI: ChunkType = ChunkType(*b"cLLI");
+    pub const EXIF: ChunkType = ChunkType(*b"eXIf");
+    pub const GAMA: ChunkType = ChunkType(*b"gAMA");
+    pub const HIST: ChunkType = ChunkType(*b"hIST");
+    pub const ICCP: ChunkType = ChunkType(*b"iCCP");
+    pub const MDCV: ChunkType = ChunkType(*b"mDCv");
+    pub const PHYS: ChunkType = ChunkType(*b"pHYs");
+    pub const SBIT: ChunkType = ChunkType(*b"sBIT");
+    pub const SPLT: ChunkType = ChunkType(*b"sPLT");
+    pub const SRGB: ChunkType = ChunkType(*b"sRGB");
+    pub const TEXT: ChunkType = ChunkType(*b"tEXt");
+    pub const TIME: ChunkType = ChunkType(*b"tIME");
+    pub const TRNS: ChunkType = ChunkType(*b"tRNS");
+    /// Private chunk holding one named message, see `messages`.
+    pub const MESSAGE: ChunkType = ChunkType(*b"meSg");
+
+    /// Creates a chunk type in a constant, panicking at compile time if the
+    /// bytes are not ASCII letters.
+    pub const fn new(bytes: [u8; 4]) -> Self {
+        let mut i = 0;
+        while i < 4 {
+            assert!(bytes[i].is_ascii_alphabetic(), "chunk type bytes must be ASCII letters");
+            i += 1;
+        }
+        ChunkType(bytes)
+    }
+
     pub fn bytes(&self) -> [u8; 4] {
         self.0
     }
@@ -41,6 +81,44 @@ impl ChunkType {
     pub fn is_safe_to_copy(&self) -> bool {
         self.0[3] & MASK != 0
     }
+
+    /// Picks a random ancillary, private, safe-to-copy chunk type.
+    pub fn random_private_ancillary<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        loop {
+            if let Some(chunk_type) = ChunkType::private_ancillary(rng.gen()) {
+                return chunk_type;
+            }
+        }
+    }
+
+    /// Derives an ancillary, private, safe-to-copy chunk type from `key` and
+    /// `label` with HMAC-SHA256, so that whoever knows the key finds the same
+    /// type again while it looks random to everyone else.
+    pub fn derive(key: &[u8], label: &str) -> Self {
+        (0u32..)
+            .find_map(|counter| {
+                let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts keys of any length");
+                mac.update(label.as_bytes());
+                if counter > 0 {
+                    mac.update(&counter.to_be_bytes());
+                }
+                let digest = mac.finalize().into_bytes();
+                ChunkType::private_ancillary([digest[0], digest[1], digest[2], digest[3]])
+            })
+            .expect("some counter yields an unused chunk type")
+    }
+
+    /// Maps four random bytes onto letters with the ancillary, private,
+    /// reserved and safe-to-copy bits set as needed, unless the result is a
+    /// known private chunk type.
+    fn private_ancillary(random: [u8; 4]) -> Option<Self> {
+        let letters = random.map(|b| b'a' + b % 26);
+        let chunk_type = ChunkType([letters[0], letters[1], letters[2] & !MASK, letters[3]]);
+        if KNOWN_PRIVATE.iter().any(|known| **known == chunk_type.0) {
+            return None;
+        }
+        Some(chunk_type)
+    }
 }
 
 impl TryFrom<[u8; 4]> for ChunkType {
@@ -69,7 +147,23 @@ impl FromStr for ChunkType {
 }
 
 impl fmt::Display for ChunkType {
+    /// Types read from damaged files may hold any bytes, those that are not
+    /// UTF-8 are shown as replacement characters.
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", String::from_utf8(Vec::from(self.0)).expect("fmt error for ChunkType"))
+        write!(f, "{}", String::from_utf8_lossy(&self.0))
+    }
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for ChunkType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ChunkType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        ChunkType::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::Result;
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{ColorType, Ihdr, Palette};
+
+/*
+bKGD layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.bKGD
+depends on the color type: a 2-byte gray sample, three 2-byte RGB samples,
+or a 1-byte palette index
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Background {
+    /// For grayscale images, with or without alpha.
+    Gray(u16),
+    /// For RGB images, with or without alpha.
+    Rgb { red: u16, green: u16, blue: u16 },
+    PaletteIndex(u8),
+}
+
+impl Background {
+    /// Parses bKGD data, whose layout is given by the image's color type.
+    pub fn from_bytes(value: &[u8], color_type: ColorType) -> Result<Self> {
+        let sample = |i: usize| u16::from_be_bytes([value[i], value[i + 1]]);
+        match color_type {
+            ColorType::Grayscale | ColorType::GrayscaleAlpha if value.len() == 2 => Ok(Background::Gray(sample(0))),
+            ColorType::Rgb | ColorType::Rgba if value.len() == 6 => Ok(Background::Rgb {
+                red: sample(0),
+                green: sample(2),
+                blue: sample(4),
+            }),
+            ColorType::Indexed if value.len() == 1 => Ok(Background::PaletteIndex(value[0])),
+            _ => Err(anyhow!("bKGD is {} bytes, which is invalid for color type {:?}", value.len(), color_type)),
+        }
+    }
+
+    /// Parses a bKGD chunk belonging to an image with header `ihdr`.
+    pub fn from_chunk(chunk: &Chunk, ihdr: &Ihdr) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::BKGD {
+            return Err(anyhow!("expected bKGD chunk, got {}", chunk.chunk_type()));
+        }
+        let background = Background::from_bytes(chunk.data(), ihdr.color_type)?;
+        background.validate_for(ihdr, None)?;
+        Ok(background)
+    }
+
+    /// Checks the background against the image header and, for indexed
+    /// images, the palette it points into.
+    pub fn validate_for(&self, ihdr: &Ihdr, palette: Option<&Palette>) -> Result<()> {
+        let max = ((1u32 << ihdr.bit_depth) - 1) as u16;
+        match (self, ihdr.color_type) {
+            (Background::Gray(gray), ColorType::Grayscale | ColorType::GrayscaleAlpha) if *gray > max => {
+                Err(anyhow!("bKGD gray value {} does not fit in {} bits", gray, ihdr.bit_depth))
+            },
+            (Background::Rgb { red, green, blue }, ColorType::Rgb | ColorType::Rgba)
+                if [red, green, blue].iter().any(|&&v| v > max) => {
+                Err(anyhow!("bKGD color does not fit in {} bits", ihdr.bit_depth))
+            },
+            (Background::PaletteIndex(index), ColorType::Indexed) => match palette {
+                Some(palette) if *index as usize >= palette.len() => {
+                    Err(anyhow!("bKGD palette index {} is past the {} palette entries", index, palette.len()))
+                },
+                _ => Ok(()),
+            },
+            (Background::Gray(_), ColorType::Grayscale | ColorType::GrayscaleAlpha)
+            | (Background::Rgb { .. }, ColorType::Rgb | ColorType::Rgba) => Ok(()),
+            _ => Err(anyhow!("bKGD does not match color type {:?}", ihdr.color_type)),
+        }
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        match *self {
+            Background::Gray(gray) => gray.to_be_bytes().to_vec(),
+            Background::Rgb { red, green, blue } => [red, green, blue].iter().flat_map(|v| v.to_be_bytes()).collect(),
+            Background::PaletteIndex(index) => vec![index],
+        }
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::BKGD, self.as_bytes())
+    }
+}
+
+impl Display for Background {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Background::Gray(gray) => write!(f, "gray {}", gray),
+            Background::Rgb { red, green, blue } => write!(f, "rgb {} {} {}", red, green, blue),
+            Background::PaletteIndex(index) => write!(f, "palette index {}", index),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::gama::{from_fixed_point, to_fixed_point};
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/// A CIE 1931 xy chromaticity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chromaticity {
+    pub x: f64,
+    pub y: f64,
+}
+
+/*
+cHRM layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.cHRM
+x and y of the white point, red, green and blue, each times 100000 as a
+4-byte unsigned integer
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chromaticities {
+    pub white: Chromaticity,
+    pub red: Chromaticity,
+    pub green: Chromaticity,
+    pub blue: Chromaticity,
+}
+
+impl Chromaticities {
+    pub const LENGTH: usize = 32;
+
+    /// The primaries and D65 white point of sRGB and BT.709.
+    pub const SRGB: Chromaticities = Chromaticities {
+        white: Chromaticity { x: 0.3127, y: 0.329 },
+        red: Chromaticity { x: 0.64, y: 0.33 },
+        green: Chromaticity { x: 0.3, y: 0.6 },
+        blue: Chromaticity { x: 0.15, y: 0.06 },
+    };
+
+    fn points(&self) -> [Chromaticity; 4] {
+        [self.white, self.red, self.green, self.blue]
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for point in self.points().iter() {
+            to_fixed_point(point.x)?;
+            to_fixed_point(point.y)?;
+        }
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(Chromaticities::LENGTH);
+        for point in self.points().iter() {
+            bytes.extend_from_slice(&to_fixed_point(point.x)?.to_be_bytes());
+            bytes.extend_from_slice(&to_fixed_point(point.y)?.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+
+    pub fn to_chunk(&self) -> Result<Chunk> {
+        Ok(Chunk::new(ChunkType::CHRM, self.as_bytes()?))
+    }
+}
+
+impl TypedChunk for Chromaticities {
+    const TYPE: ChunkType = ChunkType::CHRM;
+    const PLACEMENT: Placement = Placement::BeforePalette;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Chromaticities::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.as_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for Chromaticities {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() != Chromaticities::LENGTH {
+            return Err(anyhow!("cHRM must be {} bytes, got {}", Chromaticities::LENGTH, value.len()));
+        }
+        let field = |i: usize| from_fixed_point(u32::from_be_bytes([value[i], value[i + 1], value[i + 2], value[i + 3]]));
+        let point = |i: usize| Chromaticity { x: field(i), y: field(i + 4) };
+        Ok(Chromaticities {
+            white: point(0),
+            red: point(8),
+            green: point(16),
+            blue: point(24),
+        })
+    }
+}
+
+impl TryFrom<&Chunk> for Chromaticities {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::CHRM {
+            return Err(anyhow!("expected cHRM chunk, got {}", chunk.chunk_type()));
+        }
+        Chromaticities::try_from(chunk.data())
+    }
+}
+
+impl Display for Chromaticity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({:.5}, {:.5})", self.x, self.y)
+    }
+}
+
+impl Display for Chromaticities {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "white {}, red {}, green {}, blue {}", self.white, self.red, self.green, self.blue)
+    }
+}
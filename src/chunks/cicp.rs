@@ -0,0 +1,127 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/*
+cICP layout
+https://www.w3.org/TR/png-3/#cICP-chunk
+colour primaries, transfer function, matrix coefficients and video full
+range flag, one byte each, with code points from ITU-T H.273
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cicp {
+    pub colour_primaries: u8,
+    pub transfer_function: u8,
+    pub matrix_coefficients: u8,
+    pub video_full_range: bool,
+}
+
+impl Cicp {
+    pub const LENGTH: usize = 4;
+
+    pub fn validate(&self) -> Result<()> {
+        // png only stores RGB, so no matrix may be applied
+        if self.matrix_coefficients != 0 {
+            return Err(anyhow!("cICP matrix coefficients must be 0, got {}", self.matrix_coefficients));
+        }
+        Ok(())
+    }
+
+    /// Name of the transfer function, for the common code points.
+    pub fn transfer_name(&self) -> Option<&'static str> {
+        match self.transfer_function {
+            1 => Some("BT.709"),
+            8 => Some("linear"),
+            13 => Some("sRGB"),
+            16 => Some("PQ"),
+            18 => Some("HLG"),
+            _ => None,
+        }
+    }
+
+    /// Name of the colour primaries, for the common code points.
+    pub fn primaries_name(&self) -> Option<&'static str> {
+        match self.colour_primaries {
+            1 => Some("BT.709"),
+            9 => Some("BT.2020"),
+            12 => Some("Display P3"),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        vec![self.colour_primaries, self.transfer_function, self.matrix_coefficients, self.video_full_range as u8]
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::CICP, self.as_bytes())
+    }
+}
+
+impl TypedChunk for Cicp {
+    const TYPE: ChunkType = ChunkType::CICP;
+    const PLACEMENT: Placement = Placement::BeforePalette;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Cicp::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        Ok(self.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for Cicp {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() != Cicp::LENGTH {
+            return Err(anyhow!("cICP must be {} bytes, got {}", Cicp::LENGTH, value.len()));
+        }
+        let video_full_range = match value[3] {
+            0 => false,
+            1 => true,
+            flag => return Err(anyhow!("invalid cICP video full range flag {}", flag)),
+        };
+        let cicp = Cicp {
+            colour_primaries: value[0],
+            transfer_function: value[1],
+            matrix_coefficients: value[2],
+            video_full_range,
+        };
+        cicp.validate()?;
+        Ok(cicp)
+    }
+}
+
+impl TryFrom<&Chunk> for Cicp {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::CICP {
+            return Err(anyhow!("expected cICP chunk, got {}", chunk.chunk_type()));
+        }
+        Cicp::try_from(chunk.data())
+    }
+}
+
+impl Display for Cicp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.primaries_name() {
+            Some(name) => write!(f, "{} primaries", name)?,
+            None => write!(f, "primaries {}", self.colour_primaries)?,
+        }
+        match self.transfer_name() {
+            Some(name) => write!(f, ", {} transfer", name)?,
+            None => write!(f, ", transfer {}", self.transfer_function)?,
+        }
+        write!(f, ", {} range", if self.video_full_range { "full" } else { "narrow" })
+    }
+}
@@ -0,0 +1,88 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::mdcv::{to_luminance_units, LUMINANCE_SCALE};
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/*
+cLLI layout
+https://www.w3.org/TR/png-3/#cLLI-chunk
+maximum content light level and maximum frame-average light level, in units
+of 0.0001 cd/m² as 4-byte unsigned integers
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContentLightLevel {
+    /// MaxCLL, in cd/m².
+    pub max_content: f64,
+    /// MaxFALL, in cd/m².
+    pub max_frame_average: f64,
+}
+
+impl ContentLightLevel {
+    pub const LENGTH: usize = 8;
+
+    pub fn validate(&self) -> Result<()> {
+        to_luminance_units(self.max_content)?;
+        to_luminance_units(self.max_frame_average)?;
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = to_luminance_units(self.max_content)?.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&to_luminance_units(self.max_frame_average)?.to_be_bytes());
+        Ok(bytes)
+    }
+
+    pub fn to_chunk(&self) -> Result<Chunk> {
+        Ok(Chunk::new(ChunkType::CLLI, self.as_bytes()?))
+    }
+}
+
+impl TypedChunk for ContentLightLevel {
+    const TYPE: ChunkType = ChunkType::CLLI;
+    const PLACEMENT: Placement = Placement::BeforePalette;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        ContentLightLevel::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.as_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for ContentLightLevel {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() != ContentLightLevel::LENGTH {
+            return Err(anyhow!("cLLI must be {} bytes, got {}", ContentLightLevel::LENGTH, value.len()));
+        }
+        let level = |i: usize| {
+            u32::from_be_bytes([value[i], value[i + 1], value[i + 2], value[i + 3]]) as f64 / LUMINANCE_SCALE
+        };
+        Ok(ContentLightLevel { max_content: level(0), max_frame_average: level(4) })
+    }
+}
+
+impl TryFrom<&Chunk> for ContentLightLevel {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::CLLI {
+            return Err(anyhow!("expected cLLI chunk, got {}", chunk.chunk_type()));
+        }
+        ContentLightLevel::try_from(chunk.data())
+    }
+}
+
+impl Display for ContentLightLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "MaxCLL {} cd/m², MaxFALL {} cd/m²", self.max_content, self.max_frame_average)
+    }
+}
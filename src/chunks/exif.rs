@@ -0,0 +1,695 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/// Tags whose value is the offset of a sub-IFD rather than data.
+pub const EXIF_IFD: u16 = 0x8769;
+pub const GPS_IFD: u16 = 0x8825;
+pub const INTEROP_IFD: u16 = 0xa005;
+const SUB_IFDS: [u16; 3] = [EXIF_IFD, GPS_IFD, INTEROP_IFD];
+
+const THUMBNAIL_OFFSET: u16 = 0x0201;
+const THUMBNAIL_LENGTH: u16 = 0x0202;
+const GPS_LATITUDE_REF: u16 = 0x0001;
+const GPS_LATITUDE: u16 = 0x0002;
+const GPS_LONGITUDE_REF: u16 = 0x0003;
+const GPS_LONGITUDE: u16 = 0x0004;
+
+/// Some writers keep the JPEG APP1 marker prefix, which PNG does not use.
+const APP1_PREFIX: &[u8] = b"Exif\0\0";
+const MAX_DEPTH: usize = 4;
+
+const LONG: u16 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+impl ByteOrder {
+    fn u16(self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+        match self {
+            ByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+        }
+    }
+
+    fn u32(self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match self {
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+        }
+    }
+
+    fn u16_bytes(self, value: u16) -> [u8; 2] {
+        match self {
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+            ByteOrder::BigEndian => value.to_be_bytes(),
+        }
+    }
+
+    fn u32_bytes(self, value: u32) -> [u8; 4] {
+        match self {
+            ByteOrder::LittleEndian => value.to_le_bytes(),
+            ByteOrder::BigEndian => value.to_be_bytes(),
+        }
+    }
+}
+
+/// Which IFD a tag belongs to; tag numbers are only unique within one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Group {
+    /// IFD0, describing the main image.
+    Image,
+    Exif,
+    Gps,
+    Interop,
+    /// IFD1, describing the thumbnail.
+    Thumbnail,
+}
+
+impl Group {
+    fn of_sub_ifd(tag: u16) -> Group {
+        match tag {
+            GPS_IFD => Group::Gps,
+            INTEROP_IFD => Group::Interop,
+            _ => Group::Exif,
+        }
+    }
+}
+
+impl Display for Group {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Group::Image => "image",
+            Group::Exif => "exif",
+            Group::Gps => "gps",
+            Group::Interop => "interop",
+            Group::Thumbnail => "thumbnail",
+        };
+        f.pad(name)
+    }
+}
+
+/// The name of a common tag of `group`.
+pub fn tag_name(group: Group, tag: u16) -> Option<&'static str> {
+    let name = match (group, tag) {
+        (Group::Image | Group::Thumbnail, _) => match tag {
+            0x0100 => "ImageWidth",
+            0x0101 => "ImageLength",
+            0x0102 => "BitsPerSample",
+            0x0103 => "Compression",
+            0x0106 => "PhotometricInterpretation",
+            0x010e => "ImageDescription",
+            0x010f => "Make",
+            0x0110 => "Model",
+            0x0111 => "StripOffsets",
+            0x0112 => "Orientation",
+            0x0115 => "SamplesPerPixel",
+            0x011a => "XResolution",
+            0x011b => "YResolution",
+            0x0128 => "ResolutionUnit",
+            0x0131 => "Software",
+            0x0132 => "DateTime",
+            0x013b => "Artist",
+            0x013e => "WhitePoint",
+            0x013f => "PrimaryChromaticities",
+            0x0201 => "JPEGInterchangeFormat",
+            0x0202 => "JPEGInterchangeFormatLength",
+            0x0213 => "YCbCrPositioning",
+            0x8298 => "Copyright",
+            0xc62f => "CameraSerialNumber",
+            _ => return None,
+        },
+        (Group::Exif, _) => match tag {
+            0x829a => "ExposureTime",
+            0x829d => "FNumber",
+            0x8822 => "ExposureProgram",
+            0x8827 => "PhotographicSensitivity",
+            0x9000 => "ExifVersion",
+            0x9003 => "DateTimeOriginal",
+            0x9004 => "DateTimeDigitized",
+            0x9010 => "OffsetTime",
+            0x9011 => "OffsetTimeOriginal",
+            0x9101 => "ComponentsConfiguration",
+            0x9201 => "ShutterSpeedValue",
+            0x9202 => "ApertureValue",
+            0x9204 => "ExposureBiasValue",
+            0x9207 => "MeteringMode",
+            0x9209 => "Flash",
+            0x920a => "FocalLength",
+            0x927c => "MakerNote",
+            0x9286 => "UserComment",
+            0x9290 => "SubSecTime",
+            0x9291 => "SubSecTimeOriginal",
+            0xa000 => "FlashpixVersion",
+            0xa001 => "ColorSpace",
+            0xa002 => "PixelXDimension",
+            0xa003 => "PixelYDimension",
+            0xa402 => "ExposureMode",
+            0xa403 => "WhiteBalance",
+            0xa405 => "FocalLengthIn35mmFilm",
+            0xa406 => "SceneCaptureType",
+            0xa420 => "ImageUniqueID",
+            0xa430 => "CameraOwnerName",
+            0xa431 => "BodySerialNumber",
+            0xa432 => "LensSpecification",
+            0xa433 => "LensMake",
+            0xa434 => "LensModel",
+            0xa435 => "LensSerialNumber",
+            _ => return None,
+        },
+        (Group::Gps, _) => match tag {
+            0x0000 => "GPSVersionID",
+            GPS_LATITUDE_REF => "GPSLatitudeRef",
+            GPS_LATITUDE => "GPSLatitude",
+            GPS_LONGITUDE_REF => "GPSLongitudeRef",
+            GPS_LONGITUDE => "GPSLongitude",
+            0x0005 => "GPSAltitudeRef",
+            0x0006 => "GPSAltitude",
+            0x0007 => "GPSTimeStamp",
+            0x000c => "GPSSpeedRef",
+            0x000d => "GPSSpeed",
+            0x0010 => "GPSImgDirectionRef",
+            0x0011 => "GPSImgDirection",
+            0x0012 => "GPSMapDatum",
+            0x001b => "GPSProcessingMethod",
+            0x001d => "GPSDateStamp",
+            0x001f => "GPSHPositioningError",
+            _ => return None,
+        },
+        (Group::Interop, 0x0001) => "InteroperabilityIndex",
+        (Group::Interop, 0x0002) => "InteroperabilityVersion",
+        (Group::Interop, _) => return None,
+    };
+    Some(name)
+}
+
+/// Size in bytes of one value of a TIFF field type.
+fn type_size(field_type: u16) -> Option<usize> {
+    match field_type {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 | 13 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+/// A decoded tag value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// BYTE and UNDEFINED values.
+    Bytes(Vec<u8>),
+    Ascii(String),
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    Rational(Vec<(u32, u32)>),
+    SignedByte(Vec<i8>),
+    SignedShort(Vec<i16>),
+    SignedLong(Vec<i32>),
+    SignedRational(Vec<(i32, i32)>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+}
+
+impl Value {
+    /// The values as floating point numbers, for the numeric types.
+    pub fn to_f64s(&self) -> Option<Vec<f64>> {
+        let ratio = |n: f64, d: f64| if d == 0.0 { f64::NAN } else { n / d };
+        match self {
+            Value::Bytes(_) | Value::Ascii(_) => None,
+            Value::Short(values) => Some(values.iter().map(|&v| v as f64).collect()),
+            Value::Long(values) => Some(values.iter().map(|&v| v as f64).collect()),
+            Value::Rational(values) => Some(values.iter().map(|&(n, d)| ratio(n as f64, d as f64)).collect()),
+            Value::SignedByte(values) => Some(values.iter().map(|&v| v as f64).collect()),
+            Value::SignedShort(values) => Some(values.iter().map(|&v| v as f64).collect()),
+            Value::SignedLong(values) => Some(values.iter().map(|&v| v as f64).collect()),
+            Value::SignedRational(values) => Some(values.iter().map(|&(n, d)| ratio(n as f64, d as f64)).collect()),
+            Value::Float(values) => Some(values.iter().map(|&v| v as f64).collect()),
+            Value::Double(values) => Some(values.clone()),
+        }
+    }
+}
+
+fn join<T: Display>(values: &[T]) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn rational<T: Display + PartialEq + From<u8>>(n: &T, d: &T) -> String {
+    if *d == T::from(1) { n.to_string() } else { format!("{}/{}", n, d) }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bytes(bytes) if bytes.iter().all(|b| b.is_ascii_graphic() || *b == b' ') && !bytes.is_empty() => {
+                write!(f, "{}", String::from_utf8_lossy(bytes))
+            },
+            Value::Bytes(bytes) if bytes.len() <= 8 => {
+                write!(f, "{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" "))
+            },
+            Value::Bytes(bytes) => write!(f, "{} bytes", bytes.len()),
+            Value::Ascii(text) => write!(f, "{}", text),
+            Value::Short(values) => write!(f, "{}", join(values)),
+            Value::Long(values) => write!(f, "{}", join(values)),
+            Value::Rational(values) => {
+                write!(f, "{}", values.iter().map(|(n, d)| rational(n, d)).collect::<Vec<_>>().join(", "))
+            },
+            Value::SignedByte(values) => write!(f, "{}", join(values)),
+            Value::SignedShort(values) => write!(f, "{}", join(values)),
+            Value::SignedLong(values) => write!(f, "{}", join(values)),
+            Value::SignedRational(values) => {
+                let values: Vec<String> = values.iter().map(|&(n, d)| rational(&(n as i64), &(d as i64))).collect();
+                write!(f, "{}", values.join(", "))
+            },
+            Value::Float(values) => write!(f, "{}", join(values)),
+            Value::Double(values) => write!(f, "{}", join(values)),
+        }
+    }
+}
+
+/// One tag of an IFD, with its raw value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub tag: u16,
+    pub field_type: u16,
+    pub count: u32,
+    /// The value bytes, in the byte order of the containing `Exif`.
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    pub fn value(&self, byte_order: ByteOrder) -> Value {
+        let data = &self.data;
+        let u16s = || data.chunks_exact(2).map(|b| byte_order.u16(b)).collect::<Vec<_>>();
+        let u32s = || data.chunks_exact(4).map(|b| byte_order.u32(b)).collect::<Vec<_>>();
+        let pairs = || data.chunks_exact(8).map(|b| (byte_order.u32(&b[..4]), byte_order.u32(&b[4..]))).collect::<Vec<_>>();
+        match self.field_type {
+            2 => {
+                let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+                Value::Ascii(String::from_utf8_lossy(&data[..end]).into_owned())
+            },
+            3 => Value::Short(u16s()),
+            4 | 13 => Value::Long(u32s()),
+            5 => Value::Rational(pairs()),
+            6 => Value::SignedByte(data.iter().map(|&b| b as i8).collect()),
+            8 => Value::SignedShort(u16s().into_iter().map(|v| v as i16).collect()),
+            9 => Value::SignedLong(u32s().into_iter().map(|v| v as i32).collect()),
+            10 => Value::SignedRational(pairs().into_iter().map(|(n, d)| (n as i32, d as i32)).collect()),
+            11 => Value::Float(u32s().into_iter().map(f32::from_bits).collect()),
+            12 => Value::Double(data.chunks_exact(8)
+                .map(|b| {
+                    let (high, low) = match byte_order {
+                        ByteOrder::BigEndian => (byte_order.u32(&b[..4]), byte_order.u32(&b[4..])),
+                        ByteOrder::LittleEndian => (byte_order.u32(&b[4..]), byte_order.u32(&b[..4])),
+                    };
+                    f64::from_bits((high as u64) << 32 | low as u64)
+                })
+                .collect()),
+            _ => Value::Bytes(data.clone()),
+        }
+    }
+}
+
+/// An image file directory: a list of tags, some of which point to further
+/// IFDs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ifd {
+    pub entries: Vec<Entry>,
+    /// Sub-IFDs keyed by the tag pointing at them, such as `EXIF_IFD`.
+    pub sub_ifds: Vec<(u16, Ifd)>,
+}
+
+impl Ifd {
+    pub fn get(&self, tag: u16) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.tag == tag)
+    }
+
+    pub fn sub_ifd(&self, tag: u16) -> Option<&Ifd> {
+        self.sub_ifds.iter().find(|(t, _)| *t == tag).map(|(_, ifd)| ifd)
+    }
+
+    pub fn sub_ifd_mut(&mut self, tag: u16) -> Option<&mut Ifd> {
+        self.sub_ifds.iter_mut().find(|(t, _)| *t == tag).map(|(_, ifd)| ifd)
+    }
+
+    /// Removes the entries with any of `tags`, returning how many were removed.
+    pub fn remove_entries(&mut self, tags: &[u16]) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|entry| !tags.contains(&entry.tag));
+        before - self.entries.len()
+    }
+
+    /// Removes the sub-IFD under `tag` and returns it.
+    pub fn remove_sub_ifd(&mut self, tag: u16) -> Option<Ifd> {
+        let position = self.sub_ifds.iter().position(|(t, _)| *t == tag)?;
+        Some(self.sub_ifds.remove(position).1)
+    }
+
+    /// Number of entries in this IFD and its sub-IFDs.
+    pub fn len(&self) -> usize {
+        self.entries.len() + self.sub_ifds.iter().map(|(_, ifd)| ifd.len()).sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A tag as listed by `Exif::fields`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Field {
+    pub group: Group,
+    pub tag: u16,
+    /// The tag name, or its number in hex for uncommon tags.
+    pub name: String,
+    pub value: String,
+}
+
+/*
+eXIf layout
+https://www.w3.org/TR/png-3/#eXIf
+a TIFF header ("II*\0" or "MM\0*" and the offset of IFD0) followed by IFDs
+as in the Exif specification; offsets are relative to the start of the chunk
+data
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exif {
+    pub byte_order: ByteOrder,
+    pub ifd0: Ifd,
+    /// IFD1, describing the thumbnail.
+    pub ifd1: Option<Ifd>,
+    /// The JPEG thumbnail IFD1 points to.
+    pub thumbnail: Option<Vec<u8>>,
+}
+
+impl Exif {
+    /// The IFD holding the tags of `group`.
+    pub fn ifd(&self, group: Group) -> Option<&Ifd> {
+        match group {
+            Group::Image => Some(&self.ifd0),
+            Group::Thumbnail => self.ifd1.as_ref(),
+            Group::Exif => self.ifd0.sub_ifd(EXIF_IFD),
+            Group::Gps => self.ifd0.sub_ifd(GPS_IFD),
+            Group::Interop => self.ifd0.sub_ifd(EXIF_IFD).and_then(|exif| exif.sub_ifd(INTEROP_IFD)),
+        }
+    }
+
+    pub fn value(&self, group: Group, tag: u16) -> Option<Value> {
+        self.ifd(group)?.get(tag).map(|entry| entry.value(self.byte_order))
+    }
+
+    /// Latitude and longitude in decimal degrees, south and west negative.
+    pub fn gps_position(&self) -> Option<(f64, f64)> {
+        let coordinate = |tag: u16, ref_tag: u16, negative: &str| {
+            let degrees = degrees(&self.value(Group::Gps, tag)?)?;
+            match self.value(Group::Gps, ref_tag) {
+                Some(Value::Ascii(reference)) if reference == negative => Some(-degrees),
+                _ => Some(degrees),
+            }
+        };
+        Some((coordinate(GPS_LATITUDE, GPS_LATITUDE_REF, "S")?, coordinate(GPS_LONGITUDE, GPS_LONGITUDE_REF, "W")?))
+    }
+
+    /// Every tag, IFD0 and its sub-IFDs first, then the thumbnail's.
+    pub fn fields(&self) -> Vec<Field> {
+        let mut fields = Vec::new();
+        self.collect_fields(&self.ifd0, Group::Image, &mut fields);
+        if let Some(ifd1) = &self.ifd1 {
+            self.collect_fields(ifd1, Group::Thumbnail, &mut fields);
+        }
+        fields
+    }
+
+    fn collect_fields(&self, ifd: &Ifd, group: Group, fields: &mut Vec<Field>) {
+        for entry in ifd.entries.iter() {
+            let value = entry.value(self.byte_order);
+            let value = match (group, entry.tag) {
+                (Group::Gps, GPS_LATITUDE | GPS_LONGITUDE) => degrees(&value).map_or(value.to_string(), |d| format!("{:.6}", d)),
+                _ => value.to_string(),
+            };
+            fields.push(Field {
+                group,
+                tag: entry.tag,
+                name: tag_name(group, entry.tag).map_or_else(|| format!("0x{:04x}", entry.tag), str::to_string),
+                value,
+            });
+        }
+        for (tag, sub_ifd) in ifd.sub_ifds.iter() {
+            self.collect_fields(sub_ifd, Group::of_sub_ifd(*tag), fields);
+        }
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut out = match self.byte_order {
+            ByteOrder::LittleEndian => b"II*\0".to_vec(),
+            ByteOrder::BigEndian => b"MM\0*".to_vec(),
+        };
+        out.extend_from_slice(&self.byte_order.u32_bytes(8));
+        let next = self.write_ifd(&mut out, &self.ifd0, None);
+        if let Some(ifd1) = &self.ifd1 {
+            self.link(&mut out, next);
+            self.write_ifd(&mut out, ifd1, self.thumbnail.as_deref());
+        }
+        out
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::EXIF, self.as_bytes())
+    }
+
+    /// Word-aligns `out` and points the offset at `position` to its end.
+    fn link(&self, out: &mut Vec<u8>, position: usize) {
+        if out.len() % 2 == 1 {
+            out.push(0);
+        }
+        let offset = self.byte_order.u32_bytes(out.len() as u32);
+        out[position..position + 4].copy_from_slice(&offset);
+    }
+
+    /// Writes `ifd` with its values and sub-IFDs after it, returning the
+    /// position of its next-IFD offset.
+    fn write_ifd(&self, out: &mut Vec<u8>, ifd: &Ifd, thumbnail: Option<&[u8]>) -> usize {
+        let order = self.byte_order;
+        let mut tags: Vec<(u16, Option<&Entry>)> = ifd.entries.iter()
+            .filter(|entry| ifd.sub_ifd(entry.tag).is_none())
+            .map(|entry| (entry.tag, Some(entry)))
+            .chain(ifd.sub_ifds.iter().map(|(tag, _)| (*tag, None)))
+            .collect();
+        tags.sort_by_key(|(tag, _)| *tag);
+
+        out.extend_from_slice(&order.u16_bytes(tags.len() as u16));
+        let mut values = Vec::new();
+        let mut sub_ifds = Vec::new();
+        let mut thumbnail_offset = None;
+        for (tag, entry) in tags {
+            out.extend_from_slice(&order.u16_bytes(tag));
+            let position = out.len() + 6;
+            match (entry, thumbnail) {
+                (None, _) => {
+                    out.extend_from_slice(&order.u16_bytes(LONG));
+                    out.extend_from_slice(&order.u32_bytes(1));
+                    out.extend_from_slice(&[0; 4]);
+                    sub_ifds.push((position, ifd.sub_ifd(tag).expect("tag comes from sub_ifds")));
+                },
+                (Some(_), Some(thumbnail)) if tag == THUMBNAIL_OFFSET || tag == THUMBNAIL_LENGTH => {
+                    out.extend_from_slice(&order.u16_bytes(LONG));
+                    out.extend_from_slice(&order.u32_bytes(1));
+                    out.extend_from_slice(&order.u32_bytes(thumbnail.len() as u32));
+                    if tag == THUMBNAIL_OFFSET {
+                        thumbnail_offset = Some(position);
+                    }
+                },
+                (Some(entry), _) => {
+                    out.extend_from_slice(&order.u16_bytes(entry.field_type));
+                    out.extend_from_slice(&order.u32_bytes(entry.count));
+                    if entry.data.len() <= 4 {
+                        out.extend_from_slice(&entry.data);
+                        out.resize(out.len() + 4 - entry.data.len(), 0);
+                    } else {
+                        out.extend_from_slice(&[0; 4]);
+                        values.push((position, &entry.data));
+                    }
+                },
+            }
+        }
+        let next = out.len();
+        out.extend_from_slice(&[0; 4]);
+
+        for (position, data) in values {
+            self.link(out, position);
+            out.extend_from_slice(data);
+        }
+        for (position, sub_ifd) in sub_ifds {
+            self.link(out, position);
+            self.write_ifd(out, sub_ifd, None);
+        }
+        if let (Some(position), Some(thumbnail)) = (thumbnail_offset, thumbnail) {
+            self.link(out, position);
+            out.extend_from_slice(thumbnail);
+        }
+        next
+    }
+}
+
+/// Degrees, minutes and seconds rationals as decimal degrees.
+fn degrees(value: &Value) -> Option<f64> {
+    match value {
+        Value::Rational(_) => {
+            let parts = value.to_f64s()?;
+            (parts.len() == 3).then(|| parts[0] + parts[1] / 60.0 + parts[2] / 3600.0)
+        },
+        _ => None,
+    }
+}
+
+/// Reads IFDs out of the TIFF data of an eXIf chunk.
+struct Reader<'a> {
+    data: &'a [u8],
+    byte_order: ByteOrder,
+    /// Offsets of the IFDs read so far. Each is read at most once, so
+    /// crafted offsets cannot make the reader loop or fan out.
+    visited: HashSet<usize>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], byte_order: ByteOrder) -> Self {
+        Reader { data, byte_order, visited: HashSet::new() }
+    }
+
+    fn slice(&self, offset: usize, length: usize) -> Result<&'a [u8]> {
+        offset.checked_add(length)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or_else(|| anyhow!("eXIf offset {} is past the end of the data", offset))
+    }
+
+    /// Reads the IFD at `offset` and returns it with the offset of the next.
+    fn ifd(&mut self, offset: usize, depth: usize) -> Result<(Ifd, usize)> {
+        if depth > MAX_DEPTH {
+            return Err(anyhow!("eXIf IFDs are nested too deeply"));
+        }
+        if !self.visited.insert(offset) {
+            return Err(anyhow!("eXIf IFD at offset {} is referenced more than once", offset));
+        }
+        let count = self.byte_order.u16(self.slice(offset, 2)?) as usize;
+        let table = self.slice(offset + 2, count * 12 + 4)?;
+        let mut ifd = Ifd::default();
+        for raw in table.chunks_exact(12) {
+            let tag = self.byte_order.u16(&raw[0..2]);
+            let field_type = self.byte_order.u16(&raw[2..4]);
+            let count = self.byte_order.u32(&raw[4..8]);
+            let value = &raw[8..12];
+            if SUB_IFDS.contains(&tag) {
+                if ifd.sub_ifd(tag).is_some() {
+                    return Err(anyhow!("eXIf IFD has more than one 0x{:04x} tag", tag));
+                }
+                let (sub_ifd, _) = self.ifd(self.byte_order.u32(value) as usize, depth + 1)?;
+                ifd.sub_ifds.push((tag, sub_ifd));
+                continue;
+            }
+            let length = type_size(field_type)
+                .ok_or_else(|| anyhow!("eXIf tag 0x{:04x} has unknown field type {}", tag, field_type))?
+                .checked_mul(count as usize)
+                .ok_or_else(|| anyhow!("eXIf tag 0x{:04x} is too long", tag))?;
+            let data = if length <= 4 {
+                value[..length].to_vec()
+            } else {
+                self.slice(self.byte_order.u32(value) as usize, length)?.to_vec()
+            };
+            ifd.entries.push(Entry { tag, field_type, count, data });
+        }
+        let next = self.byte_order.u32(&table[count * 12..]) as usize;
+        Ok((ifd, next))
+    }
+
+    fn thumbnail(&self, ifd1: &Ifd) -> Result<Option<Vec<u8>>> {
+        let long = |tag| ifd1.get(tag).map(|entry| entry.value(self.byte_order).to_f64s());
+        match (long(THUMBNAIL_OFFSET), long(THUMBNAIL_LENGTH)) {
+            (Some(Some(offset)), Some(Some(length))) if offset.len() == 1 && length.len() == 1 => {
+                Ok(Some(self.slice(offset[0] as usize, length[0] as usize)?.to_vec()))
+            },
+            _ => Ok(None),
+        }
+    }
+}
+
+impl TypedChunk for Exif {
+    const TYPE: ChunkType = ChunkType::EXIF;
+    const PLACEMENT: Placement = Placement::BeforeIdat;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Exif::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for Exif {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        let data = value.strip_prefix(APP1_PREFIX).unwrap_or(value);
+        let byte_order = match data.get(..4) {
+            Some(b"II*\0") => ByteOrder::LittleEndian,
+            Some(b"MM\0*") => ByteOrder::BigEndian,
+            _ => return Err(anyhow!("eXIf data does not start with a TIFF header")),
+        };
+        let first = data.get(4..8).ok_or_else(|| anyhow!("eXIf TIFF header has no IFD offset"))?;
+        let mut reader = Reader::new(data, byte_order);
+        let (ifd0, next) = reader.ifd(byte_order.u32(first) as usize, 0)?;
+        let (ifd1, thumbnail) = if next == 0 {
+            (None, None)
+        } else {
+            let (ifd1, _) = reader.ifd(next, 0)?;
+            let thumbnail = reader.thumbnail(&ifd1)?;
+            (Some(ifd1), thumbnail)
+        };
+        Ok(Exif { byte_order, ifd0, ifd1, thumbnail })
+    }
+}
+
+impl TryFrom<&Chunk> for Exif {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::EXIF {
+            return Err(anyhow!("expected eXIf chunk, got {}", chunk.chunk_type()));
+        }
+        Exif::try_from(chunk.data())
+    }
+}
+
+impl Display for Exif {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let tags = self.ifd0.len() + self.ifd1.as_ref().map_or(0, Ifd::len);
+        let order = match self.byte_order {
+            ByteOrder::LittleEndian => "little-endian",
+            ByteOrder::BigEndian => "big-endian",
+        };
+        write!(f, "{} tags, {}", tags, order)?;
+        if self.ifd(Group::Gps).is_some() {
+            write!(f, ", with GPS")?;
+        }
+        if let Some(thumbnail) = &self.thumbnail {
+            write!(f, ", {} byte thumbnail", thumbnail.len())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/// Scale of the fixed-point values in gAMA and cHRM.
+pub(crate) const FIXED_POINT_SCALE: f64 = 100_000.0;
+
+pub(crate) fn from_fixed_point(value: u32) -> f64 {
+    value as f64 / FIXED_POINT_SCALE
+}
+
+pub(crate) fn to_fixed_point(value: f64) -> Result<u32> {
+    let scaled = (value * FIXED_POINT_SCALE).round();
+    if !(0.0..=u32::MAX as f64).contains(&scaled) {
+        return Err(anyhow!("{} cannot be stored as a png fixed-point value", value));
+    }
+    Ok(scaled as u32)
+}
+
+/*
+gAMA layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.gAMA
+the image gamma times 100000, as a 4-byte unsigned integer
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gamma {
+    pub gamma: f64,
+}
+
+impl Gamma {
+    pub const LENGTH: usize = 4;
+
+    pub fn new(gamma: f64) -> Result<Self> {
+        let gamma = Gamma { gamma };
+        gamma.validate()?;
+        Ok(gamma)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if to_fixed_point(self.gamma)? == 0 {
+            return Err(anyhow!("gamma must not be zero"));
+        }
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        Ok(to_fixed_point(self.gamma)?.to_be_bytes().to_vec())
+    }
+
+    pub fn to_chunk(&self) -> Result<Chunk> {
+        Ok(Chunk::new(ChunkType::GAMA, self.as_bytes()?))
+    }
+}
+
+impl TypedChunk for Gamma {
+    const TYPE: ChunkType = ChunkType::GAMA;
+    const PLACEMENT: Placement = Placement::BeforePalette;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Gamma::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.as_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for Gamma {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() != Gamma::LENGTH {
+            return Err(anyhow!("gAMA must be {} bytes, got {}", Gamma::LENGTH, value.len()));
+        }
+        Gamma::new(from_fixed_point(u32::from_be_bytes([value[0], value[1], value[2], value[3]])))
+    }
+}
+
+impl TryFrom<&Chunk> for Gamma {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::GAMA {
+            return Err(anyhow!("expected gAMA chunk, got {}", chunk.chunk_type()));
+        }
+        Gamma::try_from(chunk.data())
+    }
+}
+
+impl Display for Gamma {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "gamma {:.5}", self.gamma)
+    }
+}
@@ -0,0 +1,82 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::Palette;
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/*
+hIST layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.hIST
+the approximate usage frequency of each palette entry, as 2-byte unsigned
+integers, one per PLTE entry
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Histogram {
+    pub frequencies: Vec<u16>,
+}
+
+impl Histogram {
+    /// Checks that there is one frequency per entry of `palette`.
+    pub fn validate_for(&self, palette: &Palette) -> Result<()> {
+        if self.frequencies.len() != palette.len() {
+            return Err(anyhow!("hIST has {} entries, but the palette has {}", self.frequencies.len(), palette.len()));
+        }
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        self.frequencies.iter().flat_map(|frequency| frequency.to_be_bytes()).collect()
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::HIST, self.as_bytes())
+    }
+}
+
+impl TypedChunk for Histogram {
+    const TYPE: ChunkType = ChunkType::HIST;
+    const PLACEMENT: Placement = Placement::AfterPalette;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Histogram::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for Histogram {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if !value.len().is_multiple_of(2) || value.len() > 2 * Palette::MAX_ENTRIES {
+            return Err(anyhow!("hIST length {} is not a valid number of 2-byte entries", value.len()));
+        }
+        Ok(Histogram {
+            frequencies: value.chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect(),
+        })
+    }
+}
+
+impl TryFrom<&Chunk> for Histogram {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::HIST {
+            return Err(anyhow!("expected hIST chunk, got {}", chunk.chunk_type()));
+        }
+        Histogram::try_from(chunk.data())
+    }
+}
+
+impl Display for Histogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} entries", self.frequencies.len())
+    }
+}
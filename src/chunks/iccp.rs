@@ -0,0 +1,99 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use miniz_oxide::inflate::decompress_to_vec_zlib;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::text::{latin1_decode, latin1_encode, validate_keyword};
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/*
+iCCP layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.iCCP
+profile name (Latin-1, 1-79 bytes), null separator, compression method
+(always 0, zlib), then the compressed ICC profile
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IccProfile {
+    pub name: String,
+    /// The uncompressed profile.
+    pub profile: Vec<u8>,
+}
+
+impl IccProfile {
+    pub fn new(name: &str, profile: Vec<u8>) -> Result<Self> {
+        let icc = IccProfile {
+            name: name.to_string(),
+            profile,
+        };
+        icc.validate()?;
+        Ok(icc)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        validate_keyword(&self.name)
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        let mut bytes = latin1_encode(&self.name)?;
+        bytes.extend_from_slice(&[0, 0]);
+        bytes.append(&mut compress_to_vec_zlib(&self.profile, 9));
+        Ok(bytes)
+    }
+
+    pub fn to_chunk(&self) -> Result<Chunk> {
+        Ok(Chunk::new(ChunkType::ICCP, self.as_bytes()?))
+    }
+}
+
+impl TypedChunk for IccProfile {
+    const TYPE: ChunkType = ChunkType::ICCP;
+    const PLACEMENT: Placement = Placement::BeforePalette;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        IccProfile::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.as_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for IccProfile {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        let separator = value.iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| anyhow!("iCCP chunk has no profile name separator"))?;
+        match value.get(separator + 1) {
+            Some(0) => {},
+            Some(method) => return Err(anyhow!("unknown iCCP compression method {}", method)),
+            None => return Err(anyhow!("iCCP chunk has no compression method")),
+        }
+        let profile = decompress_to_vec_zlib(&value[separator + 2..])
+            .map_err(|e| anyhow!("failed to inflate ICC profile: {:?}", e.status))?;
+        IccProfile::new(&latin1_decode(&value[..separator]), profile)
+    }
+}
+
+impl TryFrom<&Chunk> for IccProfile {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::ICCP {
+            return Err(anyhow!("expected iCCP chunk, got {}", chunk.chunk_type()));
+        }
+        IccProfile::try_from(chunk.data())
+    }
+}
+
+impl Display for IccProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} bytes)", self.name, self.profile.len())
+    }
+}
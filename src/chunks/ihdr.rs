@@ -0,0 +1,186 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+
+/// Color type field of the image header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ColorType {
+    Grayscale = 0,
+    Rgb = 2,
+    Indexed = 3,
+    GrayscaleAlpha = 4,
+    Rgba = 6,
+}
+
+impl ColorType {
+    /// Number of samples stored per pixel.
+    pub fn channels(&self) -> usize {
+        match self {
+            ColorType::Grayscale | ColorType::Indexed => 1,
+            ColorType::GrayscaleAlpha => 2,
+            ColorType::Rgb => 3,
+            ColorType::Rgba => 4,
+        }
+    }
+
+    /// Bit depths the spec allows for this color type.
+    pub fn allowed_bit_depths(&self) -> &'static [u8] {
+        match self {
+            ColorType::Grayscale => &[1, 2, 4, 8, 16],
+            ColorType::Indexed => &[1, 2, 4, 8],
+            ColorType::Rgb | ColorType::GrayscaleAlpha | ColorType::Rgba => &[8, 16],
+        }
+    }
+}
+
+impl TryFrom<u8> for ColorType {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(ColorType::Grayscale),
+            2 => Ok(ColorType::Rgb),
+            3 => Ok(ColorType::Indexed),
+            4 => Ok(ColorType::GrayscaleAlpha),
+            6 => Ok(ColorType::Rgba),
+            _ => Err(anyhow!("invalid color type {}", value)),
+        }
+    }
+}
+
+/*
+IHDR layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.IHDR
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ihdr {
+    pub width: u32,
+    pub height: u32,
+    pub bit_depth: u8,
+    pub color_type: ColorType,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub compression_method: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub filter_method: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub interlace_method: u8,
+}
+
+impl Ihdr {
+    pub const LENGTH: usize = 13;
+
+    /// Creates a non-interlaced header, checking the fields against the spec.
+    pub fn new(width: u32, height: u32, bit_depth: u8, color_type: ColorType) -> Result<Self> {
+        let ihdr = Ihdr {
+            width,
+            height,
+            bit_depth,
+            color_type,
+            compression_method: 0,
+            filter_method: 0,
+            interlace_method: 0,
+        };
+        ihdr.validate()?;
+        Ok(ihdr)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.width == 0 || self.width > i32::MAX as u32 {
+            return Err(anyhow!("invalid width {}", self.width));
+        }
+        if self.height == 0 || self.height > i32::MAX as u32 {
+            return Err(anyhow!("invalid height {}", self.height));
+        }
+        if !self.color_type.allowed_bit_depths().contains(&self.bit_depth) {
+            return Err(anyhow!("bit depth {} is not allowed for color type {:?}", self.bit_depth, self.color_type));
+        }
+        if self.compression_method != 0 {
+            return Err(anyhow!("unknown compression method {}", self.compression_method));
+        }
+        if self.filter_method != 0 {
+            return Err(anyhow!("unknown filter method {}", self.filter_method));
+        }
+        if self.interlace_method > 1 {
+            return Err(anyhow!("unknown interlace method {}", self.interlace_method));
+        }
+        Ok(())
+    }
+
+    pub fn bits_per_pixel(&self) -> usize {
+        self.color_type.channels() * self.bit_depth as usize
+    }
+
+    /// Bytes in one unfiltered scanline of `width` pixels.
+    pub fn row_bytes(&self, width: u32) -> usize {
+        (width as usize * self.bits_per_pixel()).div_ceil(8)
+    }
+
+    pub fn is_interlaced(&self) -> bool {
+        self.interlace_method == 1
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Ihdr::LENGTH);
+        bytes.extend_from_slice(&self.width.to_be_bytes());
+        bytes.extend_from_slice(&self.height.to_be_bytes());
+        bytes.push(self.bit_depth);
+        bytes.push(self.color_type as u8);
+        bytes.push(self.compression_method);
+        bytes.push(self.filter_method);
+        bytes.push(self.interlace_method);
+        bytes
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::IHDR, self.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for Ihdr {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() != Ihdr::LENGTH {
+            return Err(anyhow!("IHDR must be {} bytes, got {}", Ihdr::LENGTH, value.len()));
+        }
+        let ihdr = Ihdr {
+            width: u32::from_be_bytes([value[0], value[1], value[2], value[3]]),
+            height: u32::from_be_bytes([value[4], value[5], value[6], value[7]]),
+            bit_depth: value[8],
+            color_type: ColorType::try_from(value[9])?,
+            compression_method: value[10],
+            filter_method: value[11],
+            interlace_method: value[12],
+        };
+        ihdr.validate()?;
+        Ok(ihdr)
+    }
+}
+
+impl TryFrom<&Chunk> for Ihdr {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::IHDR {
+            return Err(anyhow!("expected IHDR chunk, got {}", chunk.chunk_type()));
+        }
+        Ihdr::try_from(chunk.data())
+    }
+}
+
+impl Display for Ihdr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}, {}-bit {:?}", self.width, self.height, self.bit_depth, self.color_type)?;
+        if self.interlace_method == 1 {
+            write!(f, ", interlaced")?;
+        }
+        Ok(())
+    }
+}
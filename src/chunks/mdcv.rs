@@ -0,0 +1,143 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::Chromaticity;
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/// mDCv chromaticities are stored in units of 0.00002.
+const CHROMATICITY_SCALE: f64 = 50_000.0;
+/// mDCv and cLLI luminances are stored in units of 0.0001 cd/m².
+pub(crate) const LUMINANCE_SCALE: f64 = 10_000.0;
+
+fn to_chromaticity_units(value: f64) -> Result<u16> {
+    let scaled = (value * CHROMATICITY_SCALE).round();
+    if !(0.0..=u16::MAX as f64).contains(&scaled) {
+        return Err(anyhow!("chromaticity {} is out of range for mDCv", value));
+    }
+    Ok(scaled as u16)
+}
+
+pub(crate) fn to_luminance_units(value: f64) -> Result<u32> {
+    let scaled = (value * LUMINANCE_SCALE).round();
+    if !(0.0..=u32::MAX as f64).contains(&scaled) {
+        return Err(anyhow!("luminance {} cd/m² is out of range", value));
+    }
+    Ok(scaled as u32)
+}
+
+/*
+mDCv layout
+https://www.w3.org/TR/png-3/#mDCv-chunk
+x and y of the red, green and blue primaries and of the white point of the
+mastering display, each in units of 0.00002 as a 2-byte unsigned integer,
+then its maximum and minimum luminance in units of 0.0001 cd/m² as 4-byte
+unsigned integers
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MasteringDisplay {
+    pub red: Chromaticity,
+    pub green: Chromaticity,
+    pub blue: Chromaticity,
+    pub white: Chromaticity,
+    /// In cd/m².
+    pub max_luminance: f64,
+    /// In cd/m².
+    pub min_luminance: f64,
+}
+
+impl MasteringDisplay {
+    pub const LENGTH: usize = 24;
+
+    fn points(&self) -> [Chromaticity; 4] {
+        [self.red, self.green, self.blue, self.white]
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for point in self.points().iter() {
+            to_chromaticity_units(point.x)?;
+            to_chromaticity_units(point.y)?;
+        }
+        if to_luminance_units(self.min_luminance)? > to_luminance_units(self.max_luminance)? {
+            return Err(anyhow!("mDCv minimum luminance is above the maximum"));
+        }
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        let mut bytes = Vec::with_capacity(MasteringDisplay::LENGTH);
+        for point in self.points().iter() {
+            bytes.extend_from_slice(&to_chromaticity_units(point.x)?.to_be_bytes());
+            bytes.extend_from_slice(&to_chromaticity_units(point.y)?.to_be_bytes());
+        }
+        for luminance in [self.max_luminance, self.min_luminance] {
+            bytes.extend_from_slice(&to_luminance_units(luminance)?.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+
+    pub fn to_chunk(&self) -> Result<Chunk> {
+        Ok(Chunk::new(ChunkType::MDCV, self.as_bytes()?))
+    }
+}
+
+impl TypedChunk for MasteringDisplay {
+    const TYPE: ChunkType = ChunkType::MDCV;
+    const PLACEMENT: Placement = Placement::BeforePalette;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        MasteringDisplay::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.as_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for MasteringDisplay {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() != MasteringDisplay::LENGTH {
+            return Err(anyhow!("mDCv must be {} bytes, got {}", MasteringDisplay::LENGTH, value.len()));
+        }
+        let field = |i: usize| u16::from_be_bytes([value[i], value[i + 1]]) as f64 / CHROMATICITY_SCALE;
+        let point = |i: usize| Chromaticity { x: field(i), y: field(i + 2) };
+        let luminance = |i: usize| {
+            u32::from_be_bytes([value[i], value[i + 1], value[i + 2], value[i + 3]]) as f64 / LUMINANCE_SCALE
+        };
+        let display = MasteringDisplay {
+            red: point(0),
+            green: point(4),
+            blue: point(8),
+            white: point(12),
+            max_luminance: luminance(16),
+            min_luminance: luminance(20),
+        };
+        display.validate()?;
+        Ok(display)
+    }
+}
+
+impl TryFrom<&Chunk> for MasteringDisplay {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::MDCV {
+            return Err(anyhow!("expected mDCv chunk, got {}", chunk.chunk_type()));
+        }
+        MasteringDisplay::try_from(chunk.data())
+    }
+}
+
+impl Display for MasteringDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "red {}, green {}, blue {}, white {}, luminance {}-{} cd/m²",
+               self.red, self.green, self.blue, self.white, self.min_luminance, self.max_luminance)
+    }
+}
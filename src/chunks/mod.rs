@@ -0,0 +1,82 @@
+//! Typed views over the contents of well-known chunks.
+
+pub mod bkgd;
+pub mod chrm;
+pub mod cicp;
+pub mod clli;
+pub mod exif;
+pub mod gama;
+pub mod hist;
+pub mod iccp;
+pub mod ihdr;
+pub mod mdcv;
+pub mod phys;
+pub mod plte;
+pub mod sbit;
+pub mod splt;
+pub mod srgb;
+pub mod text;
+pub mod time;
+pub mod trns;
+pub mod typed;
+
+pub use bkgd::Background;
+pub use chrm::{Chromaticities, Chromaticity};
+pub use cicp::Cicp;
+pub use clli::ContentLightLevel;
+pub use exif::Exif;
+pub use gama::Gamma;
+pub use hist::Histogram;
+pub use iccp::IccProfile;
+pub use ihdr::{ColorType, Ihdr};
+pub use mdcv::MasteringDisplay;
+pub use phys::PhysicalDimensions;
+pub use plte::Palette;
+pub use sbit::SignificantBits;
+pub use splt::{SuggestedColor, SuggestedPalette};
+pub use srgb::{RenderingIntent, Srgb};
+pub use text::Text;
+pub use time::Time;
+pub use trns::Transparency;
+pub use typed::{Placement, TypedChunk};
+
+use std::convert::TryFrom;
+
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::messages::Message;
+
+/// One-line description of a chunk's decoded contents, for the chunk types
+/// this module knows about.
+pub fn describe(chunk: &Chunk) -> Option<String> {
+    describe_data(&chunk.chunk_type(), chunk.data(), None)
+}
+
+/// Like `describe`, for raw chunk data. Chunks whose layout depends on the
+/// color type, such as tRNS or bKGD, are only described when the image
+/// header `ihdr` is given.
+pub fn describe_data(chunk_type: &ChunkType, data: &[u8], ihdr: Option<&Ihdr>) -> Option<String> {
+    let description = match *chunk_type {
+        ChunkType::IHDR => Ihdr::try_from(data).map(|ihdr| ihdr.to_string()),
+        ChunkType::BKGD => Background::from_bytes(data, ihdr?.color_type).map(|bkgd| bkgd.to_string()),
+        ChunkType::CHRM => Chromaticities::try_from(data).map(|chrm| chrm.to_string()),
+        ChunkType::CICP => Cicp::try_from(data).map(|cicp| cicp.to_string()),
+        ChunkType::CLLI => ContentLightLevel::try_from(data).map(|clli| clli.to_string()),
+        ChunkType::EXIF => Exif::try_from(data).map(|exif| exif.to_string()),
+        ChunkType::GAMA => Gamma::try_from(data).map(|gamma| gamma.to_string()),
+        ChunkType::HIST => Histogram::try_from(data).map(|hist| hist.to_string()),
+        ChunkType::ICCP => IccProfile::try_from(data).map(|icc| icc.to_string()),
+        ChunkType::MDCV => MasteringDisplay::try_from(data).map(|mdcv| mdcv.to_string()),
+        ChunkType::PHYS => PhysicalDimensions::try_from(data).map(|phys| phys.to_string()),
+        ChunkType::PLTE => Palette::try_from(data).map(|palette| palette.to_string()),
+        ChunkType::SBIT => SignificantBits::from_bytes(data, ihdr?.color_type).map(|sbit| sbit.to_string()),
+        ChunkType::SPLT => SuggestedPalette::try_from(data).map(|splt| splt.to_string()),
+        ChunkType::SRGB => Srgb::try_from(data).map(|srgb| srgb.to_string()),
+        ChunkType::TEXT => Text::try_from(data).map(|text| text.to_string()),
+        ChunkType::TIME => Time::try_from(data).map(|time| time.to_string()),
+        ChunkType::TRNS => Transparency::from_bytes(data, ihdr?.color_type).map(|trns| trns.to_string()),
+        ChunkType::MESSAGE => Message::try_from(data).map(|message| message.to_string()),
+        _ => return None,
+    };
+    Some(description.unwrap_or_else(|e| format!("invalid: {}", e)))
+}
@@ -0,0 +1,129 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::typed::{Placement, TypedChunk};
+
+const METERS_PER_INCH: f64 = 0.0254;
+
+/// Unit of the pHYs pixel densities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Unit {
+    /// Only the aspect ratio is known.
+    Unknown = 0,
+    Meter = 1,
+}
+
+impl TryFrom<u8> for Unit {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Unit::Unknown),
+            1 => Ok(Unit::Meter),
+            _ => Err(anyhow!("invalid pHYs unit {}", value)),
+        }
+    }
+}
+
+/*
+pHYs layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.pHYs
+pixels per unit on the x and y axes as 4-byte unsigned integers, then the unit
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhysicalDimensions {
+    pub x: u32,
+    pub y: u32,
+    pub unit: Unit,
+}
+
+impl PhysicalDimensions {
+    pub const LENGTH: usize = 9;
+
+    /// Densities given in dots per inch, stored as pixels per meter.
+    pub fn from_dpi(x: f64, y: f64) -> Result<Self> {
+        let per_meter = |dpi: f64| {
+            let ppm = (dpi / METERS_PER_INCH).round();
+            if !(1.0..=u32::MAX as f64).contains(&ppm) {
+                return Err(anyhow!("{} dpi cannot be stored in pHYs", dpi));
+            }
+            Ok(ppm as u32)
+        };
+        Ok(PhysicalDimensions { x: per_meter(x)?, y: per_meter(y)?, unit: Unit::Meter })
+    }
+
+    /// The densities in dots per inch, if the unit is known.
+    pub fn dpi(&self) -> Option<(f64, f64)> {
+        match self.unit {
+            Unit::Meter => Some((self.x as f64 * METERS_PER_INCH, self.y as f64 * METERS_PER_INCH)),
+            Unit::Unknown => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(PhysicalDimensions::LENGTH);
+        bytes.extend_from_slice(&self.x.to_be_bytes());
+        bytes.extend_from_slice(&self.y.to_be_bytes());
+        bytes.push(self.unit as u8);
+        bytes
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::PHYS, self.as_bytes())
+    }
+}
+
+impl TypedChunk for PhysicalDimensions {
+    const TYPE: ChunkType = ChunkType::PHYS;
+    const PLACEMENT: Placement = Placement::BeforeIdat;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        PhysicalDimensions::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for PhysicalDimensions {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() != PhysicalDimensions::LENGTH {
+            return Err(anyhow!("pHYs must be {} bytes, got {}", PhysicalDimensions::LENGTH, value.len()));
+        }
+        Ok(PhysicalDimensions {
+            x: u32::from_be_bytes([value[0], value[1], value[2], value[3]]),
+            y: u32::from_be_bytes([value[4], value[5], value[6], value[7]]),
+            unit: Unit::try_from(value[8])?,
+        })
+    }
+}
+
+impl TryFrom<&Chunk> for PhysicalDimensions {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::PHYS {
+            return Err(anyhow!("expected pHYs chunk, got {}", chunk.chunk_type()));
+        }
+        PhysicalDimensions::try_from(chunk.data())
+    }
+}
+
+impl Display for PhysicalDimensions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.dpi() {
+            Some((x, y)) => write!(f, "{}x{} pixels per meter ({:.0}x{:.0} dpi)", self.x, self.y, x, y),
+            None => write!(f, "{}:{} aspect ratio", self.x, self.y),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{ColorType, Ihdr};
+
+/*
+PLTE layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.PLTE
+1 to 256 entries of three bytes each: red, green, blue
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Palette {
+    pub entries: Vec<[u8; 3]>,
+}
+
+impl Palette {
+    pub const MAX_ENTRIES: usize = 256;
+
+    pub fn new(entries: Vec<[u8; 3]>) -> Result<Self> {
+        let palette = Palette { entries };
+        palette.validate()?;
+        Ok(palette)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.entries.is_empty() || self.entries.len() > Palette::MAX_ENTRIES {
+            return Err(anyhow!("palette must have 1 to {} entries, got {}", Palette::MAX_ENTRIES, self.entries.len()));
+        }
+        Ok(())
+    }
+
+    /// Checks that `ihdr` allows a palette of this size.
+    pub fn validate_for(&self, ihdr: &Ihdr) -> Result<()> {
+        self.validate()?;
+        match ihdr.color_type {
+            ColorType::Grayscale | ColorType::GrayscaleAlpha => {
+                Err(anyhow!("PLTE is not allowed for color type {:?}", ihdr.color_type))
+            }
+            ColorType::Indexed if self.len() > 1 << ihdr.bit_depth => {
+                Err(anyhow!("palette has {} entries, but {}-bit indices allow only {}",
+                            self.len(), ihdr.bit_depth, 1 << ihdr.bit_depth))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        self.entries.iter().flatten().copied().collect()
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::PLTE, self.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for Palette {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if !value.len().is_multiple_of(3) {
+            return Err(anyhow!("PLTE length {} is not a multiple of 3", value.len()));
+        }
+        Palette::new(value.chunks(3).map(|rgb| [rgb[0], rgb[1], rgb[2]]).collect())
+    }
+}
+
+impl TryFrom<&Chunk> for Palette {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::PLTE {
+            return Err(anyhow!("expected PLTE chunk, got {}", chunk.chunk_type()));
+        }
+        Palette::try_from(chunk.data())
+    }
+}
+
+impl Display for Palette {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} entries", self.len())
+    }
+}
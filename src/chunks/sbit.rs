@@ -0,0 +1,100 @@
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::Result;
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{ColorType, Ihdr};
+
+/*
+sBIT layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.sBIT
+the number of significant bits of each channel of the original image, one
+byte per channel; indexed images give the bits of the palette's red, green
+and blue
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SignificantBits {
+    Gray(u8),
+    /// Also used by indexed images.
+    Rgb { red: u8, green: u8, blue: u8 },
+    GrayAlpha { gray: u8, alpha: u8 },
+    Rgba { red: u8, green: u8, blue: u8, alpha: u8 },
+}
+
+impl SignificantBits {
+    /// Parses sBIT data, whose layout is given by the image's color type.
+    pub fn from_bytes(value: &[u8], color_type: ColorType) -> Result<Self> {
+        let expected = match color_type {
+            ColorType::Grayscale => 1,
+            ColorType::GrayscaleAlpha => 2,
+            ColorType::Rgb | ColorType::Indexed => 3,
+            ColorType::Rgba => 4,
+        };
+        if value.len() != expected {
+            return Err(anyhow!("sBIT must be {} bytes for color type {:?}, got {}", expected, color_type, value.len()));
+        }
+        Ok(match color_type {
+            ColorType::Grayscale => SignificantBits::Gray(value[0]),
+            ColorType::GrayscaleAlpha => SignificantBits::GrayAlpha { gray: value[0], alpha: value[1] },
+            ColorType::Rgb | ColorType::Indexed => SignificantBits::Rgb { red: value[0], green: value[1], blue: value[2] },
+            ColorType::Rgba => SignificantBits::Rgba { red: value[0], green: value[1], blue: value[2], alpha: value[3] },
+        })
+    }
+
+    /// Parses an sBIT chunk belonging to an image with header `ihdr`.
+    pub fn from_chunk(chunk: &Chunk, ihdr: &Ihdr) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::SBIT {
+            return Err(anyhow!("expected sBIT chunk, got {}", chunk.chunk_type()));
+        }
+        let bits = SignificantBits::from_bytes(chunk.data(), ihdr.color_type)?;
+        bits.validate_for(ihdr)?;
+        Ok(bits)
+    }
+
+    /// Checks that the layout matches the color type of `ihdr` and that no
+    /// channel claims more bits than its samples have.
+    pub fn validate_for(&self, ihdr: &Ihdr) -> Result<()> {
+        let matches = matches!((self, ihdr.color_type),
+            (SignificantBits::Gray(_), ColorType::Grayscale)
+            | (SignificantBits::Rgb { .. }, ColorType::Rgb | ColorType::Indexed)
+            | (SignificantBits::GrayAlpha { .. }, ColorType::GrayscaleAlpha)
+            | (SignificantBits::Rgba { .. }, ColorType::Rgba));
+        if !matches {
+            return Err(anyhow!("sBIT does not match color type {:?}", ihdr.color_type));
+        }
+        let depth = if ihdr.color_type == ColorType::Indexed { 8 } else { ihdr.bit_depth };
+        if self.as_bytes().iter().any(|&bits| bits == 0 || bits > depth) {
+            return Err(anyhow!("sBIT values must be 1 to {}", depth));
+        }
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        match *self {
+            SignificantBits::Gray(gray) => vec![gray],
+            SignificantBits::Rgb { red, green, blue } => vec![red, green, blue],
+            SignificantBits::GrayAlpha { gray, alpha } => vec![gray, alpha],
+            SignificantBits::Rgba { red, green, blue, alpha } => vec![red, green, blue, alpha],
+        }
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::SBIT, self.as_bytes())
+    }
+}
+
+impl Display for SignificantBits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            SignificantBits::Gray(gray) => write!(f, "gray {} bits", gray),
+            SignificantBits::Rgb { red, green, blue } => write!(f, "rgb {}/{}/{} bits", red, green, blue),
+            SignificantBits::GrayAlpha { gray, alpha } => write!(f, "gray {} bits, alpha {} bits", gray, alpha),
+            SignificantBits::Rgba { red, green, blue, alpha } => {
+                write!(f, "rgba {}/{}/{}/{} bits", red, green, blue, alpha)
+            },
+        }
+    }
+}
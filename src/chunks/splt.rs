@@ -0,0 +1,148 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::text::{latin1_decode, latin1_encode, validate_keyword};
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/// One color of a suggested palette. With a sample depth of 8 only the low
+/// byte of each sample is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuggestedColor {
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+    pub alpha: u16,
+    pub frequency: u16,
+}
+
+/*
+sPLT layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.sPLT
+palette name (Latin-1, 1-79 bytes), null separator, sample depth (8 or 16),
+then entries of red, green, blue and alpha samples of that depth followed by
+a 2-byte frequency
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SuggestedPalette {
+    pub name: String,
+    pub sample_depth: u8,
+    pub entries: Vec<SuggestedColor>,
+}
+
+impl SuggestedPalette {
+    pub fn validate(&self) -> Result<()> {
+        validate_keyword(&self.name)?;
+        match self.sample_depth {
+            8 => {
+                let samples = |entry: &SuggestedColor| [entry.red, entry.green, entry.blue, entry.alpha];
+                if self.entries.iter().flat_map(samples).any(|sample| sample > 255) {
+                    return Err(anyhow!("sPLT samples must fit in 8 bits"));
+                }
+                Ok(())
+            },
+            16 => Ok(()),
+            depth => Err(anyhow!("invalid sPLT sample depth {}", depth)),
+        }
+    }
+
+    fn entry_length(&self) -> usize {
+        4 * (self.sample_depth as usize / 8) + 2
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        let mut bytes = latin1_encode(&self.name)?;
+        bytes.push(0);
+        bytes.push(self.sample_depth);
+        for entry in self.entries.iter() {
+            for sample in [entry.red, entry.green, entry.blue, entry.alpha] {
+                if self.sample_depth == 8 {
+                    bytes.push(sample as u8);
+                } else {
+                    bytes.extend_from_slice(&sample.to_be_bytes());
+                }
+            }
+            bytes.extend_from_slice(&entry.frequency.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+
+    pub fn to_chunk(&self) -> Result<Chunk> {
+        Ok(Chunk::new(ChunkType::SPLT, self.as_bytes()?))
+    }
+}
+
+impl TypedChunk for SuggestedPalette {
+    const TYPE: ChunkType = ChunkType::SPLT;
+    const PLACEMENT: Placement = Placement::BeforeIdat;
+    const MULTIPLE: bool = true;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        SuggestedPalette::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.as_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for SuggestedPalette {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        let separator = value.iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| anyhow!("sPLT chunk has no palette name separator"))?;
+        let sample_depth = *value.get(separator + 1).ok_or_else(|| anyhow!("sPLT chunk has no sample depth"))?;
+        let mut palette = SuggestedPalette {
+            name: latin1_decode(&value[..separator]),
+            sample_depth,
+            entries: Vec::new(),
+        };
+        palette.validate()?;
+
+        let data = &value[separator + 2..];
+        let entry_length = palette.entry_length();
+        if !data.len().is_multiple_of(entry_length) {
+            return Err(anyhow!("sPLT data is not a whole number of {}-byte entries", entry_length));
+        }
+        let width = sample_depth as usize / 8;
+        let sample = |entry: &[u8], i: usize| match width {
+            1 => entry[i] as u16,
+            _ => u16::from_be_bytes([entry[2 * i], entry[2 * i + 1]]),
+        };
+        palette.entries = data.chunks(entry_length)
+            .map(|entry| SuggestedColor {
+                red: sample(entry, 0),
+                green: sample(entry, 1),
+                blue: sample(entry, 2),
+                alpha: sample(entry, 3),
+                frequency: u16::from_be_bytes([entry[entry_length - 2], entry[entry_length - 1]]),
+            })
+            .collect();
+        Ok(palette)
+    }
+}
+
+impl TryFrom<&Chunk> for SuggestedPalette {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::SPLT {
+            return Err(anyhow!("expected sPLT chunk, got {}", chunk.chunk_type()));
+        }
+        SuggestedPalette::try_from(chunk.data())
+    }
+}
+
+impl Display for SuggestedPalette {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} entries, {}-bit", self.name, self.entries.len(), self.sample_depth)
+    }
+}
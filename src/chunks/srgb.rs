@@ -0,0 +1,101 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::typed::{Placement, TypedChunk};
+
+/// Rendering intent of the sRGB chunk, as defined by the ICC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RenderingIntent {
+    Perceptual = 0,
+    RelativeColorimetric = 1,
+    Saturation = 2,
+    AbsoluteColorimetric = 3,
+}
+
+impl TryFrom<u8> for RenderingIntent {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(RenderingIntent::Perceptual),
+            1 => Ok(RenderingIntent::RelativeColorimetric),
+            2 => Ok(RenderingIntent::Saturation),
+            3 => Ok(RenderingIntent::AbsoluteColorimetric),
+            _ => Err(anyhow!("invalid rendering intent {}", value)),
+        }
+    }
+}
+
+/*
+sRGB layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.sRGB
+a single byte holding the rendering intent
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Srgb {
+    pub rendering_intent: RenderingIntent,
+}
+
+impl Srgb {
+    pub const LENGTH: usize = 1;
+
+    pub fn new(rendering_intent: RenderingIntent) -> Self {
+        Srgb { rendering_intent }
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        vec![self.rendering_intent as u8]
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::SRGB, self.as_bytes())
+    }
+}
+
+impl TypedChunk for Srgb {
+    const TYPE: ChunkType = ChunkType::SRGB;
+    const PLACEMENT: Placement = Placement::BeforePalette;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Srgb::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(self.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for Srgb {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() != Srgb::LENGTH {
+            return Err(anyhow!("sRGB must be {} byte, got {}", Srgb::LENGTH, value.len()));
+        }
+        Ok(Srgb::new(RenderingIntent::try_from(value[0])?))
+    }
+}
+
+impl TryFrom<&Chunk> for Srgb {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::SRGB {
+            return Err(anyhow!("expected sRGB chunk, got {}", chunk.chunk_type()));
+        }
+        Srgb::try_from(chunk.data())
+    }
+}
+
+impl Display for Srgb {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} rendering intent", self.rendering_intent)
+    }
+}
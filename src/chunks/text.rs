@@ -0,0 +1,123 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::typed::TypedChunk;
+
+/*
+tEXt layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.tEXt
+keyword and text are Latin-1, separated by a single null byte
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Text {
+    pub keyword: String,
+    pub text: String,
+}
+
+impl Text {
+    pub fn new(keyword: &str, text: &str) -> Result<Self> {
+        let text = Text {
+            keyword: keyword.to_string(),
+            text: text.to_string(),
+        };
+        text.validate()?;
+        Ok(text)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        validate_keyword(&self.keyword)?;
+        if self.text.contains('\0') {
+            return Err(anyhow!("tEXt text must not contain null bytes"));
+        }
+        latin1_encode(&self.text)?;
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        let mut bytes = latin1_encode(&self.keyword)?;
+        bytes.push(0);
+        bytes.append(&mut latin1_encode(&self.text)?);
+        Ok(bytes)
+    }
+
+    pub fn to_chunk(&self) -> Result<Chunk> {
+        Ok(Chunk::new(ChunkType::TEXT, self.as_bytes()?))
+    }
+}
+
+/// Checks a keyword, as used by the text chunks and as the iCCP profile name.
+pub(crate) fn validate_keyword(keyword: &str) -> Result<()> {
+    let bytes = latin1_encode(keyword)?;
+    if bytes.is_empty() || bytes.len() > 79 {
+        return Err(anyhow!("keyword must be 1 to 79 bytes, got {}", bytes.len()));
+    }
+    if bytes.contains(&0) {
+        return Err(anyhow!("keyword must not contain null bytes"));
+    }
+    if bytes.starts_with(b" ") || bytes.ends_with(b" ") || bytes.windows(2).any(|w| w == b"  ") {
+        return Err(anyhow!("keyword {:?} has leading, trailing or consecutive spaces", keyword));
+    }
+    Ok(())
+}
+
+pub(crate) fn latin1_encode(s: &str) -> Result<Vec<u8>> {
+    s.chars()
+        .map(|ch| u8::try_from(ch as u32).map_err(|_| anyhow!("{:?} is not a Latin-1 character", ch)))
+        .collect()
+}
+
+pub(crate) fn latin1_decode(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+impl TypedChunk for Text {
+    const TYPE: ChunkType = ChunkType::TEXT;
+    const MULTIPLE: bool = true;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Text::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.as_bytes()
+    }
+}
+
+impl TryFrom<&[u8]> for Text {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        let separator = value.iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| anyhow!("tEXt chunk has no keyword separator"))?;
+        let text = Text {
+            keyword: latin1_decode(&value[..separator]),
+            text: latin1_decode(&value[separator + 1..]),
+        };
+        text.validate()?;
+        Ok(text)
+    }
+}
+
+impl TryFrom<&Chunk> for Text {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::TEXT {
+            return Err(anyhow!("expected tEXt chunk, got {}", chunk.chunk_type()));
+        }
+        Text::try_from(chunk.data())
+    }
+}
+
+impl Display for Text {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.keyword, self.text)
+    }
+}
@@ -0,0 +1,226 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::typed::TypedChunk;
+
+/// Converts days since the unix epoch to a (year, month, day) civil date.
+/// See http://howardhinnant.github.io/date_algorithms.html
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }, month, day)
+}
+
+/// The inverse of `civil_from_days`.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let day_of_year = (153 * mp + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/*
+tIME layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.tIME
+the time of the last modification in UTC: 2-byte year, then month, day,
+hour, minute and second as one byte each; second may be 60 for leap seconds
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Time {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl Time {
+    pub const LENGTH: usize = 7;
+
+    pub fn new(year: u16, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Result<Self> {
+        let time = Time { year, month, day, hour, minute, second };
+        time.validate()?;
+        Ok(time)
+    }
+
+    /// The current time, to the second.
+    pub fn now() -> Self {
+        Time::from_system_time(SystemTime::now()).expect("the current year fits in a u16")
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=12).contains(&self.month) {
+            return Err(anyhow!("invalid month {}", self.month));
+        }
+        if self.day == 0 || self.day > days_in_month(self.year, self.month) {
+            return Err(anyhow!("invalid day {} for {:04}-{:02}", self.day, self.year, self.month));
+        }
+        if self.hour > 23 || self.minute > 59 || self.second > 60 {
+            return Err(anyhow!("invalid time of day {:02}:{:02}:{:02}", self.hour, self.minute, self.second));
+        }
+        Ok(())
+    }
+
+    /// Seconds since the unix epoch, negative before it. A leap second
+    /// counts as the first second of the next minute.
+    pub fn unix_seconds(&self) -> i64 {
+        days_from_civil(self.year as i64, self.month as u32, self.day as u32) * 86400
+            + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64
+    }
+
+    pub fn from_unix_seconds(seconds: i64) -> Result<Self> {
+        let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+        let year = u16::try_from(year).map_err(|_| anyhow!("year {} cannot be stored in tIME", year))?;
+        let time = seconds.rem_euclid(86400);
+        Time::new(year, month as u8, day as u8, (time / 3600) as u8, (time / 60 % 60) as u8, (time % 60) as u8)
+    }
+
+    pub fn from_system_time(time: SystemTime) -> Result<Self> {
+        let seconds = match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs_f64().ceil() as i64),
+        };
+        Time::from_unix_seconds(seconds)
+    }
+
+    pub fn to_system_time(&self) -> SystemTime {
+        let seconds = self.unix_seconds();
+        if seconds >= 0 {
+            UNIX_EPOCH + Duration::from_secs(seconds as u64)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+        }
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Time::LENGTH);
+        bytes.extend_from_slice(&self.year.to_be_bytes());
+        bytes.extend_from_slice(&[self.month, self.day, self.hour, self.minute, self.second]);
+        bytes
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::TIME, self.as_bytes())
+    }
+}
+
+impl TypedChunk for Time {
+    const TYPE: ChunkType = ChunkType::TIME;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Time::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        Ok(self.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for Time {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        if value.len() != Time::LENGTH {
+            return Err(anyhow!("tIME must be {} bytes, got {}", Time::LENGTH, value.len()));
+        }
+        Time::new(u16::from_be_bytes([value[0], value[1]]), value[2], value[3], value[4], value[5], value[6])
+    }
+}
+
+impl TryFrom<&Chunk> for Time {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::TIME {
+            return Err(anyhow!("expected tIME chunk, got {}", chunk.chunk_type()));
+        }
+        Time::try_from(chunk.data())
+    }
+}
+
+/// Parses an RFC 3339 timestamp such as `2024-05-01T12:30:00Z` or
+/// `2024-05-01 14:30:00.25+02:00`. Fractions of a second are dropped and
+/// offsets converted to UTC.
+impl FromStr for Time {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow!("invalid RFC 3339 timestamp {:?}", s);
+        let bytes = s.as_bytes();
+        if bytes.len() < 20 || !s.is_ascii() {
+            return Err(invalid());
+        }
+        let number = |from: usize, to: usize| -> Result<u16> {
+            let digits = &s[from..to];
+            if !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            digits.parse().map_err(|_| invalid())
+        };
+        let separators = [(4, b'-'), (7, b'-'), (13, b':'), (16, b':')];
+        if separators.iter().any(|&(i, sep)| bytes[i] != sep) || !b"Tt ".contains(&bytes[10]) {
+            return Err(invalid());
+        }
+        let time = Time::new(number(0, 4)?, number(5, 7)? as u8, number(8, 10)? as u8,
+                             number(11, 13)? as u8, number(14, 16)? as u8, number(17, 19)? as u8)?;
+
+        let mut rest = &s[19..];
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            if digits == 0 {
+                return Err(invalid());
+            }
+            rest = &fraction[digits..];
+        }
+        let offset = match rest.as_bytes() {
+            [b'Z'] | [b'z'] => 0,
+            [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+                let minutes = number(s.len() - 5, s.len() - 3)? as i64 * 60 + number(s.len() - 2, s.len())? as i64;
+                if minutes >= 24 * 60 {
+                    return Err(invalid());
+                }
+                if *sign == b'+' { minutes * 60 } else { -minutes * 60 }
+            },
+            _ => return Err(invalid()),
+        };
+        if offset == 0 {
+            // keeps a leap second as is
+            return Ok(time);
+        }
+        Time::from_unix_seconds(time.unix_seconds() - offset)
+    }
+}
+
+impl Display for Time {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+               self.year, self.month, self.day, self.hour, self.minute, self.second)
+    }
+}
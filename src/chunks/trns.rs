@@ -0,0 +1,109 @@
+use std::fmt::{self, Display, Formatter};
+use anyhow::anyhow;
+
+use crate::Result;
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{ColorType, Ihdr, Palette};
+
+/*
+tRNS layout
+http://www.libpng.org/pub/png/spec/1.2/PNG-Chunks.html#C.tRNS
+depends on the color type: one 2-byte gray sample, three 2-byte RGB samples,
+or one alpha byte for each of the first palette entries
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Transparency {
+    /// The gray sample value to treat as fully transparent.
+    Gray(u16),
+    /// The color to treat as fully transparent.
+    Rgb { red: u16, green: u16, blue: u16 },
+    /// Alpha of the palette entries, in order; missing entries are opaque.
+    Palette(Vec<u8>),
+}
+
+impl Transparency {
+    /// Parses tRNS data, whose layout is given by the image's color type.
+    pub fn from_bytes(value: &[u8], color_type: ColorType) -> Result<Self> {
+        let sample = |i: usize| u16::from_be_bytes([value[i], value[i + 1]]);
+        match color_type {
+            ColorType::Grayscale if value.len() == 2 => Ok(Transparency::Gray(sample(0))),
+            ColorType::Rgb if value.len() == 6 => Ok(Transparency::Rgb {
+                red: sample(0),
+                green: sample(2),
+                blue: sample(4),
+            }),
+            ColorType::Indexed if value.len() <= Palette::MAX_ENTRIES => Ok(Transparency::Palette(value.to_vec())),
+            ColorType::GrayscaleAlpha | ColorType::Rgba => {
+                Err(anyhow!("tRNS is not allowed for color type {:?}", color_type))
+            }
+            _ => Err(anyhow!("tRNS is {} bytes, which is invalid for color type {:?}", value.len(), color_type)),
+        }
+    }
+
+    /// Parses a tRNS chunk belonging to an image with header `ihdr`.
+    pub fn from_chunk(chunk: &Chunk, ihdr: &Ihdr) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::TRNS {
+            return Err(anyhow!("expected tRNS chunk, got {}", chunk.chunk_type()));
+        }
+        let transparency = Transparency::from_bytes(chunk.data(), ihdr.color_type)?;
+        transparency.validate_for(ihdr, None)?;
+        Ok(transparency)
+    }
+
+    /// Checks this tRNS against the image header and, for indexed images,
+    /// the palette it applies to.
+    pub fn validate_for(&self, ihdr: &Ihdr, palette: Option<&Palette>) -> Result<()> {
+        let max = ((1u32 << ihdr.bit_depth) - 1) as u16;
+        match (self, ihdr.color_type) {
+            (Transparency::Gray(gray), ColorType::Grayscale) if *gray > max => {
+                Err(anyhow!("tRNS gray value {} does not fit in {} bits", gray, ihdr.bit_depth))
+            }
+            (Transparency::Rgb { red, green, blue }, ColorType::Rgb) if [red, green, blue].iter().any(|&&v| v > max) => {
+                Err(anyhow!("tRNS color does not fit in {} bits", ihdr.bit_depth))
+            }
+            (Transparency::Palette(alpha), ColorType::Indexed) => match palette {
+                Some(palette) if alpha.len() > palette.len() => {
+                    Err(anyhow!("tRNS has {} entries, but the palette only has {}", alpha.len(), palette.len()))
+                }
+                _ => Ok(()),
+            },
+            (Transparency::Gray(_), ColorType::Grayscale) | (Transparency::Rgb { .. }, ColorType::Rgb) => Ok(()),
+            _ => Err(anyhow!("tRNS does not match color type {:?}", ihdr.color_type)),
+        }
+    }
+
+    /// Alpha of palette entry `index`, 255 for entries without one.
+    pub fn alpha(&self, index: usize) -> u8 {
+        match self {
+            Transparency::Palette(alpha) => alpha.get(index).copied().unwrap_or(255),
+            _ => 255,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        match self {
+            Transparency::Gray(gray) => gray.to_be_bytes().to_vec(),
+            Transparency::Rgb { red, green, blue } => {
+                [red, green, blue].iter().flat_map(|v| v.to_be_bytes()).collect()
+            }
+            Transparency::Palette(alpha) => alpha.clone(),
+        }
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::TRNS, self.as_bytes())
+    }
+}
+
+impl Display for Transparency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Transparency::Gray(gray) => write!(f, "gray {}", gray),
+            Transparency::Rgb { red, green, blue } => write!(f, "rgb {} {} {}", red, green, blue),
+            Transparency::Palette(alpha) => write!(f, "{} palette alpha values", alpha.len()),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::Result;
+use crate::chunk_type::ChunkType;
+
+/// Where a chunk may appear relative to PLTE and IDAT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// Anywhere between IHDR and IEND.
+    Anywhere,
+    /// Before the first IDAT.
+    BeforeIdat,
+    /// Before PLTE and IDAT.
+    BeforePalette,
+    /// After PLTE, if there is one, and before IDAT.
+    AfterPalette,
+}
+
+/// A chunk type with a typed model of its data, for use with `Png::get`,
+/// `Png::get_all` and `Png::set`. Chunks whose layout depends on the image
+/// header, such as tRNS, bKGD and sBIT, are decoded by their own
+/// `from_chunk` instead.
+///
+/// ```
+/// use pngme::Result;
+/// use pngme::chunk_type::ChunkType;
+/// use pngme::chunks::{Placement, TypedChunk};
+///
+/// struct Label(String);
+///
+/// impl TypedChunk for Label {
+///     const TYPE: ChunkType = ChunkType::new(*b"laBl");
+///     const PLACEMENT: Placement = Placement::BeforeIdat;
+///
+///     fn decode(data: &[u8]) -> Result<Self> {
+///         Ok(Label(String::from_utf8(data.to_vec())?))
+///     }
+///
+///     fn encode(&self) -> Result<Vec<u8>> {
+///         Ok(self.0.as_bytes().to_vec())
+///     }
+/// }
+/// ```
+pub trait TypedChunk: Sized {
+    const TYPE: ChunkType;
+    const PLACEMENT: Placement = Placement::Anywhere;
+    /// Whether a png may contain more than one chunk of this type.
+    const MULTIPLE: bool = false;
+
+    fn decode(data: &[u8]) -> Result<Self>;
+
+    /// Fails if the value cannot be stored, such as a field out of range.
+    fn encode(&self) -> Result<Vec<u8>>;
+}
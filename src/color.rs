@@ -0,0 +1,179 @@
+//! The color space a png declares through its color management chunks.
+//!
+//! When several of them are present, the PNG third edition gives cICP
+//! precedence over iCCP, iCCP over sRGB, and sRGB over gAMA and cHRM.
+
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+
+use crate::Result;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{
+    Chromaticities, Cicp, ContentLightLevel, Gamma, IccProfile, MasteringDisplay, RenderingIntent, Srgb, TypedChunk,
+};
+use crate::png::Png;
+
+/// Which chunks define the effective color space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ColorSpace {
+    Cicp,
+    IccProfile,
+    Srgb,
+    /// gAMA and/or cHRM.
+    Calibrated,
+    Unspecified,
+}
+
+impl Display for ColorSpace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ColorSpace::Cicp => "cICP",
+            ColorSpace::IccProfile => "ICC profile",
+            ColorSpace::Srgb => "sRGB",
+            ColorSpace::Calibrated => "gAMA/cHRM",
+            ColorSpace::Unspecified => "unspecified",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Name and size of an embedded ICC profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct IccSummary {
+    pub name: String,
+    pub size: usize,
+}
+
+/// Every color management chunk of a png, and which of them applies.
+/// Chunks that fail to parse are left out and reported in `notes`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ColorInfo {
+    pub color_space: ColorSpace,
+    pub cicp: Option<Cicp>,
+    pub icc_profile: Option<IccSummary>,
+    pub rendering_intent: Option<RenderingIntent>,
+    pub gamma: Option<f64>,
+    pub chromaticities: Option<Chromaticities>,
+    /// HDR metadata from mDCv.
+    pub mastering_display: Option<MasteringDisplay>,
+    /// HDR metadata from cLLI.
+    pub content_light_level: Option<ContentLightLevel>,
+    pub notes: Vec<String>,
+}
+
+impl ColorInfo {
+    pub fn new(png: &Png) -> Self {
+        let mut notes = Vec::new();
+        let cicp = parsed::<Cicp>(png, &mut notes);
+        let icc = parsed::<IccProfile>(png, &mut notes);
+        let srgb = parsed::<Srgb>(png, &mut notes);
+        let gamma = parsed::<Gamma>(png, &mut notes);
+        let chromaticities = parsed::<Chromaticities>(png, &mut notes);
+        let mastering_display = parsed::<MasteringDisplay>(png, &mut notes);
+        let content_light_level = parsed::<ContentLightLevel>(png, &mut notes);
+
+        let color_space = if cicp.is_some() {
+            ColorSpace::Cicp
+        } else if icc.is_some() {
+            ColorSpace::IccProfile
+        } else if srgb.is_some() {
+            ColorSpace::Srgb
+        } else if gamma.is_some() || chromaticities.is_some() {
+            ColorSpace::Calibrated
+        } else {
+            ColorSpace::Unspecified
+        };
+        if icc.is_some() && srgb.is_some() {
+            notes.push("iCCP and sRGB should not both be present".to_string());
+        }
+        let overridden: Vec<&str> = [
+            (icc.is_some() && color_space != ColorSpace::IccProfile, "iCCP"),
+            (srgb.is_some() && color_space != ColorSpace::Srgb, "sRGB"),
+            (gamma.is_some() && color_space != ColorSpace::Calibrated, "gAMA"),
+            (chromaticities.is_some() && color_space != ColorSpace::Calibrated, "cHRM"),
+        ].iter().filter(|(overridden, _)| *overridden).map(|&(_, name)| name).collect();
+        if !overridden.is_empty() {
+            notes.push(format!("{} overridden by {}", overridden.join(", "), color_space));
+        }
+
+        ColorInfo {
+            color_space,
+            cicp,
+            icc_profile: icc.map(|icc| IccSummary { name: icc.name, size: icc.profile.len() }),
+            rendering_intent: srgb.map(|srgb| srgb.rendering_intent),
+            gamma: gamma.map(|gamma| gamma.gamma),
+            chromaticities,
+            mastering_display,
+            content_light_level,
+            notes,
+        }
+    }
+}
+
+/// Parses the first chunk of `chunk_type`, noting a failure.
+fn parsed<T: TypedChunk>(png: &Png, notes: &mut Vec<String>) -> Option<T> {
+    match png.get::<T>() {
+        Ok(value) => value,
+        Err(e) => {
+            notes.push(format!("invalid {}: {}", T::TYPE, e));
+            None
+        },
+    }
+}
+
+impl Display for ColorInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "color space: {}", self.color_space)?;
+        if let Some(cicp) = &self.cicp {
+            writeln!(f, "cICP: {}", cicp)?;
+        }
+        if let Some(icc) = &self.icc_profile {
+            writeln!(f, "iCCP: {} ({} bytes)", icc.name, icc.size)?;
+        }
+        if let Some(intent) = &self.rendering_intent {
+            writeln!(f, "sRGB: {:?} rendering intent", intent)?;
+        }
+        if let Some(gamma) = self.gamma {
+            writeln!(f, "gAMA: {:.5}", gamma)?;
+        }
+        if let Some(chromaticities) = &self.chromaticities {
+            writeln!(f, "cHRM: {}", chromaticities)?;
+        }
+        if let Some(display) = &self.mastering_display {
+            writeln!(f, "mDCv: {}", display)?;
+        }
+        if let Some(level) = &self.content_light_level {
+            writeln!(f, "cLLI: {}", level)?;
+        }
+        for note in self.notes.iter() {
+            writeln!(f, "note: {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+/// The embedded ICC profile of `png`, if it has one.
+pub fn icc_profile(png: &Png) -> Result<Option<IccProfile>> {
+    png.chunks_of_type(&ChunkType::ICCP).next().map(IccProfile::try_from).transpose()
+}
+
+/// Embeds `icc`, replacing any existing profile. An sRGB chunk is removed,
+/// since it must not appear alongside iCCP; returns whether there was one.
+pub fn set_icc_profile(png: &mut Png, icc: &IccProfile) -> Result<bool> {
+    let chunk = icc.to_chunk()?;
+    png.ihdr()?;
+    let had_srgb = !png.positions_of(&ChunkType::SRGB).is_empty();
+    png.retain_chunks(|chunk| chunk.chunk_type() != ChunkType::SRGB);
+    match png.positions_of(&ChunkType::ICCP).first().copied() {
+        Some(position) => {
+            png.replace_chunk(position, chunk)?;
+        },
+        // right after IHDR, which is before PLTE and IDAT as the spec requires
+        None => png.insert_chunk(1, chunk)?,
+    }
+    Ok(had_srgb)
+}
@@ -1,11 +1,80 @@
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+#[cfg(feature = "mmap")]
+use std::convert::TryFrom;
+use std::fmt::Display;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use anyhow::anyhow;
 use clap::{App, Arg, arg, ArgMatches, SubCommand};
-use crate::{Error, Result};
+use crate::Result;
+use crate::append;
+use crate::batch::{self, BatchOptions, FileReport, Outcome, Summary};
 use crate::chunk::Chunk;
-use crate::png::Png;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{IccProfile, Time};
+#[cfg(feature = "mmap")]
+use crate::chunks::Ihdr;
+use crate::color::{self, ColorInfo};
+use crate::convert::{self, ImageFormat, Source};
+use crate::detect::{self, DetectOptions};
+use crate::diff::PngDiff;
+use crate::exif::{self, Sensitive};
+use crate::inspect::{self, ChunkInspection, InspectOptions};
+#[cfg(feature = "mmap")]
+use crate::mmap::MappedPng;
+use crate::messages::{self, Message, MessageInfo, MessageStore};
+use crate::output::{self, DecodedMessage, Format, PngSummary, TimeReport, TrailingData, Validation};
+use crate::palette::{self, SortKey};
+use crate::query::ChunkQuery;
+use crate::recovery::Recovery;
+use crate::stdio;
+use crate::trailer;
+use crate::validate;
+
+/// Where `encode` and `decode` keep a message.
+enum Storage {
+    Chunk(ChunkType),
+    /// A framed payload after IEND, see `trailer`.
+    Trailer,
+}
+
+impl Storage {
+    fn kind(&self) -> &'static str {
+        match self {
+            Storage::Chunk(_) => "chunk",
+            Storage::Trailer => "trailer",
+        }
+    }
+
+    /// Why a file without the message is skipped.
+    fn missing(&self) -> &'static str {
+        match self {
+            Storage::Chunk(_) => "no such chunk type",
+            Storage::Trailer => "no trailer",
+        }
+    }
+}
+
+impl Display for Storage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Storage::Chunk(chunk_type) => write!(f, "{}", chunk_type),
+            Storage::Trailer => write!(f, "trailer"),
+        }
+    }
+}
+
+/// The `file_path` arguments of `encode`, see `Args::encode_args`.
+struct EncodeArgs {
+    inputs: Vec<String>,
+    /// None if `--key` or `--mode trailer` replaces it.
+    chunk_type: Option<String>,
+    message: String,
+    /// The legacy positional output file.
+    output: Option<String>,
+}
+
+/// Label mixed into the key when `--key` derives a chunk type.
+const KEY_LABEL: &str = "pngme message";
 
 pub struct Args{
     matches: ArgMatches,
@@ -19,32 +88,432 @@ impl Args {
                 .author("unknown")
                 .about("personal implementation of pngme")
                 .subcommand(SubCommand::with_name("encode")
-                    .about("encode info into png, <file path> <chunk type> <message> [output file]")
+                    .about("encode info into png files, <file path>... <chunk type> <message>")
+                    .override_usage("pngme encode [OPTIONS] <file_path>... <chunk_type> <message>\n    \
+                                     pngme encode [OPTIONS] <file_path> <chunk_type> <message> <output_file>\n    \
+                                     pngme encode [OPTIONS] --key <key> <file_path>... <message>\n    \
+                                     pngme encode [OPTIONS] --mode trailer <file_path>... <message>")
                     .args([
-                        arg!(<file_path> "file path"),
-                        arg!(<chunk_type> "chunk type"),
-                        arg!(<message> "message"),
-                        arg!([output_file] "output file")
-                    ]))
+                        // the chunk type and message are split off the end, see `encode_args`
+                        arg!(<file_path> ... "file paths, globs or directories, then the chunk type and message")
+                            .min_values(2),
+                        Self::key_arg(),
+                        Self::mode_arg(),
+                        arg!(--touch "also set the tIME chunk to the current time"),
+                        Self::output_arg(),
+                    ])
+                    .args(Self::batch_args()))
                 .subcommand(SubCommand::with_name("decode")
-                    .about("decode from a png file with specified chunk type, <file path>, <chunk type>")
+                    .about("decode from png files with specified chunk type, <file path>... <chunk type>")
+                    .override_usage("pngme decode [OPTIONS] <file_path>... <chunk_type>\n    \
+                                     pngme decode [OPTIONS] --key <key> <file_path>...\n    \
+                                     pngme decode [OPTIONS] --mode trailer <file_path>...")
                     .args([
-                        arg!(<file_path> "file path"),
-                        arg!(<chunk_type> "chunk type")
-                    ]))
+                        // the chunk type is split off the end unless --key or --mode replaces it
+                        arg!(<file_path> ... "file paths, globs or directories, then the chunk type")
+                            .min_values(1),
+                        Self::key_arg(),
+                        Self::mode_arg(),
+                        Self::format_arg(),
+                    ])
+                    .args(Self::batch_args()))
                 .subcommand(SubCommand::with_name("remove")
                     .about("remove chunk type from a png file, <file path> <chunk type>")
                     .args([
                         arg!(<file_path> "file path"),
                         arg!(<chunk_type> "chunk type"),
+                        Self::output_arg(),
                     ]))
                 .subcommand(SubCommand::with_name("print")
                     .about("print file info")
-                    .arg(arg!(<file_path> "file path")))
+                    .args([
+                        arg!(<file_path> ... "file paths, globs or directories"),
+                        arg!(--"verify-crc" "check the crc of every chunk, which reads the whole file"),
+                        Self::format_arg(),
+                    ])
+                    .args(Self::batch_args()))
+                .subcommand(SubCommand::with_name("list")
+                    .about("list the chunks matching a type or property bits, <file path>...")
+                    .args([
+                        arg!(<file_path> ... "file paths, globs or directories"),
+                        arg!(--type [chunk_type] "only list chunks of this type"),
+                        arg!(--critical "only list critical chunks").conflicts_with("ancillary"),
+                        arg!(--ancillary "only list ancillary chunks"),
+                        arg!(--public "only list public chunks").conflicts_with("private"),
+                        arg!(--private "only list private chunks"),
+                        arg!(--"safe-to-copy" "only list chunks that are safe to copy")
+                            .conflicts_with("unsafe-to-copy"),
+                        arg!(--"unsafe-to-copy" "only list chunks that are unsafe to copy"),
+                        Self::format_arg(),
+                    ])
+                    .args(Self::batch_args()))
+                .subcommand(SubCommand::with_name("messages")
+                    .about("manage named messages stored in a png")
+                    .subcommand_required(true)
+                    .subcommand(SubCommand::with_name("list")
+                        .about("list the messages of a png, <file path>")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            Self::format_arg(),
+                        ]))
+                    .subcommand(SubCommand::with_name("get")
+                        .about("print the content of a message, <file path> <name>")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            arg!(<name> "message name"),
+                        ]))
+                    .subcommand(SubCommand::with_name("put")
+                        .about("store a message, replacing any with the same name, <file path> <name> [message]")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            arg!(<name> "message name"),
+                            arg!([message] "message text").required_unless_present("file"),
+                            arg!(--file [content_file] "read the content from this file, - for stdin")
+                                .conflicts_with("message"),
+                            arg!(--"content-type" [content_type] "content type of the message"),
+                            Self::output_arg(),
+                        ]))
+                    .subcommand(SubCommand::with_name("delete")
+                        .about("remove a message, <file path> <name>")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            arg!(<name> "message name"),
+                            Self::output_arg(),
+                        ])))
+                .subcommand(SubCommand::with_name("palette")
+                    .about("show or edit the palette of a png")
+                    .subcommand_required(true)
+                    .subcommand(SubCommand::with_name("show")
+                        .about("list the palette entries with their alpha, <file path>")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            Self::format_arg(),
+                        ]))
+                    .subcommand(SubCommand::with_name("set")
+                        .about("change or append a palette entry, <file path> <index> <color>")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            arg!(<index> "palette index, one past the end to append")
+                                .value_parser(clap::value_parser!(usize)),
+                            arg!(<color> "color as #rrggbb"),
+                            arg!(--alpha [alpha] "alpha of the entry, 0 to 255")
+                                .value_parser(clap::value_parser!(u8)),
+                            Self::output_arg(),
+                        ]))
+                    .subcommand(SubCommand::with_name("sort")
+                        .about("reorder the palette and remap the pixels to match, <file path>")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            arg!(--by [key] "sort key")
+                                .value_parser(SortKey::NAMES)
+                                .default_value("luminance"),
+                            Self::output_arg(),
+                        ])))
+                .subcommand(SubCommand::with_name("color")
+                    .about("show the color space declared by png files, <file path>...")
+                    .args([
+                        arg!(<file_path> ... "file paths, globs or directories"),
+                        Self::format_arg(),
+                    ])
+                    .args(Self::batch_args()))
+                .subcommand(SubCommand::with_name("icc")
+                    .about("extract or replace the embedded ICC profile")
+                    .subcommand_required(true)
+                    .subcommand(SubCommand::with_name("extract")
+                        .about("write the ICC profile to a file, <file path> [profile file]")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            arg!([profile_file] "profile file, defaults to stdout"),
+                        ]))
+                    .subcommand(SubCommand::with_name("set")
+                        .about("embed an ICC profile, replacing any sRGB chunk, <file path> <profile file>")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            arg!(<profile_file> "profile file, - for stdin"),
+                            arg!(--name [name] "profile name stored in the chunk")
+                                .default_value("ICC Profile"),
+                            Self::output_arg(),
+                        ])))
+                .subcommand(SubCommand::with_name("exif")
+                    .about("show the eXIf metadata of a png or remove sensitive tags from it")
+                    .subcommand_required(true)
+                    .subcommand(SubCommand::with_name("show")
+                        .about("list the eXIf tags, <file path>")
+                        .args([
+                            arg!(<file_path> "file path"),
+                            Self::format_arg(),
+                        ]))
+                    .subcommand(SubCommand::with_name("strip")
+                        .about("remove GPS and serial number tags from the eXIf chunk of png files, <file path>...")
+                        .args([
+                            arg!(<file_path> ... "file paths, globs or directories"),
+                            arg!(--only [category] ... "only remove these tags")
+                                .value_parser(Sensitive::NAMES),
+                            Self::output_arg(),
+                        ])
+                        .args(Self::batch_args())))
+                .subcommand(SubCommand::with_name("time")
+                    .about("read, set or remove the last-modification time of png files")
+                    .subcommand_required(true)
+                    .subcommand(SubCommand::with_name("get")
+                        .about("print the tIME of png files as RFC 3339, <file path>...")
+                        .args([
+                            arg!(<file_path> ... "file paths, globs or directories"),
+                            Self::format_arg(),
+                        ])
+                        .args(Self::batch_args()))
+                    .subcommand(SubCommand::with_name("set")
+                        .about("set the tIME of png files, <file path>...")
+                        .args([
+                            arg!(<file_path> ... "file paths, globs or directories"),
+                            arg!(--at [time] "RFC 3339 timestamp, defaults to now"),
+                            Self::output_arg(),
+                        ])
+                        .args(Self::batch_args()))
+                    .subcommand(SubCommand::with_name("clear")
+                        .about("remove the tIME of png files, <file path>...")
+                        .args([
+                            arg!(<file_path> ... "file paths, globs or directories"),
+                            Self::output_arg(),
+                        ])
+                        .args(Self::batch_args())))
+                .subcommand(SubCommand::with_name("strip")
+                    .about("remove ancillary chunks from png files, <file path>...")
+                    .args([
+                        arg!(<file_path> ... "file paths, globs or directories"),
+                        arg!(--private "only remove private ancillary chunks"),
+                        arg!(--trailing "also remove data after the IEND chunk"),
+                        Self::output_arg(),
+                    ])
+                    .args(Self::batch_args()))
+                .subcommand(SubCommand::with_name("trailing")
+                    .about("show, extract or strip data appended after IEND, <file path>")
+                    .args([
+                        arg!(<file_path> "file path"),
+                        arg!(--extract [extract_file] "write the trailing data to this file, - for stdout"),
+                        arg!(--strip "remove the trailing data from the png"),
+                        Self::output_arg(),
+                        Self::format_arg(),
+                    ]))
+                .subcommand(SubCommand::with_name("validate")
+                    .about("check png files against the spec, <file path>...")
+                    .args([
+                        arg!(<file_path> ... "file paths, globs or directories"),
+                        Self::format_arg(),
+                    ])
+                    .args(Self::batch_args()))
+                .subcommand(SubCommand::with_name("inspect")
+                    .about("show offsets, crc and a hexdump of every chunk, <file path>")
+                    .args([
+                        arg!(<file_path> "file path"),
+                        arg!(--full "dump all chunk data instead of the first bytes"),
+                        arg!(--strict "fail on bad crcs and garbage instead of keeping going"),
+                        Self::format_arg(),
+                    ]))
+                .subcommand(SubCommand::with_name("diff")
+                    .about("compare the chunks of two png files, <old file> <new file>")
+                    .args([
+                        arg!(<old_file> "old file path"),
+                        arg!(<new_file> "new file path"),
+                        arg!(--pixels "also decode and compare the image data"),
+                        Self::format_arg(),
+                    ]))
+                .subcommand(SubCommand::with_name("detect")
+                    .about("scan png files for hidden data and score the risk, <file path>...")
+                    .args([
+                        arg!(<file_path> ... "file paths, globs or directories"),
+                        arg!(--pixels "also run statistical tests on the pixels' least significant bits"),
+                        arg!(--format [format] "output format")
+                            .value_parser(Format::NAMES)
+                            .default_value("json"),
+                    ])
+                    .args(Self::batch_args()))
+                .subcommand(SubCommand::with_name("repair")
+                    .about("salvage a damaged png, fixing crcs and the IEND chunk, <file path> [output file]")
+                    .args([
+                        arg!(<file_path> "file path"),
+                        arg!([output_file] "output file"),
+                        Self::output_arg(),
+                        arg!(--"dry-run" "only report what would be fixed"),
+                        arg!(--"drop-trailing" "also drop any data after IEND"),
+                    ]))
+                .subcommand(SubCommand::with_name("dump")
+                    .about("dump a png as editable json or yaml, <file path>")
+                    .args([
+                        arg!(<file_path> "file path"),
+                        arg!(--format [format] "output format")
+                            .value_parser(["json", "yaml"])
+                            .default_value("json"),
+                    ]))
+                .subcommand(SubCommand::with_name("build")
+                    .about("build a png from a json or yaml dump, <dump path> [output file]")
+                    .args([
+                        arg!(<dump_path> "dump path, - for stdin"),
+                        arg!([output_file] "output file, defaults to stdout"),
+                        Self::output_arg(),
+                    ]))
+                .subcommand(SubCommand::with_name("convert")
+                    .about("convert between png, netpbm (pbm, pgm, ppm, pam) and raw rgba, <input file> <output file>")
+                    .args([
+                        arg!(<input_file> "input file, - for stdin"),
+                        arg!(<output_file> "output file, - for stdout"),
+                        arg!(--to [format] "output format, by default taken from the output file extension")
+                            .value_parser(ImageFormat::NAMES),
+                        arg!(--size [size] "WIDTHxHEIGHT of raw rgba input"),
+                        arg!(--depth [depth] "bits per output sample, by default those of the input")
+                            .value_parser(clap::value_parser!(u8)),
+                    ]))
                 .get_matches(),
         }
     }
 
+    /// The `--format` option shared by all read-only commands.
+    fn format_arg() -> Arg<'static> {
+        arg!(--format [format] "output format")
+            .value_parser(Format::NAMES)
+            .default_value("table")
+    }
+
+    /// The `--key` option of commands that derive their chunk type from a key.
+    fn key_arg() -> Arg<'static> {
+        arg!(--key [key] "derive a random-looking chunk type from this key instead")
+    }
+
+    /// The `--mode` option of `encode` and `decode`.
+    fn mode_arg() -> Arg<'static> {
+        arg!(--mode [mode] "store the message in a chunk or in a trailer after IEND")
+            .value_parser(["chunk", "trailer"])
+            .conflicts_with("key")
+    }
+
+    /// Where `encode` and `decode` keep the message. The chunk type argument
+    /// is not needed, and so holds the message, unless `given_chunk_type`.
+    fn storage(matches: &ArgMatches, given: Option<&String>) -> Result<Storage> {
+        match matches.get_one::<String>("mode").map(String::as_str) {
+            Some("trailer") => Ok(Storage::Trailer),
+            _ => Ok(Storage::Chunk(Self::chunk_type(matches, given)?)),
+        }
+    }
+
+    /// Whether the chunk type argument is replaced by `--key` or `--mode trailer`.
+    fn chunk_type_implied(matches: &ArgMatches) -> bool {
+        matches.contains_id("key") || matches.get_one::<String>("mode").is_some_and(|mode| mode == "trailer")
+    }
+
+    /// The chunk type given as `chunk_type`, or derived from `--key`.
+    fn chunk_type(matches: &ArgMatches, given: Option<&String>) -> Result<ChunkType> {
+        match (matches.get_one::<String>("key"), given) {
+            (Some(key), _) => Ok(ChunkType::derive(key.as_bytes(), KEY_LABEL)),
+            (None, Some(chunk_type)) => chunk_type.parse(),
+            (None, None) => Err(anyhow!("give a chunk type or --key")),
+        }
+    }
+
+    /// The `-o` option of commands that write a png.
+    fn output_arg() -> Arg<'static> {
+        arg!(-o --output [output] "write the png here instead of in place, - for stdout")
+    }
+
+    /// Where a modifying command writes its png: the `-o` option or the
+    /// legacy positional `output_file`, if either is given.
+    fn output_path(matches: &ArgMatches) -> Result<Option<PathBuf>> {
+        let positional = matches.try_get_one::<String>("output_file").ok().flatten();
+        let inputs: Vec<String> = matches.try_get_many::<String>("file_path").ok().flatten()
+            .map_or_else(Vec::new, |inputs| inputs.cloned().collect());
+        Self::output_path_for(matches, &inputs, positional)
+    }
+
+    /// `output_path` for `inputs` and `positional` split off the arguments by hand.
+    fn output_path_for(matches: &ArgMatches, inputs: &[String], positional: Option<&String>) -> Result<Option<PathBuf>> {
+        let option = matches.try_get_one::<String>("output").ok().flatten();
+        let output = match (option, positional) {
+            (Some(_), Some(_)) => return Err(anyhow!("give the output file either with -o or as an argument, not both")),
+            (output, None) | (None, output) => output.map(PathBuf::from),
+        };
+        let single = inputs.len() <= 1
+            && inputs.iter().all(|input| !batch::is_pattern(input) && !Path::new(input).is_dir());
+        if output.is_some() && !single {
+            return Err(anyhow!("an output file can only be given for a single input"));
+        }
+        Ok(output)
+    }
+
+    /// Options shared by commands that accept several files.
+    fn batch_args() -> [Arg<'static>; 2] {
+        [
+            arg!(-r --recursive "process every png below the given directories"),
+            arg!(-j --jobs [jobs] "number of files processed in parallel")
+                .value_parser(clap::value_parser!(usize)),
+        ]
+    }
+
+    fn batch_options(matches: &ArgMatches) -> BatchOptions {
+        let mut options = BatchOptions {
+            recursive: matches.contains_id("recursive"),
+            ..BatchOptions::default()
+        };
+        if let Some(&jobs) = matches.get_one::<usize>("jobs") {
+            options.jobs = jobs;
+        }
+        options
+    }
+
+    /// Runs `job` over every input of a batch command and reports the results.
+    /// A single input is reported as before batching existed; several inputs
+    /// get one entry each followed by a summary. Fails if any file failed.
+    /// Reports go to stderr when the png itself is written to stdout.
+    fn run_batch<F>(matches: &ArgMatches, job: F) -> Result<()>
+        where F: Fn(&Path) -> Result<Outcome> + Sync
+    {
+        let inputs: Vec<String> = matches.get_many::<String>("file_path").unwrap().cloned().collect();
+        Self::run_batch_on(matches, &inputs, Self::output_path(matches)?.as_deref(), job)
+    }
+
+    /// `run_batch` over `inputs` and writing to `output`, for commands that
+    /// split their `file_path` arguments by hand.
+    fn run_batch_on<F>(matches: &ArgMatches, inputs: &[String], output: Option<&Path>, job: F) -> Result<()>
+        where F: Fn(&Path) -> Result<Outcome> + Sync
+    {
+        let mut results = batch::run(inputs, &Self::batch_options(matches), job);
+        // only commands writing a png take -o; without it the png replaces
+        // its input, which for stdin means stdout
+        let writes_png = matches.try_get_one::<String>("output").is_ok();
+        let png_to_stdout = match output {
+            Some(path) => stdio::is_stdio(path),
+            None => writes_png && inputs.iter().any(|input| stdio::is_stdio(Path::new(input))),
+        };
+        let mut out: Box<dyn Write> = if png_to_stdout { Box::new(io::stderr()) } else { Box::new(io::stdout()) };
+        if results.len() == 1 {
+            let result = results.remove(0);
+            match result.outcome? {
+                Outcome::Succeeded(report) => writeln!(out, "{}", report.trim_end())?,
+                Outcome::Skipped(reason) => writeln!(out, "{}: {}", result.path.display(), reason)?,
+            }
+            return Ok(());
+        }
+
+        for result in results.iter() {
+            let path = result.path.display();
+            match &result.outcome {
+                Ok(Outcome::Succeeded(report)) if report.trim_end().contains('\n') =>
+                    writeln!(out, "==> {} <==\n{}", path, report.trim_end())?,
+                Ok(Outcome::Succeeded(report)) => writeln!(out, "{}: {}", path, report.trim_end())?,
+                Ok(Outcome::Skipped(reason)) => writeln!(out, "{}: skipped, {}", path, reason)?,
+                Err(e) => eprintln!("{}: failed, {}", path, e),
+            }
+        }
+        let summary = Summary::new(&results);
+        writeln!(out, "{}", summary)?;
+        if summary.failed > 0 {
+            return Err(anyhow!("{} of {} files failed", summary.failed, results.len()));
+        }
+        Ok(())
+    }
+
+    fn format(matches: &ArgMatches) -> Result<Format> {
+        matches.get_one::<String>("format").unwrap().parse()
+    }
+
     pub fn parse() -> Result<()>{
         let args = Self::new();
         match args.matches.subcommand() {
@@ -52,6 +521,23 @@ impl Args {
             Some(("decode", sub_cmd)) => Self::handle_decode(sub_cmd),
             Some(("remove", sub_cmd)) => Self::handle_remove_chunk_type(sub_cmd),
             Some(("print", sub_cmd)) => Self::handle_print(sub_cmd),
+            Some(("list", sub_cmd)) => Self::handle_list(sub_cmd),
+            Some(("messages", sub_cmd)) => Self::handle_messages(sub_cmd),
+            Some(("palette", sub_cmd)) => Self::handle_palette(sub_cmd),
+            Some(("color", sub_cmd)) => Self::handle_color(sub_cmd),
+            Some(("icc", sub_cmd)) => Self::handle_icc(sub_cmd),
+            Some(("exif", sub_cmd)) => Self::handle_exif(sub_cmd),
+            Some(("time", sub_cmd)) => Self::handle_time(sub_cmd),
+            Some(("strip", sub_cmd)) => Self::handle_strip(sub_cmd),
+            Some(("trailing", sub_cmd)) => Self::handle_trailing(sub_cmd),
+            Some(("validate", sub_cmd)) => Self::handle_validate(sub_cmd),
+            Some(("inspect", sub_cmd)) => Self::handle_inspect(sub_cmd),
+            Some(("diff", sub_cmd)) => Self::handle_diff(sub_cmd),
+            Some(("detect", sub_cmd)) => Self::handle_detect(sub_cmd),
+            Some(("repair", sub_cmd)) => Self::handle_repair(sub_cmd),
+            Some(("dump", sub_cmd)) => Self::handle_dump(sub_cmd),
+            Some(("build", sub_cmd)) => Self::handle_build(sub_cmd),
+            Some(("convert", sub_cmd)) => Self::handle_convert(sub_cmd),
             _ => {
                 Err(anyhow!("command not found"))
             },
@@ -60,54 +546,618 @@ impl Args {
 
 
     fn handle_encode(matches: &ArgMatches) -> Result<()> {
+        let EncodeArgs { inputs, chunk_type, message: msg, output } = Self::encode_args(matches)?;
+        let storage = Self::storage(matches, chunk_type.as_ref())?;
+        let output_file = Self::output_path_for(matches, &inputs, output.as_ref())?;
+        // every file of a batch gets the same stamp
+        let touch = matches.contains_id("touch").then(Time::now);
+        Self::run_batch_on(matches, &inputs, output_file.as_deref(), |path| {
+            let chunk_type = match &storage {
+                Storage::Chunk(chunk_type) => chunk_type,
+                Storage::Trailer => {
+                    let mut png = stdio::read_png(path)?;
+                    trailer::write(&mut png, msg.as_bytes())?;
+                    if let Some(time) = touch {
+                        png.set_chunk(time.to_chunk());
+                    }
+                    stdio::write(output_file.as_deref().unwrap_or(path), &png.as_bytes())?;
+                    return Ok(Outcome::Succeeded("message encoded".to_string()));
+                },
+            };
+            let chunk = Chunk::new(chunk_type.clone(), msg.as_bytes().to_vec());
+            // an existing tIME chunk may be anywhere, so touching rewrites the whole file
+            if output_file.is_none() && !stdio::is_stdio(path) && touch.is_none() {
+                append::append_chunk(path, &chunk)?;
+                return Ok(Outcome::Succeeded("message encoded".to_string()));
+            }
+            let mut png = stdio::read_png(path)?;
+            png.append_chunk(chunk);
+            if let Some(time) = touch {
+                png.set_chunk(time.to_chunk());
+            }
+            stdio::write(output_file.as_deref().unwrap_or(path), &png.as_bytes())?;
+            Ok(Outcome::Succeeded("message encoded".to_string()))
+        })
+    }
+
+    /// Splits the `file_path` arguments of `encode` into the inputs, the chunk
+    /// type unless `--key` or `--mode trailer` replaces it, the message and
+    /// the legacy output file. The output file is only recognized in the
+    /// four argument form `<file_path> <chunk_type> <message> <output_file>`.
+    fn encode_args(matches: &ArgMatches) -> Result<EncodeArgs> {
+        let mut values: Vec<String> = matches.get_many::<String>("file_path").unwrap().cloned().collect();
+        let implied = Self::chunk_type_implied(matches);
+        let legacy = !implied && values.len() == 4
+            && values[1].parse::<ChunkType>().is_ok() && !Path::new(&values[1]).exists();
+        let output = if legacy { values.pop() } else { None };
+        let message = values.pop().unwrap();
+        let chunk_type = if implied { None } else { values.pop() };
+        if values.is_empty() {
+            return Err(anyhow!("give the file paths, then the chunk type and message, or --key"));
+        }
+        Ok(EncodeArgs { inputs: values, chunk_type, message, output })
+    }
+
+    fn handle_decode(matches: &ArgMatches) -> Result<()> {
+        let format = Self::format(matches)?;
+        let mut inputs: Vec<String> = matches.get_many::<String>("file_path").unwrap().cloned().collect();
+        let storage = if Self::chunk_type_implied(matches) {
+            Self::storage(matches, None)?
+        } else if inputs.len() < 2 {
+            return Err(anyhow!("give a chunk type after the file paths, or --key"));
+        } else {
+            Self::storage(matches, inputs.pop().as_ref())?
+        };
+        Self::run_batch_on(matches, &inputs, None, |path| {
+            let found = match &storage {
+                Storage::Chunk(chunk_type) => Self::find_chunk(path, chunk_type)?
+                    .map(|(offset, chunk)| (offset, chunk.data().to_vec())),
+                Storage::Trailer => {
+                    let png = stdio::read_png(path)?;
+                    trailer::read(png.trailing_data())?
+                        .map(|(offset, payload)| (png.trailing_offset() + offset, payload))
+                },
+            };
+            let report = match (found, format) {
+                (Some((_, data)), Format::Table) => format!("{} data: {}", storage.kind(), String::from_utf8(data)?),
+                (Some((offset, data)), _) => {
+                    let decoded = DecodedMessage {
+                        chunk_type: storage.to_string(),
+                        offset,
+                        message: String::from_utf8(data)?,
+                    };
+                    output::to_string(&decoded, format)?
+                },
+                (None, _) => return Ok(Outcome::Skipped(storage.missing().to_string())),
+            };
+            Ok(Outcome::Succeeded(report))
+        })
+    }
+
+    fn handle_remove_chunk_type(matches: &ArgMatches) -> Result<()> {
         let path_buf = PathBuf::from(matches.get_one::<String>("file_path").unwrap());
+        let mut png = stdio::read_png(&path_buf)?;
+
         let chunk_type = matches.get_one::<String>("chunk_type").unwrap();
-        let msg:&str = matches.get_one::<String>("message").unwrap();
-        let mut png = Png::from_file(&path_buf).unwrap();
-        png.append_chunk(Chunk::new(chunk_type.parse()?, msg.as_bytes().to_vec()));
-
-        if let Some(output_file) = matches.get_one::<String>("output_file") {
-            let mut file = File::create(output_file)?;
-            file.write_all(png.as_bytes().as_slice())?;
-        }else{
-            let mut file = File::create(&path_buf)?;
-            file.write_all(png.as_bytes().as_slice())?;
-        }
-        println!("message encoded");
+        png.remove_chunk(chunk_type)?;
+        let output_file = Self::output_path(matches)?.unwrap_or(path_buf);
+        stdio::write(&output_file, &png.as_bytes())?;
+        Self::status(&output_file, "specified chunk type removed");
         Ok(())
     }
 
-    fn handle_decode(matches: &ArgMatches) -> Result<()> {
+    fn handle_print(matches: &ArgMatches) -> Result<()> {
+        let format = Self::format(matches)?;
+        let verify_crc = matches.contains_id("verify-crc");
+        Self::run_batch(matches, |path| {
+            let summary = Self::summarize(path, verify_crc)?;
+            let report = match format {
+                Format::Table => output::chunk_table(&summary),
+                format => output::to_string(&summary, format)?,
+            };
+            Ok(Outcome::Succeeded(report))
+        })
+    }
+
+    fn handle_list(matches: &ArgMatches) -> Result<()> {
+        let format = Self::format(matches)?;
+        let mut query = ChunkQuery::new();
+        if let Some(chunk_type) = matches.get_one::<String>("type") {
+            query = query.chunk_type(chunk_type.parse()?);
+        }
+        for (flag, negated, set) in [
+            ("critical", "ancillary", ChunkQuery::critical as fn(ChunkQuery, bool) -> ChunkQuery),
+            ("public", "private", ChunkQuery::public),
+            ("safe-to-copy", "unsafe-to-copy", ChunkQuery::safe_to_copy),
+        ] {
+            if matches.contains_id(flag) || matches.contains_id(negated) {
+                query = set(query, matches.contains_id(flag));
+            }
+        }
+        Self::run_batch(matches, |path| {
+            let png = stdio::read_png(path)?;
+            let mut summary = PngSummary::new(&png);
+            let mut matches = png.chunks().iter().map(|chunk| query.matches(chunk));
+            summary.chunks.retain(|_| matches.next().unwrap_or(false));
+            let report = match format {
+                Format::Table => output::chunk_table(&summary),
+                format => output::to_string(&summary, format)?,
+            };
+            Ok(Outcome::Succeeded(report))
+        })
+    }
+
+    fn handle_messages(matches: &ArgMatches) -> Result<()> {
+        let (command, matches) = matches.subcommand().expect("a messages subcommand is required");
         let path_buf = PathBuf::from(matches.get_one::<String>("file_path").unwrap());
-        let png = Png::from_file(path_buf).unwrap();
+        let mut store = MessageStore::new(stdio::read_png(&path_buf)?);
+        let name = matches.try_get_one::<String>("name").ok().flatten();
+        match (command, name) {
+            ("list", _) => {
+                let messages: Vec<MessageInfo> = store.list()?.iter().map(MessageInfo::new).collect();
+                match Self::format(matches)? {
+                    Format::Table => print!("{}", output::message_table(&messages)),
+                    format => println!("{}", output::to_string(&messages, format)?),
+                }
+                return Ok(());
+            },
+            ("get", Some(name)) => {
+                let message = store.get(name)?.ok_or_else(|| anyhow!("no message named {:?}", name))?;
+                let mut stdout = io::stdout();
+                stdout.write_all(&message.content)?;
+                if message.text().is_some() && stdout.is_terminal() {
+                    writeln!(stdout)?;
+                }
+                return Ok(());
+            },
+            ("put", Some(name)) => {
+                let (content, default_type) = match matches.get_one::<String>("file") {
+                    Some(file) => (stdio::read(Path::new(file))?, "application/octet-stream"),
+                    None => (matches.get_one::<String>("message").unwrap().as_bytes().to_vec(),
+                             messages::DEFAULT_CONTENT_TYPE),
+                };
+                let content_type = matches.get_one::<String>("content-type").map_or(default_type, String::as_str);
+                let replaced = store.put(Message::new(name, content_type, content)?)?;
+                let output_file = Self::output_path(matches)?.unwrap_or(path_buf);
+                stdio::write(&output_file, &store.into_png().as_bytes())?;
+                let verb = if replaced.is_some() { "replaced" } else { "stored" };
+                Self::status(&output_file, format!("message {:?} {}", name, verb));
+            },
+            ("delete", Some(name)) => {
+                store.delete(name)?;
+                let output_file = Self::output_path(matches)?.unwrap_or(path_buf);
+                stdio::write(&output_file, &store.into_png().as_bytes())?;
+                Self::status(&output_file, format!("message {:?} deleted", name));
+            },
+            _ => return Err(anyhow!("command not found")),
+        }
+        Ok(())
+    }
 
-        let chunk_type = matches.get_one::<String>("chunk_type").unwrap();
-        if let Some(chunk) = png.get_chunk(chunk_type) {
-            println!("chunk data: {}", chunk.data_as_string()?);
-        }else {
-            println!("no such chunk type");
+    fn handle_palette(matches: &ArgMatches) -> Result<()> {
+        let (command, matches) = matches.subcommand().expect("a palette subcommand is required");
+        let path_buf = PathBuf::from(matches.get_one::<String>("file_path").unwrap());
+        let mut png = stdio::read_png(&path_buf)?;
+        let status = match command {
+            "show" => {
+                let entries = palette::entries(&png)?;
+                match Self::format(matches)? {
+                    Format::Table => print!("{}", output::palette_table(&entries)),
+                    format => println!("{}", output::to_string(&entries, format)?),
+                }
+                return Ok(());
+            },
+            "set" => {
+                let index = *matches.get_one::<usize>("index").unwrap();
+                let color = palette::parse_color(matches.get_one::<String>("color").unwrap())?;
+                palette::set(&mut png, index, color, matches.get_one::<u8>("alpha").copied())?;
+                format!("palette entry {} set to {}", index, palette::format_color(color))
+            },
+            "sort" => {
+                let key: SortKey = matches.get_one::<String>("by").unwrap().parse()?;
+                if !palette::sort(&mut png, key)? {
+                    println!("palette is already sorted");
+                    return Ok(());
+                }
+                format!("palette sorted by {}", matches.get_one::<String>("by").unwrap())
+            },
+            _ => return Err(anyhow!("command not found")),
+        };
+        let output_file = Self::output_path(matches)?.unwrap_or(path_buf);
+        stdio::write(&output_file, &png.as_bytes())?;
+        Self::status(&output_file, status);
+        Ok(())
+    }
+
+    fn handle_color(matches: &ArgMatches) -> Result<()> {
+        let format = Self::format(matches)?;
+        if format == Format::Table {
+            return Self::run_batch(matches, |path| {
+                Ok(Outcome::Succeeded(stdio::read_png(path)?.color_info().to_string()))
+            });
+        }
+        let inputs: Vec<String> = matches.get_many::<String>("file_path").unwrap().cloned().collect();
+        let results = batch::run(&inputs, &Self::batch_options(matches), |path| {
+            Ok(Outcome::Succeeded(stdio::read_png(path)?.color_info()))
+        });
+        let reports: Vec<FileReport<ColorInfo>> = results.iter().map(FileReport::new).collect();
+        if reports.len() == 1 {
+            println!("{}", output::to_string(&reports[0], format)?);
+        } else {
+            println!("{}", output::to_string(&reports, format)?);
+        }
+        let failed = Summary::new(&results).failed;
+        if failed > 0 {
+            return Err(anyhow!("{} of {} files failed", failed, results.len()));
         }
         Ok(())
     }
 
-    fn handle_remove_chunk_type(matches: &ArgMatches) -> Result<()> {
+    fn handle_icc(matches: &ArgMatches) -> Result<()> {
+        let (command, matches) = matches.subcommand().expect("an icc subcommand is required");
         let path_buf = PathBuf::from(matches.get_one::<String>("file_path").unwrap());
-        let mut png = Png::from_file(path_buf).unwrap();
+        let mut png = stdio::read_png(&path_buf)?;
+        match command {
+            "extract" => {
+                let icc = color::icc_profile(&png)?.ok_or_else(|| anyhow!("png has no iCCP chunk"))?;
+                let output_file = matches.get_one::<String>("profile_file")
+                    .map_or_else(|| PathBuf::from(stdio::STDIO_PATH), PathBuf::from);
+                stdio::write(&output_file, &icc.profile)?;
+                if !stdio::is_stdio(&output_file) {
+                    println!("extracted {}", icc);
+                }
+            },
+            "set" => {
+                let profile = stdio::read(Path::new(matches.get_one::<String>("profile_file").unwrap()))?;
+                let icc = IccProfile::new(matches.get_one::<String>("name").unwrap(), profile)?;
+                let removed_srgb = color::set_icc_profile(&mut png, &icc)?;
+                let output_file = Self::output_path(matches)?.unwrap_or(path_buf);
+                stdio::write(&output_file, &png.as_bytes())?;
+                Self::status(&output_file, format!("embedded {}", icc));
+                if removed_srgb {
+                    Self::status(&output_file, "removed the sRGB chunk, which must not appear alongside iCCP");
+                }
+            },
+            _ => return Err(anyhow!("command not found")),
+        }
+        Ok(())
+    }
 
-        let chunk_type = matches.get_one::<String>("chunk_type").unwrap();
-        png.remove_chunk(chunk_type)?;
-        println!("specified chunk type removed");
+    /// Finds the first chunk of `chunk_type` and its offset. Files are memory
+    /// mapped when the `mmap` feature is enabled, so that only the chunk
+    /// headers up to the match are read.
+    fn find_chunk(path: &Path, chunk_type: &ChunkType) -> Result<Option<(usize, Chunk)>> {
+        #[cfg(feature = "mmap")]
+        if !stdio::is_stdio(path) {
+            let mapped = MappedPng::open(path)?;
+            return Ok(mapped.find(chunk_type)?.map(|chunk| (chunk.offset(), chunk.to_chunk())));
+        }
+        let png = stdio::read_png(path)?;
+        let found = png.chunks_with_offsets()
+            .find(|(_, chunk)| chunk.chunk_type() == *chunk_type)
+            .map(|(offset, chunk)| (offset, chunk.clone()));
+        Ok(found)
+    }
+
+    /// Builds the chunk table of `print`, from the chunk headers of a memory
+    /// mapping when the `mmap` feature is enabled. Only the stored CRCs are
+    /// reported unless `verify_crc`.
+    fn summarize(path: &Path, verify_crc: bool) -> Result<PngSummary> {
+        #[cfg(feature = "mmap")]
+        if !stdio::is_stdio(path) {
+            let mapped = MappedPng::open(path)?;
+            let mut ihdr = None;
+            let chunks = mapped.chunks()
+                .map(|chunk| chunk.map(|chunk| {
+                    if *chunk.chunk_type() == ChunkType::IHDR {
+                        ihdr = Ihdr::try_from(chunk.data()).ok();
+                    }
+                    chunk.summary(ihdr.as_ref(), verify_crc)
+                }))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(PngSummary::from_chunks(chunks));
+        }
+        // reading the png checks every crc anyway, but report the same as above
+        let mut summary = PngSummary::new(&stdio::read_png(path)?);
+        if !verify_crc {
+            summary.chunks.iter_mut().for_each(|chunk| chunk.crc_valid = None);
+        }
+        Ok(summary)
+    }
+
+    fn handle_exif(matches: &ArgMatches) -> Result<()> {
+        let (command, matches) = matches.subcommand().expect("an exif subcommand is required");
+        if command == "show" {
+            let png = stdio::read_png(Path::new(matches.get_one::<String>("file_path").unwrap()))?;
+            let fields = exif::fields(&png)?;
+            match Self::format(matches)? {
+                Format::Table => print!("{}", output::exif_table(&fields)),
+                format => println!("{}", output::to_string(&fields, format)?),
+            }
+            return Ok(());
+        }
+        let categories = match matches.get_many::<String>("only") {
+            Some(names) => names.map(|name| name.parse()).collect::<Result<Vec<Sensitive>>>()?,
+            None => Sensitive::ALL.to_vec(),
+        };
+        let output_file = Self::output_path(matches)?;
+        Self::run_batch(matches, |path| {
+            let mut png = stdio::read_png(path)?;
+            if exif::find(&png)?.is_none() {
+                return Ok(Outcome::Skipped("no eXIf chunk".to_string()));
+            }
+            let removed = exif::strip(&mut png, &categories)?;
+            if removed == 0 {
+                return Ok(Outcome::Skipped("no sensitive tags".to_string()));
+            }
+            stdio::write(output_file.as_deref().unwrap_or(path), &png.as_bytes())?;
+            Ok(Outcome::Succeeded(format!("removed {} tags", removed)))
+        })
+    }
+
+    fn handle_time(matches: &ArgMatches) -> Result<()> {
+        let (command, matches) = matches.subcommand().expect("a time subcommand is required");
+        if command == "get" {
+            let format = Self::format(matches)?;
+            if format == Format::Table {
+                return Self::run_batch(matches, |path| {
+                    let png = stdio::read_png(path)?;
+                    match png.get::<Time>()? {
+                        Some(time) => Ok(Outcome::Succeeded(time.to_string())),
+                        None => Ok(Outcome::Skipped("no tIME chunk".to_string())),
+                    }
+                });
+            }
+            let inputs: Vec<String> = matches.get_many::<String>("file_path").unwrap().cloned().collect();
+            let results = batch::run(&inputs, &Self::batch_options(matches), |path| {
+                match stdio::read_png(path)?.get::<Time>()? {
+                    Some(time) => Ok(Outcome::Succeeded(TimeReport { time: time.to_string() })),
+                    None => Ok(Outcome::Skipped("no tIME chunk".to_string())),
+                }
+            });
+            let reports: Vec<FileReport<TimeReport>> = results.iter().map(FileReport::new).collect();
+            if reports.len() == 1 {
+                println!("{}", output::to_string(&reports[0], format)?);
+            } else {
+                println!("{}", output::to_string(&reports, format)?);
+            }
+            let failed = Summary::new(&results).failed;
+            if failed > 0 {
+                return Err(anyhow!("{} of {} files failed", failed, results.len()));
+            }
+            return Ok(());
+        }
+        let time = match matches.try_get_one::<String>("at").ok().flatten() {
+            Some(at) => Some(at.parse::<Time>()?),
+            None if command == "set" => Some(Time::now()),
+            None => None,
+        };
+        let output_file = Self::output_path(matches)?;
+        Self::run_batch(matches, |path| {
+            let mut png = stdio::read_png(path)?;
+            let report = match time {
+                Some(time) => {
+                    png.set(&time)?;
+                    format!("time set to {}", time)
+                },
+                None => {
+                    if png.positions_of(&ChunkType::TIME).is_empty() {
+                        return Ok(Outcome::Skipped("no tIME chunk".to_string()));
+                    }
+                    png.retain_chunks(|chunk| chunk.chunk_type() != ChunkType::TIME);
+                    "time removed".to_string()
+                },
+            };
+            stdio::write(output_file.as_deref().unwrap_or(path), &png.as_bytes())?;
+            Ok(Outcome::Succeeded(report))
+        })
+    }
+
+    fn handle_strip(matches: &ArgMatches) -> Result<()> {
+        let private_only = matches.contains_id("private");
+        let trailing = matches.contains_id("trailing");
+        let output_file = Self::output_path(matches)?;
+        Self::run_batch(matches, |path| {
+            let mut png = stdio::read_png(path)?;
+            let before = png.chunks().len();
+            png.retain_chunks(|chunk| {
+                let chunk_type = chunk.chunk_type();
+                chunk_type.is_critical() || (private_only && chunk_type.is_public())
+            });
+            let removed = before - png.chunks().len();
+            let trailing_removed = if trailing { png.take_trailing_data().len() } else { 0 };
+            if removed == 0 && trailing_removed == 0 {
+                return Ok(Outcome::Skipped("nothing to strip".to_string()));
+            }
+            stdio::write(output_file.as_deref().unwrap_or(path), &png.as_bytes())?;
+            let mut report = format!("removed {} chunks", removed);
+            if trailing_removed > 0 {
+                report.push_str(&format!(" and {} trailing bytes", trailing_removed));
+            }
+            Ok(Outcome::Succeeded(report))
+        })
+    }
+
+    fn handle_trailing(matches: &ArgMatches) -> Result<()> {
+        let path_buf = PathBuf::from(matches.get_one::<String>("file_path").unwrap());
+        let mut png = stdio::read_png(&path_buf)?;
+        let output_file = Self::output_path(matches)?.unwrap_or_else(|| path_buf.clone());
+        let extract = matches.get_one::<String>("extract").map(PathBuf::from);
+        let format = Self::format(matches)?;
+        let trailing = TrailingData { offset: png.trailing_offset(), length: png.trailing_data().len() };
+        if format != Format::Table && extract.is_none() && !matches.contains_id("strip") {
+            println!("{}", output::to_string(&trailing, format)?);
+            return Ok(());
+        }
+        if trailing.length == 0 {
+            Self::status(&output_file, "no data after IEND");
+            return Ok(());
+        }
+        if extract.is_none() && !matches.contains_id("strip") {
+            println!("{} bytes after IEND at offset {}", trailing.length, trailing.offset);
+            return Ok(());
+        }
+        if let Some(extract) = extract.as_ref() {
+            stdio::write(extract, png.trailing_data())?;
+        }
+        if matches.contains_id("strip") {
+            let length = png.take_trailing_data().len();
+            stdio::write(&output_file, &png.as_bytes())?;
+            // keep stdout clean when the extracted data goes there
+            let status_to = extract.filter(|extract| stdio::is_stdio(extract)).unwrap_or(output_file);
+            Self::status(&status_to, format!("removed {} bytes after IEND", length));
+        }
         Ok(())
     }
 
-    fn handle_print(matches: &ArgMatches) -> Result<()> {
+    fn handle_validate(matches: &ArgMatches) -> Result<()> {
+        let format = Self::format(matches)?;
+        if format == Format::Table {
+            return Self::run_batch(matches, |path| {
+                let png = stdio::read_png(path)?;
+                let problems = validate::validate(&png);
+                if problems.is_empty() {
+                    Ok(Outcome::Succeeded("valid".to_string()))
+                } else {
+                    Err(anyhow!("invalid: {}", problems.join("; ")))
+                }
+            });
+        }
+        let inputs: Vec<String> = matches.get_many::<String>("file_path").unwrap().cloned().collect();
+        let results = batch::run(&inputs, &Self::batch_options(matches), |path| {
+            let problems = validate::validate(&stdio::read_png(path)?);
+            Ok(Outcome::Succeeded(Validation { valid: problems.is_empty(), problems }))
+        });
+        let reports: Vec<FileReport<Validation>> = results.iter().map(FileReport::new).collect();
+        if reports.len() == 1 {
+            println!("{}", output::to_string(&reports[0], format)?);
+        } else {
+            println!("{}", output::to_string(&reports, format)?);
+        }
+        // invalid files fail the command as they do with table output
+        let failed = reports.iter()
+            .filter(|report| report.error.is_some() || matches!(&report.report, Some(validation) if !validation.valid))
+            .count();
+        if failed > 0 {
+            return Err(anyhow!("{} of {} files failed", failed, results.len()));
+        }
+        Ok(())
+    }
+
+    fn handle_inspect(matches: &ArgMatches) -> Result<()> {
+        let path_buf = PathBuf::from(matches.get_one::<String>("file_path").unwrap());
+        let png = if matches.contains_id("strict") {
+            stdio::read_png(&path_buf)?
+        } else {
+            let recovery = Recovery::from_bytes(&stdio::read(&path_buf)?);
+            recovery.issues.iter().for_each(|issue| eprintln!("{}", issue));
+            recovery.png
+        };
+        let options = InspectOptions {
+            full: matches.contains_id("full"),
+            color: std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        };
+        match Self::format(matches)? {
+            Format::Table => print!("{}", inspect::inspect(&png, &options)),
+            format => println!("{}", output::to_string(&ChunkInspection::all(&png, &options), format)?),
+        }
+        Ok(())
+    }
+
+    fn handle_diff(matches: &ArgMatches) -> Result<()> {
+        let old = stdio::read_png(Path::new(matches.get_one::<String>("old_file").unwrap()))?;
+        let new = stdio::read_png(Path::new(matches.get_one::<String>("new_file").unwrap()))?;
+        let mut diff = PngDiff::new(&old, &new);
+        if matches.contains_id("pixels") {
+            diff = diff.with_pixels(&old, &new);
+        }
+        match Self::format(matches)? {
+            Format::Table => print!("{}", diff),
+            format => println!("{}", output::to_string(&diff, format)?),
+        }
+        Ok(())
+    }
+
+    fn handle_detect(matches: &ArgMatches) -> Result<()> {
+        let options = DetectOptions { pixels: matches.contains_id("pixels") };
+        let inputs: Vec<String> = matches.get_many::<String>("file_path").unwrap().cloned().collect();
+        let results = batch::run(&inputs, &Self::batch_options(matches), |path| {
+            Ok(Outcome::Succeeded(detect::detect(&stdio::read(path)?, &options)))
+        });
+        let reports: Vec<detect::FileReport> = results.iter().map(FileReport::new).collect();
+        match Self::format(matches)? {
+            Format::Table => reports.iter().for_each(|report| println!("{}", report)),
+            format if reports.len() == 1 => println!("{}", output::to_string(&reports[0], format)?),
+            format => println!("{}", output::to_string(&reports, format)?),
+        }
+        let failed = Summary::new(&results).failed;
+        if failed > 0 {
+            return Err(anyhow!("{} of {} files failed", failed, results.len()));
+        }
+        Ok(())
+    }
+
+    fn handle_repair(matches: &ArgMatches) -> Result<()> {
         let path_buf = PathBuf::from(matches.get_one::<String>("file_path").unwrap());
-        let png = Png::from_file(path_buf)?;
-        png.chunks().iter()
-            .for_each(
-                |chunk|
-                    println!("{:?}", chunk.chunk_type())
-            );
+        let recovery = Recovery::from_bytes(&stdio::read(&path_buf)?);
+        let output_file = Self::output_path(matches)?.unwrap_or(path_buf);
+        if recovery.is_clean() {
+            Self::status(&output_file, "nothing to repair");
+            return Ok(());
+        }
+        recovery.issues.iter().for_each(|issue| Self::status(&output_file, issue));
+        if matches.contains_id("dry-run") {
+            return Ok(());
+        }
+
+        let mut png = recovery.repaired();
+        if matches.contains_id("drop-trailing") {
+            png.take_trailing_data();
+        }
+        stdio::write(&output_file, &png.as_bytes())?;
+        Self::status(&output_file, format!("repaired file written to {}", output_file.display()));
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn handle_dump(matches: &ArgMatches) -> Result<()> {
+        let path_buf = PathBuf::from(matches.get_one::<String>("file_path").unwrap());
+        let png = stdio::read_png(&path_buf)?;
+        println!("{}", output::to_string(&png, Self::format(matches)?)?);
+        Ok(())
+    }
+
+    fn handle_build(matches: &ArgMatches) -> Result<()> {
+        let path_buf = PathBuf::from(matches.get_one::<String>("dump_path").unwrap());
+        let format = match path_buf.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Format::Json,
+            // YAML is a superset of JSON, so it also covers dumps piped in on stdin
+            _ => Format::Yaml,
+        };
+        let png = output::png_from_dump(&String::from_utf8(stdio::read(&path_buf)?)?, format)?;
+        let output_file = Self::output_path(matches)?.unwrap_or_else(|| PathBuf::from(stdio::STDIO_PATH));
+        stdio::write(&output_file, &png.as_bytes())
+    }
+
+    fn handle_convert(matches: &ArgMatches) -> Result<()> {
+        let input_file = PathBuf::from(matches.get_one::<String>("input_file").unwrap());
+        let output_file = PathBuf::from(matches.get_one::<String>("output_file").unwrap());
+        let format = match matches.get_one::<String>("to") {
+            Some(format) => format.parse()?,
+            None => ImageFormat::from_path(&output_file)
+                .ok_or_else(|| anyhow!("cannot tell the output format of {}, use --to", output_file.display()))?,
+        };
+        let size = matches.get_one::<String>("size").map(|size| convert::parse_size(size)).transpose()?;
+        let source = Source::read(&stdio::read(&input_file)?, size)?;
+        stdio::write(&output_file, &source.write(format, matches.get_one::<u8>("depth").copied())?)
+    }
+
+    /// Prints a status message for a command writing its png to `output`,
+    /// on stderr if that is stdout.
+    fn status<T: Display>(output: &Path, message: T) {
+        if stdio::is_stdio(output) {
+            eprintln!("{}", message);
+        } else {
+            println!("{}", message);
+        }
+    }
+}
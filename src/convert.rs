@@ -0,0 +1,130 @@
+//! Converting images between png, the Netpbm formats and raw RGBA samples.
+
+use std::convert::TryFrom;
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunks::ColorType;
+use crate::netpbm::{self, Netpbm};
+use crate::pixels::Rgba16;
+use crate::png::Png;
+
+/// A format `convert` reads or writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Netpbm(Netpbm),
+    /// Headerless RGBA samples in row-major order, 8 bits each or 16 bits
+    /// big-endian.
+    Raw,
+}
+
+impl ImageFormat {
+    pub const NAMES: [&'static str; 6] = ["png", "pbm", "pgm", "ppm", "pam", "raw"];
+
+    /// The format named by the extension of `path`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "rgba" => Some(ImageFormat::Raw),
+            name => name.parse().ok(),
+        }
+    }
+
+    /// The format of `bytes`, known by its signature. Raw samples have none.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&Png::STANDARD_HEADER) {
+            return Some(ImageFormat::Png);
+        }
+        Netpbm::detect(bytes).map(ImageFormat::Netpbm)
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "png" => Ok(ImageFormat::Png),
+            "raw" => Ok(ImageFormat::Raw),
+            _ => s.parse().map(ImageFormat::Netpbm)
+                .map_err(|_| anyhow!("unknown image format {}, expected one of {:?}", s, ImageFormat::NAMES)),
+        }
+    }
+}
+
+/// Parses a `WIDTHxHEIGHT` image size.
+pub fn parse_size(s: &str) -> Result<(u32, u32)> {
+    let invalid = || anyhow!("invalid size {:?}, expected WIDTHxHEIGHT", s);
+    let (width, height) = s.split_once('x').ok_or_else(invalid)?;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Pixels read by `convert`, with the png color type and bit depth that
+/// hold them without loss.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    pub image: Rgba16,
+    pub color_type: ColorType,
+    pub bit_depth: u8,
+}
+
+impl Source {
+    /// Reads a png or Netpbm image, or raw samples of the given `size`
+    /// whose depth follows from the length of `bytes`.
+    pub fn read(bytes: &[u8], size: Option<(u32, u32)>) -> Result<Self> {
+        if let Some((width, height)) = size {
+            return Source::read_raw(bytes, width, height);
+        }
+        match ImageFormat::detect(bytes) {
+            Some(ImageFormat::Png) => {
+                let png = Png::try_from(bytes)?;
+                let ihdr = png.ihdr()?;
+                let image = Rgba16::from_png(&png, None)?;
+                Ok(Source { image, color_type: ihdr.color_type, bit_depth: ihdr.bit_depth })
+            },
+            Some(ImageFormat::Netpbm(_)) => {
+                let decoded = netpbm::decode(bytes)?;
+                Ok(Source { image: decoded.image, color_type: decoded.color_type, bit_depth: decoded.bit_depth })
+            },
+            _ => Err(anyhow!("input is neither a png nor a binary netpbm image, raw input needs a size")),
+        }
+    }
+
+    fn read_raw(bytes: &[u8], width: u32, height: u32) -> Result<Self> {
+        let samples = 4 * width as usize * height as usize;
+        let (data, bit_depth) = if bytes.len() == samples {
+            (bytes.iter().map(|&v| v as u16 * 257).collect(), 8)
+        } else if bytes.len() == 2 * samples {
+            (bytes.chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect(), 16)
+        } else {
+            return Err(anyhow!("raw input is {} bytes, a {}x{} rgba image has {} or {}",
+                               bytes.len(), width, height, samples, 2 * samples));
+        };
+        Ok(Source { image: Rgba16 { width, height, data }, color_type: ColorType::Rgba, bit_depth })
+    }
+
+    /// Writes the pixels as `format`. `bit_depth` defaults to the source's,
+    /// and Netpbm and raw output use 16 bits for 16-bit sources and 8 bits
+    /// otherwise.
+    pub fn write(&self, format: ImageFormat, bit_depth: Option<u8>) -> Result<Vec<u8>> {
+        let sample_depth = bit_depth.unwrap_or(if self.bit_depth == 16 { 16 } else { 8 });
+        match format {
+            ImageFormat::Png => {
+                let png = self.image.to_png(self.color_type, bit_depth.unwrap_or(self.bit_depth))?;
+                Ok(png.as_bytes())
+            },
+            ImageFormat::Netpbm(format) => netpbm::encode(&self.image, format, sample_depth),
+            ImageFormat::Raw => match sample_depth {
+                8 => Ok(self.image.to_rgba8().data),
+                16 => Ok(self.image.data.iter().flat_map(|v| v.to_be_bytes()).collect()),
+                _ => Err(anyhow!("raw samples must be 8 or 16 bits, got {}", sample_depth)),
+            },
+        }
+    }
+}
@@ -0,0 +1,432 @@
+//! Steganalysis: heuristics that flag pngs likely to carry hidden data.
+//!
+//! Structural checks look at the chunks and at anything after IEND. With
+//! `DetectOptions::pixels`, the least significant bits of 8-bit samples are
+//! also tested with the chi-square attack and RS analysis.
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::batch;
+use crate::chunk::Chunk;
+use crate::chunks::ColorType;
+use crate::image::RawImage;
+use crate::png::Png;
+use crate::recovery::{Issue, Recovery};
+use crate::trailer;
+
+/// Text chunks longer than this are reported.
+pub const TEXT_LIMIT: usize = 4096;
+/// Payloads shorter than this are too small for a meaningful entropy.
+const ENTROPY_MIN_LENGTH: usize = 256;
+/// Bits per byte above which an uncompressed payload looks encrypted.
+const ENTROPY_LIMIT: f64 = 7.2;
+/// Chi-square p-values above this suggest LSB embedding...
+const CHI_SQUARE_LIMIT: f64 = 0.95;
+/// ...unless the pairs (2k+1, 2k+2), which LSB replacement leaves alone, are
+/// about as even, as in any image with a smooth histogram.
+const CHI_SQUARE_CONTROL_LIMIT: f64 = 0.5;
+/// Estimated embedding rates above this are reported by RS analysis.
+const RS_LIMIT: f64 = 0.15;
+
+/// Private chunks written by common editors and platforms.
+const BENIGN_PRIVATE: [&[u8; 4]; 11] = [
+    b"caNv", b"mkBF", b"mkBS", b"mkBT", b"mkTS", b"npLb", b"npOl", b"npTc", b"prVW", b"skMf", b"vpAg",
+];
+/// Chunk types pngme writes its own messages to.
+const PNGME_TYPES: [(&[u8; 4], &str); 2] = [
+    (b"meSg", "pngme named message"),
+    (b"ruSt", "pngme default chunk type"),
+];
+/// Ancillary chunks whose payload is compressed, and so always high entropy.
+const COMPRESSED: [&[u8; 4]; 3] = [b"iCCP", b"iTXt", b"zTXt"];
+const TEXT_TYPES: [&[u8; 4]; 3] = [b"iTXt", b"tEXt", b"zTXt"];
+/// Magic bytes of files commonly smuggled inside or after an image.
+const SIGNATURES: [(&str, &[u8]); 6] = [
+    ("pngme trailer", trailer::MAGIC),
+    ("zip archive", b"PK\x03\x04"),
+    ("rar archive", b"Rar!\x1a\x07"),
+    ("7z archive", b"7z\xbc\xaf\x27\x1c"),
+    ("png image", &Png::STANDARD_HEADER),
+    ("pgp message", b"-----BEGIN PGP"),
+];
+
+/// Something suspicious found in a file.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum Finding {
+    /// A private ancillary chunk not written by any software we know of.
+    PrivateChunk { offset: usize, chunk_type: String, length: usize },
+    /// Bytes of a known tool or file format.
+    Signature { offset: usize, name: String },
+    /// Bytes after IEND.
+    TrailingData { offset: usize, length: usize, entropy: f64 },
+    /// A text chunk longer than `TEXT_LIMIT`.
+    OversizedText { offset: usize, chunk_type: String, length: usize },
+    /// An uncompressed ancillary payload that looks encrypted or compressed.
+    HighEntropy { offset: usize, chunk_type: String, length: usize, entropy: f64 },
+    /// The chi-square attack found the pairs of values differing only in
+    /// their LSB to be suspiciously equal in a channel.
+    ChiSquare { channel: usize, p_value: f64 },
+    /// RS analysis estimates that this fraction of a channel's LSBs was replaced.
+    RsAnalysis { channel: usize, rate: f64 },
+}
+
+impl Finding {
+    /// How much this finding adds to the risk score.
+    pub fn weight(&self) -> u32 {
+        match self {
+            Finding::Signature { .. } => 50,
+            Finding::TrailingData { .. } => 40,
+            Finding::ChiSquare { .. } | Finding::RsAnalysis { .. } => 35,
+            Finding::PrivateChunk { .. } | Finding::HighEntropy { .. } => 25,
+            Finding::OversizedText { .. } => 15,
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Finding::PrivateChunk { offset, chunk_type, length } =>
+                write!(f, "unknown private chunk {} at offset {}, {} bytes", chunk_type, offset, length),
+            Finding::Signature { offset, name } => write!(f, "{} at offset {}", name, offset),
+            Finding::TrailingData { offset, length, entropy } =>
+                write!(f, "{} bytes after IEND at offset {}, entropy {:.2}", length, offset, entropy),
+            Finding::OversizedText { offset, chunk_type, length } =>
+                write!(f, "{} at offset {} holds {} bytes of text", chunk_type, offset, length),
+            Finding::HighEntropy { offset, chunk_type, length, entropy } =>
+                write!(f, "{} at offset {} has entropy {:.2} over {} bytes", chunk_type, offset, entropy, length),
+            Finding::ChiSquare { channel, p_value } =>
+                write!(f, "chi-square attack on channel {}: p = {:.3}", channel, p_value),
+            Finding::RsAnalysis { channel, rate } =>
+                write!(f, "RS analysis on channel {}: estimated embedding rate {:.3}", channel, rate),
+        }
+    }
+}
+
+/// LSB statistics of one color channel.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ChannelStats {
+    pub channel: usize,
+    pub chi_square_p: f64,
+    /// The same test over the pairs (2k+1, 2k+2).
+    pub control_p: f64,
+    pub rs_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DetectOptions {
+    /// Decode the image and run the LSB tests.
+    pub pixels: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Report {
+    /// 0 to 100, the capped sum of the findings' weights.
+    pub risk: u32,
+    pub findings: Vec<Finding>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub channels: Vec<ChannelStats>,
+    /// Checks that could not be run, and why.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub notes: Vec<String>,
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "risk {}/100", self.risk)?;
+        for finding in self.findings.iter() {
+            write!(f, "\n  {}", finding)?;
+        }
+        for note in self.notes.iter() {
+            write!(f, "\n  note: {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+/// Scans the bytes of a file. Damaged files are parsed leniently so that
+/// they are still scanned rather than rejected.
+pub fn detect(content: &[u8], options: &DetectOptions) -> Report {
+    let recovery = Recovery::from_bytes(content);
+    let mut findings = Vec::new();
+    let mut notes = Vec::new();
+
+    for (offset, chunk) in recovery.png.chunks_with_offsets() {
+        check_chunk(offset, chunk, &mut findings);
+    }
+    for issue in recovery.issues.iter() {
+        match *issue {
+            Issue::TrailingData { offset, length } => {
+                let trailing = &content[offset..offset + length];
+                findings.push(Finding::TrailingData { offset, length, entropy: entropy(trailing) });
+                find_signatures(offset, trailing, &mut findings);
+            },
+            Issue::InvalidHeader => notes.push("file does not start with the PNG signature".to_string()),
+            _ => {},
+        }
+    }
+
+    let mut channels = Vec::new();
+    if options.pixels {
+        match lsb_samples(&recovery.png) {
+            Ok(samples) => {
+                for (channel, samples) in samples.iter().enumerate() {
+                    let stats = ChannelStats {
+                        channel,
+                        chi_square_p: chi_square_p(samples),
+                        control_p: pairs_p(samples, 1),
+                        rs_rate: rs_rate(samples),
+                    };
+                    if stats.chi_square_p > CHI_SQUARE_LIMIT && stats.control_p < CHI_SQUARE_CONTROL_LIMIT {
+                        findings.push(Finding::ChiSquare { channel, p_value: stats.chi_square_p });
+                    }
+                    if let Some(rate) = stats.rs_rate.filter(|&rate| rate > RS_LIMIT) {
+                        findings.push(Finding::RsAnalysis { channel, rate });
+                    }
+                    channels.push(stats);
+                }
+            },
+            Err(reason) => notes.push(format!("pixel analysis skipped: {}", reason)),
+        }
+    }
+
+    let risk = findings.iter().map(Finding::weight).sum::<u32>().min(100);
+    Report { risk, findings, channels, notes }
+}
+
+fn check_chunk(offset: usize, chunk: &Chunk, findings: &mut Vec<Finding>) {
+    let chunk_type = chunk.chunk_type();
+    if chunk_type.is_critical() {
+        return;
+    }
+    let bytes = chunk_type.bytes();
+    let data = chunk.data();
+    let data_offset = offset + 8;
+
+    if let Some((_, name)) = PNGME_TYPES.iter().find(|(known, _)| **known == bytes) {
+        findings.push(Finding::Signature { offset, name: format!("{} {}", name, chunk_type) });
+    } else if !chunk_type.is_public() && !BENIGN_PRIVATE.contains(&&bytes) {
+        findings.push(Finding::PrivateChunk { offset, chunk_type: chunk_type.to_string(), length: data.len() });
+    }
+    if TEXT_TYPES.contains(&&bytes) && data.len() > TEXT_LIMIT {
+        findings.push(Finding::OversizedText { offset, chunk_type: chunk_type.to_string(), length: data.len() });
+    }
+    if !COMPRESSED.contains(&&bytes) {
+        let entropy = entropy(data);
+        if data.len() >= ENTROPY_MIN_LENGTH && entropy > ENTROPY_LIMIT {
+            findings.push(Finding::HighEntropy { offset, chunk_type: chunk_type.to_string(), length: data.len(), entropy });
+        }
+        find_signatures(data_offset, data, findings);
+    }
+}
+
+fn find_signatures(offset: usize, data: &[u8], findings: &mut Vec<Finding>) {
+    for (name, magic) in SIGNATURES.iter() {
+        if let Some(pos) = data.windows(magic.len()).position(|window| window == *magic) {
+            findings.push(Finding::Signature { offset: offset + pos, name: name.to_string() });
+        }
+    }
+}
+
+/// Shannon entropy of `data` in bits per byte.
+pub fn entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    data.iter().for_each(|&b| counts[b as usize] += 1);
+    counts.iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / data.len() as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The samples of every color channel, alpha excluded.
+fn lsb_samples(png: &Png) -> std::result::Result<Vec<Vec<u8>>, String> {
+    let image = RawImage::decode(png).map_err(|e| e.to_string())?;
+    let ihdr = &image.ihdr;
+    if ihdr.bit_depth != 8 || ihdr.color_type == ColorType::Indexed {
+        return Err("only 8-bit grayscale and truecolor images are analyzed".to_string());
+    }
+    let channels = ihdr.color_type.channels();
+    let colors = match ihdr.color_type {
+        ColorType::GrayscaleAlpha => 1,
+        ColorType::Rgba => 3,
+        _ => channels,
+    };
+    Ok((0..colors)
+        .map(|channel| image.data.iter().skip(channel).step_by(channels).copied().collect())
+        .collect())
+}
+
+/// Westfeld and Pfitzmann's chi-square attack: the probability that the
+/// values 2k and 2k+1 are as evenly used as LSB replacement leaves them.
+pub fn chi_square_p(samples: &[u8]) -> f64 {
+    pairs_p(samples, 0)
+}
+
+/// The chi-square test over the pairs of values (2k + shift, 2k + shift + 1).
+fn pairs_p(samples: &[u8], shift: usize) -> f64 {
+    let mut histogram = [0u64; 256];
+    samples.iter().for_each(|&s| histogram[s as usize] += 1);
+    let mut chi_square = 0.0;
+    let mut pairs = 0;
+    for pair in histogram[shift..].chunks_exact(2) {
+        let expected = (pair[0] + pair[1]) as f64 / 2.0;
+        // the test is unreliable for sparsely used pairs
+        if expected < 5.0 {
+            continue;
+        }
+        chi_square += (pair[0] as f64 - expected).powi(2) / expected;
+        pairs += 1;
+    }
+    if pairs < 2 {
+        return 0.0;
+    }
+    1.0 - gamma_p((pairs - 1) as f64 / 2.0, chi_square / 2.0)
+}
+
+/// Fridrich's RS analysis: estimates the fraction of samples whose LSB was
+/// replaced, from how flipping LSBs changes the smoothness of groups of
+/// four samples. `None` if there are too few samples.
+pub fn rs_rate(samples: &[u8]) -> Option<f64> {
+    if samples.len() < 400 {
+        return None;
+    }
+    let (r_m, s_m, r_n, s_n) = rs_groups(samples, false);
+    let (r_m1, s_m1, r_n1, s_n1) = rs_groups(samples, true);
+    let (d0, d1) = (r_m - s_m, r_m1 - s_m1);
+    let (dn0, dn1) = (r_n - s_n, r_n1 - s_n1);
+    let a = 2.0 * (d1 + d0);
+    let b = dn0 - dn1 - d1 - 3.0 * d0;
+    let c = d0 - dn0;
+
+    let z = if a.abs() < f64::EPSILON {
+        if b.abs() < f64::EPSILON {
+            return Some(0.0);
+        }
+        -c / b
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return Some(0.0);
+        }
+        let (z1, z2) = ((-b + discriminant.sqrt()) / (2.0 * a), (-b - discriminant.sqrt()) / (2.0 * a));
+        if z1.abs() < z2.abs() { z1 } else { z2 }
+    };
+    Some((z / (z - 0.5)).clamp(0.0, 1.0))
+}
+
+/// Relative counts of regular and singular groups under the mask
+/// [0, 1, 1, 0] and its negation, optionally with every LSB flipped first.
+fn rs_groups(samples: &[u8], flip_all: bool) -> (f64, f64, f64, f64) {
+    const MASK: [bool; 4] = [false, true, true, false];
+    let flip = |x: i32| x ^ 1;
+    let flip_negative = |x: i32| ((x + 1) ^ 1) - 1;
+    let smoothness = |group: &[i32; 4]| group.windows(2).map(|w| (w[1] - w[0]).abs()).sum::<i32>();
+
+    let (mut r_m, mut s_m, mut r_n, mut s_n) = (0, 0, 0, 0);
+    let groups = samples.chunks_exact(4);
+    let total = groups.len() as f64;
+    for group in groups {
+        let group: [i32; 4] = std::array::from_fn(|i| {
+            let sample = group[i] as i32;
+            if flip_all { flip(sample) } else { sample }
+        });
+        let original = smoothness(&group);
+        let positive = smoothness(&std::array::from_fn(|i| if MASK[i] { flip(group[i]) } else { group[i] }));
+        let negative = smoothness(&std::array::from_fn(|i| if MASK[i] { flip_negative(group[i]) } else { group[i] }));
+        match positive.cmp(&original) {
+            std::cmp::Ordering::Greater => r_m += 1,
+            std::cmp::Ordering::Less => s_m += 1,
+            std::cmp::Ordering::Equal => {},
+        }
+        match negative.cmp(&original) {
+            std::cmp::Ordering::Greater => r_n += 1,
+            std::cmp::Ordering::Less => s_n += 1,
+            std::cmp::Ordering::Equal => {},
+        }
+    }
+    (r_m as f64 / total, s_m as f64 / total, r_n as f64 / total, s_n as f64 / total)
+}
+
+/// The regularized lower incomplete gamma function P(a, x), which is the
+/// CDF of the chi-square distribution with 2a degrees of freedom at 2x.
+fn gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let prefix = (-x + a * x.ln() - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        // series expansion
+        let (mut term, mut sum, mut n) = (1.0 / a, 1.0 / a, a);
+        for _ in 0..1000 {
+            n += 1.0;
+            term *= x / n;
+            sum += term;
+            if term.abs() < sum.abs() * 1e-14 {
+                break;
+            }
+        }
+        (sum * prefix).min(1.0)
+    } else {
+        // continued fraction for the upper function, by Lentz's method
+        let tiny = 1e-300;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            h *= delta;
+            if (delta - 1.0).abs() < 1e-14 {
+                break;
+            }
+        }
+        (1.0 - prefix * h).max(0.0)
+    }
+}
+
+/// ln Γ(x) for x > 0, by the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - 1.0;
+    let sum = COEFFICIENTS.iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64));
+    let t = x + G + 0.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The `detect` entry of one input file.
+pub type FileReport = batch::FileReport<Report>;
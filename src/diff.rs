@@ -0,0 +1,276 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{Ihdr, Text};
+use crate::image::RawImage;
+use crate::png::Png;
+
+/// One difference between the chunk lists of two files. Indices refer to
+/// positions in the respective chunk lists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "change", rename_all = "snake_case"))]
+pub enum Change {
+    Added { index: usize, chunk_type: String, length: u32 },
+    Removed { index: usize, chunk_type: String, length: u32 },
+    Modified { old_index: usize, new_index: usize, chunk_type: String, details: Vec<String> },
+    Moved { old_index: usize, new_index: usize, chunk_type: String },
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added { index, chunk_type, length } =>
+                write!(f, "+ {} at #{} ({} bytes)", chunk_type, index, length),
+            Change::Removed { index, chunk_type, length } =>
+                write!(f, "- {} at #{} ({} bytes)", chunk_type, index, length),
+            Change::Modified { old_index, new_index, chunk_type, details } =>
+                write!(f, "~ {} #{} -> #{}: {}", chunk_type, old_index, new_index, details.join(", ")),
+            Change::Moved { old_index, new_index, chunk_type } =>
+                write!(f, "> {} moved from #{} to #{}", chunk_type, old_index, new_index),
+        }
+    }
+}
+
+/// Outcome of comparing the decoded pixels of two files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "result", rename_all = "snake_case"))]
+pub enum PixelComparison {
+    Identical,
+    Different { differing_bytes: usize, total_bytes: usize },
+    Incomparable { reason: String },
+}
+
+impl PixelComparison {
+    pub fn new(old: &Png, new: &Png) -> Self {
+        let (old, new) = match (RawImage::decode(old), RawImage::decode(new)) {
+            (Ok(old), Ok(new)) => (old, new),
+            (Err(e), _) | (_, Err(e)) => return PixelComparison::Incomparable { reason: e.to_string() },
+        };
+        let (mut old_ihdr, mut new_ihdr) = (old.ihdr.clone(), new.ihdr.clone());
+        old_ihdr.interlace_method = 0;
+        new_ihdr.interlace_method = 0;
+        if old_ihdr != new_ihdr {
+            return PixelComparison::Incomparable {
+                reason: format!("image format differs: {} vs {}", old.ihdr, new.ihdr),
+            };
+        }
+        let differing_bytes = old.data.iter()
+            .zip(new.data.iter())
+            .filter(|(a, b)| a != b)
+            .count();
+        match differing_bytes {
+            0 => PixelComparison::Identical,
+            _ => PixelComparison::Different { differing_bytes, total_bytes: old.data.len() },
+        }
+    }
+}
+
+impl Display for PixelComparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PixelComparison::Identical => write!(f, "pixels: identical"),
+            PixelComparison::Different { differing_bytes, total_bytes } =>
+                write!(f, "pixels: {} of {} bytes differ", differing_bytes, total_bytes),
+            PixelComparison::Incomparable { reason } => write!(f, "pixels: not comparable, {}", reason),
+        }
+    }
+}
+
+/// Chunk-level differences between two files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PngDiff {
+    pub unchanged: usize,
+    pub changes: Vec<Change>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub pixels: Option<PixelComparison>,
+}
+
+impl PngDiff {
+    /// Aligns the chunk lists of `old` and `new` on their longest common
+    /// subsequence of (type, CRC) pairs and classifies what is left over.
+    pub fn new(old: &Png, new: &Png) -> Self {
+        let (old, new) = (old.chunks(), new.chunks());
+        let matched = longest_common_subsequence(old, new);
+        let (mut old_matched, mut new_matched) = (vec![false; old.len()], vec![false; new.len()]);
+        for &(i, j) in matched.iter() {
+            old_matched[i] = true;
+            new_matched[j] = true;
+        }
+        let mut old_left: Vec<usize> = (0..old.len()).filter(|&i| !old_matched[i]).collect();
+        let mut new_left: Vec<usize> = (0..new.len()).filter(|&j| !new_matched[j]).collect();
+
+        let mut changes = Vec::new();
+        // identical chunks outside the common subsequence were reordered
+        take_pairs(&mut old_left, &mut new_left, |i, j| same_chunk(&old[i], &new[j]))
+            .into_iter()
+            .for_each(|(i, j)| changes.push(Change::Moved {
+                old_index: i,
+                new_index: j,
+                chunk_type: old[i].chunk_type().to_string(),
+            }));
+        // chunks that play the same role in both files were edited
+        take_pairs(&mut old_left, &mut new_left, |i, j| identity(&old[i]) == identity(&new[j]))
+            .into_iter()
+            .for_each(|(i, j)| changes.push(Change::Modified {
+                old_index: i,
+                new_index: j,
+                chunk_type: old[i].chunk_type().to_string(),
+                details: describe_changes(&old[i], &new[j]),
+            }));
+        old_left.into_iter().for_each(|i| changes.push(Change::Removed {
+            index: i,
+            chunk_type: old[i].chunk_type().to_string(),
+            length: old[i].length(),
+        }));
+        new_left.into_iter().for_each(|j| changes.push(Change::Added {
+            index: j,
+            chunk_type: new[j].chunk_type().to_string(),
+            length: new[j].length(),
+        }));
+
+        PngDiff {
+            unchanged: matched.len(),
+            changes,
+            pixels: None,
+        }
+    }
+
+    /// Also decodes and compares the pixels of both files.
+    pub fn with_pixels(mut self, old: &Png, new: &Png) -> Self {
+        self.pixels = Some(PixelComparison::new(old, new));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for PngDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for change in self.changes.iter() {
+            writeln!(f, "{}", change)?;
+        }
+        writeln!(f, "{} chunks unchanged, {} changes", self.unchanged, self.changes.len())?;
+        if let Some(pixels) = &self.pixels {
+            writeln!(f, "{}", pixels)?;
+        }
+        Ok(())
+    }
+}
+
+fn same_chunk(a: &Chunk, b: &Chunk) -> bool {
+    a.chunk_type() == b.chunk_type() && a.crc() == b.crc()
+}
+
+/// What a chunk is "about": its type, plus the keyword for text chunks so
+/// that editing one tEXt entry is not reported against another.
+fn identity(chunk: &Chunk) -> (ChunkType, Option<String>) {
+    let keyword = match chunk.chunk_type() {
+        ChunkType::TEXT => Text::try_from(chunk.data()).ok().map(|text| text.keyword),
+        _ => None,
+    };
+    (chunk.chunk_type(), keyword)
+}
+
+/// Removes and returns, in order, the first pairs of leftover indices that
+/// satisfy `matches`.
+fn take_pairs<F>(old_left: &mut Vec<usize>, new_left: &mut Vec<usize>, matches: F) -> Vec<(usize, usize)>
+    where F: Fn(usize, usize) -> bool
+{
+    let mut pairs = Vec::new();
+    old_left.retain(|&i| {
+        match new_left.iter().position(|&j| matches(i, j)) {
+            Some(pos) => {
+                pairs.push((i, new_left.remove(pos)));
+                false
+            },
+            None => true,
+        }
+    });
+    pairs
+}
+
+/// Index pairs of a longest common subsequence of `old` and `new`. The
+/// common prefix and suffix, usually most of the file, are matched directly
+/// so that only the chunks in between go through the quadratic table.
+fn longest_common_subsequence(old: &[Chunk], new: &[Chunk]) -> Vec<(usize, usize)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| same_chunk(a, b)).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| same_chunk(a, b))
+        .count();
+    let (n, m) = (old.len() - prefix - suffix, new.len() - prefix - suffix);
+    let (old_middle, new_middle) = (&old[prefix..prefix + n], &new[prefix..prefix + m]);
+
+    // table[i * width + j] is the length of the subsequence of old_middle[i..] and new_middle[j..]
+    let width = m + 1;
+    let mut table = vec![0usize; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i * width + j] = if same_chunk(&old_middle[i], &new_middle[j]) {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if same_chunk(&old_middle[i], &new_middle[j]) {
+            pairs.push((prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs.extend((0..suffix).map(|k| (prefix + n + k, prefix + m + k)));
+    pairs
+}
+
+/// Human-readable differences between two chunks of the same type.
+fn describe_changes(old: &Chunk, new: &Chunk) -> Vec<String> {
+    let typed = match old.chunk_type() {
+        ChunkType::IHDR => match (Ihdr::try_from(old.data()), Ihdr::try_from(new.data())) {
+            (Ok(a), Ok(b)) => Some(ihdr_changes(&a, &b)),
+            _ => None,
+        },
+        ChunkType::TEXT => match (Text::try_from(old.data()), Text::try_from(new.data())) {
+            (Ok(a), Ok(b)) => Some(vec![format!("{} {:?} -> {:?}", a.keyword, a.text, b.text)]),
+            _ => None,
+        },
+        _ => None,
+    };
+    typed.filter(|details| !details.is_empty())
+        .unwrap_or_else(|| {
+            if old.length() == new.length() {
+                vec![format!("data changed ({} bytes)", old.length())]
+            } else {
+                vec![format!("length {} -> {}", old.length(), new.length())]
+            }
+        })
+}
+
+fn ihdr_changes(a: &Ihdr, b: &Ihdr) -> Vec<String> {
+    let fields = [
+        ("width", a.width.to_string(), b.width.to_string()),
+        ("height", a.height.to_string(), b.height.to_string()),
+        ("bit depth", a.bit_depth.to_string(), b.bit_depth.to_string()),
+        ("color type", format!("{:?}", a.color_type), format!("{:?}", b.color_type)),
+        ("compression method", a.compression_method.to_string(), b.compression_method.to_string()),
+        ("filter method", a.filter_method.to_string(), b.filter_method.to_string()),
+        ("interlace method", a.interlace_method.to_string(), b.interlace_method.to_string()),
+    ];
+    fields.iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(name, old, new)| format!("{} {} -> {}", name, old, new))
+        .collect()
+}
@@ -0,0 +1,93 @@
+//! Reading the eXIf chunk of a png and removing privacy-sensitive tags from
+//! it.
+
+use std::convert::TryFrom;
+use std::str::FromStr;
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk_type::ChunkType;
+use crate::chunks::Exif;
+use crate::chunks::exif::{Field, EXIF_IFD, GPS_IFD};
+use crate::png::Png;
+
+/// Tags that identify the camera or its owner. MakerNote is included as it
+/// is opaque and commonly repeats the serial numbers.
+const SERIAL_TAGS: [u16; 5] = [
+    0x927c, // MakerNote
+    0xa430, // CameraOwnerName
+    0xa431, // BodySerialNumber
+    0xa435, // LensSerialNumber
+    0xc62f, // CameraSerialNumber
+];
+
+/// Kinds of tags `strip` can remove.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sensitive {
+    /// The whole GPS IFD.
+    Gps,
+    /// Camera and lens serial numbers, the owner name and the maker note.
+    Serials,
+}
+
+impl Sensitive {
+    pub const NAMES: [&'static str; 2] = ["gps", "serials"];
+    pub const ALL: [Sensitive; 2] = [Sensitive::Gps, Sensitive::Serials];
+}
+
+impl FromStr for Sensitive {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "gps" => Ok(Sensitive::Gps),
+            "serials" => Ok(Sensitive::Serials),
+            _ => Err(anyhow!("unknown tag category {}, expected one of {:?}", s, Sensitive::NAMES)),
+        }
+    }
+}
+
+/// The parsed eXIf chunk of `png`, if it has one.
+pub fn find(png: &Png) -> Result<Option<Exif>> {
+    png.get::<Exif>()
+}
+
+/// Every tag of the eXIf chunk of `png`.
+pub fn fields(png: &Png) -> Result<Vec<Field>> {
+    let exif = find(png)?.ok_or_else(|| anyhow!("png has no eXIf chunk"))?;
+    Ok(exif.fields())
+}
+
+/// Removes the tags of `categories` from `exif`, returning how many were
+/// removed.
+pub fn remove_sensitive(exif: &mut Exif, categories: &[Sensitive]) -> usize {
+    let mut removed = 0;
+    for category in categories {
+        for ifd in std::iter::once(&mut exif.ifd0).chain(exif.ifd1.as_mut()) {
+            match category {
+                Sensitive::Gps => removed += ifd.remove_sub_ifd(GPS_IFD).map_or(0, |gps| gps.len().max(1)),
+                Sensitive::Serials => {
+                    removed += ifd.remove_entries(&SERIAL_TAGS);
+                    if let Some(exif_ifd) = ifd.sub_ifd_mut(EXIF_IFD) {
+                        removed += exif_ifd.remove_entries(&SERIAL_TAGS);
+                    }
+                },
+            }
+        }
+    }
+    removed
+}
+
+/// Removes the tags of `categories` from the eXIf chunk of `png` and
+/// rewrites it in place, returning how many tags were removed. The chunk is
+/// left untouched when nothing matches.
+pub fn strip(png: &mut Png, categories: &[Sensitive]) -> Result<usize> {
+    let index = *png.positions_of(&ChunkType::EXIF).first()
+        .ok_or_else(|| anyhow!("png has no eXIf chunk"))?;
+    let mut exif = Exif::try_from(&png.chunks()[index])?;
+    let removed = remove_sensitive(&mut exif, categories);
+    if removed > 0 {
+        png.replace_chunk(index, exif.to_chunk())?;
+    }
+    Ok(removed)
+}
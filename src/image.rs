@@ -0,0 +1,283 @@
+use anyhow::anyhow;
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use miniz_oxide::inflate::{decompress_to_vec_zlib_with_limit, TINFLStatus};
+
+use crate::Result;
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{ColorType, Ihdr};
+use crate::png::Png;
+
+/// (x start, y start, x step, y step) of the seven Adam7 passes.
+const ADAM7: [(u32, u32, u32, u32); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+/// Pixel data from the IDAT chunks, inflated, unfiltered and deinterlaced.
+///
+/// Samples keep the bit depth and color type given by `ihdr`; each of the
+/// `ihdr.height` rows is `ihdr.row_bytes(ihdr.width)` bytes long.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawImage {
+    pub ihdr: Ihdr,
+    pub data: Vec<u8>,
+}
+
+impl RawImage {
+    pub fn decode(png: &Png) -> Result<Self> {
+        let ihdr = png.ihdr()?;
+        let compressed: Vec<u8> = png.chunks().iter()
+            .filter(|chunk| chunk.chunk_type() == ChunkType::IDAT)
+            .flat_map(|chunk| chunk.data().iter().copied())
+            .collect();
+        if compressed.is_empty() {
+            return Err(anyhow!("png has no IDAT chunk"));
+        }
+        let expected = filtered_len(&ihdr)
+            .ok_or_else(|| anyhow!("a {}x{} image is too large to decode", ihdr.width, ihdr.height))?;
+        // Extra data after the last scanline is ignored rather than inflated
+        let filtered = match decompress_to_vec_zlib_with_limit(&compressed, expected) {
+            Ok(filtered) => filtered,
+            Err(e) if e.status == TINFLStatus::HasMoreOutput => e.output,
+            Err(e) => return Err(anyhow!("failed to inflate image data: {:?}", e.status)),
+        };
+        if filtered.len() < expected {
+            return Err(anyhow!("image data is {} bytes, expected {}", filtered.len(), expected));
+        }
+
+        let data = if ihdr.is_interlaced() {
+            deinterlace(&ihdr, &filtered)?
+        } else {
+            unfilter(&ihdr, ihdr.width, ihdr.height, &filtered)?
+        };
+        Ok(RawImage { ihdr, data })
+    }
+
+    pub fn row_bytes(&self) -> usize {
+        self.ihdr.row_bytes(self.ihdr.width)
+    }
+
+    pub fn rows(&self) -> std::slice::Chunks<'_, u8> {
+        self.data.chunks(self.row_bytes())
+    }
+
+    /// Filters and compresses the pixel data into the contents of a single,
+    /// non-interlaced IDAT chunk.
+    pub fn encode(&self) -> Vec<u8> {
+        compress_to_vec_zlib(&filter(&self.ihdr, &self.data), 6)
+    }
+
+    /// Replaces the IHDR and IDAT chunks of `png` with this image, written
+    /// without interlacing.
+    pub fn store(&self, png: &mut Png) -> Result<()> {
+        png.ihdr()?;
+        let mut ihdr = self.ihdr.clone();
+        ihdr.interlace_method = 0;
+        let position = png.positions_of(&ChunkType::IDAT).first().copied();
+        png.retain_chunks(|chunk| chunk.chunk_type() != ChunkType::IDAT);
+        png.replace_chunk(0, ihdr.to_chunk())?;
+        let position = position.unwrap_or(png.chunks().len() - 1);
+        png.insert_chunk(position, Chunk::new(ChunkType::IDAT, self.encode()))
+    }
+
+    /// How many pixels use each palette index.
+    pub fn index_usage(&self) -> Result<[usize; 256]> {
+        let bits = self.indexed_bit_depth()?;
+        let mut usage = [0; 256];
+        for row in self.rows() {
+            for x in 0..self.ihdr.width as usize {
+                usage[read_bits(row, x * bits, bits) as usize] += 1;
+            }
+        }
+        Ok(usage)
+    }
+
+    /// Rewrites every palette index `i` of an indexed image as `map[i]`.
+    pub fn remap_indices(&mut self, map: &[u8]) -> Result<()> {
+        let bits = self.indexed_bit_depth()?;
+        let (width, row_bytes) = (self.ihdr.width as usize, self.row_bytes());
+        for row in self.data.chunks_mut(row_bytes) {
+            for x in 0..width {
+                let index = read_bits(row, x * bits, bits) as usize;
+                let mapped = *map.get(index)
+                    .ok_or_else(|| anyhow!("palette index {} has no mapping", index))?;
+                copy_bits(&[mapped << (8 - bits)], 0, row, x * bits, bits);
+            }
+        }
+        Ok(())
+    }
+
+    fn indexed_bit_depth(&self) -> Result<usize> {
+        if self.ihdr.color_type != ColorType::Indexed {
+            return Err(anyhow!("image is not indexed but {:?}", self.ihdr.color_type));
+        }
+        Ok(self.ihdr.bit_depth as usize)
+    }
+}
+
+/// Applies a filter to every scanline and prefixes it with the filter type.
+/// Indexed and sub-byte images are left unfiltered, as the spec recommends;
+/// other rows get whichever filter minimizes the sum of absolute differences.
+fn filter(ihdr: &Ihdr, data: &[u8]) -> Vec<u8> {
+    let row_bytes = ihdr.row_bytes(ihdr.width);
+    let bpp = (ihdr.bits_per_pixel() / 8).max(1);
+    let adaptive = ihdr.color_type != ColorType::Indexed && ihdr.bit_depth >= 8;
+    let mut out = Vec::with_capacity((row_bytes + 1) * ihdr.height as usize);
+    let mut candidate = vec![0u8; row_bytes];
+    let mut best = vec![0u8; row_bytes];
+    for (y, current) in data.chunks(row_bytes).enumerate() {
+        let prior = if y == 0 { None } else { Some(&data[(y - 1) * row_bytes..y * row_bytes]) };
+        let filters = if adaptive { 0..5 } else { 0..1 };
+        let mut best_filter = (0, u64::MAX);
+        for filter in filters {
+            for x in 0..row_bytes {
+                let a = if x >= bpp { current[x - bpp] } else { 0 };
+                let b = prior.map_or(0, |prior| prior[x]);
+                let c = if x >= bpp { prior.map_or(0, |prior| prior[x - bpp]) } else { 0 };
+                let predictor = match filter {
+                    0 => 0,
+                    1 => a,
+                    2 => b,
+                    3 => ((a as u16 + b as u16) / 2) as u8,
+                    _ => paeth(a, b, c),
+                };
+                candidate[x] = current[x].wrapping_sub(predictor);
+            }
+            let score = candidate.iter().map(|&v| (v as i8).unsigned_abs() as u64).sum();
+            if score < best_filter.1 {
+                best_filter = (filter, score);
+                std::mem::swap(&mut best, &mut candidate);
+            }
+        }
+        out.push(best_filter.0);
+        out.extend_from_slice(&best);
+    }
+    out
+}
+
+/// Reverses the per-scanline filters of a `width` x `height` image, or of one
+/// interlace pass. Returns the unfiltered rows without their filter bytes.
+fn unfilter(ihdr: &Ihdr, width: u32, height: u32, filtered: &[u8]) -> Result<Vec<u8>> {
+    let row_bytes = ihdr.row_bytes(width);
+    let needed = pass_len(ihdr, width, height)
+        .ok_or_else(|| anyhow!("a {}x{} image is too large to decode", width, height))?;
+    if filtered.len() < needed {
+        return Err(anyhow!("image data is {} bytes, expected {}", filtered.len(), needed));
+    }
+    let bpp = (ihdr.bits_per_pixel() / 8).max(1);
+    let mut out = vec![0u8; row_bytes * height as usize];
+    for y in 0..height as usize {
+        let line = &filtered[y * (row_bytes + 1)..(y + 1) * (row_bytes + 1)];
+        let (filter, line) = (line[0], &line[1..]);
+        let (previous, current) = out.split_at_mut(y * row_bytes);
+        let prior = if y == 0 { None } else { Some(&previous[(y - 1) * row_bytes..]) };
+        let current = &mut current[..row_bytes];
+        for x in 0..row_bytes {
+            let a = if x >= bpp { current[x - bpp] } else { 0 };
+            let b = prior.map_or(0, |prior| prior[x]);
+            let c = if x >= bpp { prior.map_or(0, |prior| prior[x - bpp]) } else { 0 };
+            let predictor = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return Err(anyhow!("unknown filter type {} on row {}", filter, y)),
+            };
+            current[x] = line[x].wrapping_add(predictor);
+        }
+    }
+    Ok(out)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Size of the filtered scanlines of a `width` x `height` image or pass,
+/// filter bytes included, if it fits in memory.
+fn pass_len(ihdr: &Ihdr, width: u32, height: u32) -> Option<usize> {
+    ihdr.row_bytes(width).checked_add(1)?.checked_mul(height as usize)
+}
+
+/// Size of all the filtered scanlines `ihdr` describes, over every pass
+/// when interlaced.
+fn filtered_len(ihdr: &Ihdr) -> Option<usize> {
+    if !ihdr.is_interlaced() {
+        return pass_len(ihdr, ihdr.width, ihdr.height);
+    }
+    ADAM7.iter()
+        .map(|&(x0, y0, dx, dy)| ((ihdr.width + dx - 1 - x0) / dx, (ihdr.height + dy - 1 - y0) / dy))
+        .filter(|&(width, height)| width > 0 && height > 0)
+        .try_fold(0usize, |total, (width, height)| total.checked_add(pass_len(ihdr, width, height)?))
+}
+
+/// Unfilters the seven Adam7 passes and scatters their pixels into a
+/// regular row-major image.
+fn deinterlace(ihdr: &Ihdr, filtered: &[u8]) -> Result<Vec<u8>> {
+    let bits = ihdr.bits_per_pixel();
+    let row_bytes = ihdr.row_bytes(ihdr.width);
+    match filtered_len(ihdr) {
+        Some(expected) if filtered.len() >= expected => {},
+        _ => return Err(anyhow!("image data is {} bytes, too short for a {}x{} image",
+                                filtered.len(), ihdr.width, ihdr.height)),
+    }
+    let mut out = vec![0u8; row_bytes * ihdr.height as usize];
+    let mut pos = 0;
+    for &(x0, y0, dx, dy) in ADAM7.iter() {
+        let width = (ihdr.width + dx - 1 - x0) / dx;
+        let height = (ihdr.height + dy - 1 - y0) / dy;
+        if width == 0 || height == 0 {
+            continue;
+        }
+        let pass_row_bytes = ihdr.row_bytes(width);
+        let pass = unfilter(ihdr, width, height, filtered.get(pos..).unwrap_or(&[]))?;
+        pos += pass_len(ihdr, width, height).expect("pass sizes are checked above");
+        for py in 0..height as usize {
+            let y = y0 as usize + py * dy as usize;
+            for px in 0..width as usize {
+                let x = x0 as usize + px * dx as usize;
+                copy_bits(&pass[py * pass_row_bytes..], px * bits,
+                          &mut out[y * row_bytes..], x * bits, bits);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Reads the `count`-bit sample starting at bit `from` of `src`, `count` <= 8.
+pub(crate) fn read_bits(src: &[u8], from: usize, count: usize) -> u8 {
+    let byte = src[from / 8];
+    (byte << (from % 8)) >> (8 - count)
+}
+
+/// Copies `count` bits starting at bit `from` of `src` to bit `to` of `dst`,
+/// most significant bit first as PNG packs sub-byte samples.
+fn copy_bits(src: &[u8], from: usize, dst: &mut [u8], to: usize, count: usize) {
+    if from.is_multiple_of(8) && to.is_multiple_of(8) && count.is_multiple_of(8) {
+        let n = count / 8;
+        dst[to / 8..to / 8 + n].copy_from_slice(&src[from / 8..from / 8 + n]);
+        return;
+    }
+    for i in 0..count {
+        let bit = (src[(from + i) / 8] >> (7 - (from + i) % 8)) & 1;
+        let shift = 7 - (to + i) % 8;
+        dst[(to + i) / 8] = (dst[(to + i) / 8] & !(1 << shift)) | (bit << shift);
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::Write;
+
+use crate::chunk::Chunk;
+use crate::chunks;
+use crate::output;
+use crate::png::Png;
+
+/// Number of data bytes shown per chunk unless the full dump is requested.
+pub const DEFAULT_LIMIT: usize = 64;
+
+const BYTES_PER_LINE: usize = 16;
+
+/// Options for rendering `inspect` output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InspectOptions {
+    /// Dump all of each chunk's data instead of the first `DEFAULT_LIMIT` bytes.
+    pub full: bool,
+    /// Highlight types and CRC status with ANSI escape codes.
+    pub color: bool,
+}
+
+impl InspectOptions {
+    fn limit(&self) -> Option<usize> {
+        if self.full { None } else { Some(DEFAULT_LIMIT) }
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Formats `data` like `xxd`, labelling each line with its absolute offset
+/// `base + index`. At most `limit` bytes are shown.
+pub fn hexdump(data: &[u8], base: usize, limit: Option<usize>) -> String {
+    let shown = limit.map_or(data.len(), |limit| limit.min(data.len()));
+    let mut dump = String::new();
+    for (line, bytes) in data[..shown].chunks(BYTES_PER_LINE).enumerate() {
+        let hex = bytes.iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let ascii = bytes.iter()
+            .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+            .collect::<String>();
+        writeln!(dump, "{:08x}: {:<width$}  {}", base + line * BYTES_PER_LINE, hex, ascii,
+                 width = BYTES_PER_LINE * 3 - 1)
+            .expect("writing to a String never fails");
+    }
+    if shown < data.len() {
+        writeln!(dump, "... {} more bytes", data.len() - shown)
+            .expect("writing to a String never fails");
+    }
+    dump
+}
+
+/// Renders one chunk located at `offset` in the file.
+pub fn inspect_chunk(offset: usize, chunk: &Chunk, options: &InspectOptions) -> String {
+    let mut out = String::new();
+    let crc_status = if chunk.is_crc_valid() {
+        options.paint("32", "ok")
+    } else {
+        options.paint("1;31", "MISMATCH")
+    };
+    writeln!(out, "{} at offset {} (0x{:x})",
+             options.paint("1;36", &chunk.chunk_type().to_string()), offset, offset)
+        .expect("writing to a String never fails");
+    writeln!(out, "  length:     {}", chunk.length()).expect("writing to a String never fails");
+    writeln!(out, "  properties: {}", output::properties(&chunk.chunk_type())).expect("writing to a String never fails");
+    writeln!(out, "  crc:        stored {:08x}, computed {:08x} ({})",
+             chunk.crc(), chunk.computed_crc(), crc_status)
+        .expect("writing to a String never fails");
+    if let Some(decoded) = chunks::describe(chunk) {
+        writeln!(out, "  decoded:    {}", decoded).expect("writing to a String never fails");
+    }
+    if chunk.length() > 0 {
+        for line in hexdump(chunk.data(), offset + 8, options.limit()).lines() {
+            writeln!(out, "  {}", line).expect("writing to a String never fails");
+        }
+    }
+    out
+}
+
+/// Renders every chunk of `png`, separated by blank lines.
+pub fn inspect(png: &Png, options: &InspectOptions) -> String {
+    png.chunks_with_offsets()
+        .map(|(offset, chunk)| inspect_chunk(offset, chunk, options))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Machine-readable form of `inspect`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ChunkInspection {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub summary: output::ChunkSummary,
+    pub computed_crc: u32,
+    pub data: String,
+    pub truncated: bool,
+}
+
+impl ChunkInspection {
+    pub fn new(offset: usize, chunk: &Chunk, options: &InspectOptions) -> Self {
+        let shown = options.limit().map_or(chunk.data().len(), |limit| limit.min(chunk.data().len()));
+        ChunkInspection {
+            summary: output::ChunkSummary::new(offset, chunk, None),
+            computed_crc: chunk.computed_crc(),
+            data: chunk.data()[..shown].iter().map(|b| format!("{:02x}", b)).collect(),
+            truncated: shown < chunk.data().len(),
+        }
+    }
+
+    pub fn all(png: &Png, options: &InspectOptions) -> Vec<Self> {
+        png.chunks_with_offsets()
+            .map(|(offset, chunk)| ChunkInspection::new(offset, chunk, options))
+            .collect()
+    }
+}
@@ -1,9 +1,32 @@
 extern crate core;
 // pub mod args;
+pub mod append;
+pub mod batch;
+pub mod builder;
 pub mod chunk;
 pub mod chunk_type;
+pub mod chunks;
+pub mod color;
 pub mod commands;
+pub mod convert;
+pub mod detect;
+pub mod diff;
+pub mod exif;
+pub mod image;
+pub mod inspect;
+pub mod messages;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod netpbm;
+pub mod output;
+pub mod palette;
+pub mod pixels;
 pub mod png;
+pub mod query;
+pub mod recovery;
+pub mod stdio;
+pub mod trailer;
+pub mod validate;
 
 pub type Error = anyhow::Error;
 pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
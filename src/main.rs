@@ -1,10 +1,9 @@
 use std::process;
-use pngme::{Error, Result};
 use pngme::commands::Args;
 
 fn main() {
     if let Err(e) = Args::parse() {
-        println!("error occurred: {}", e);
+        eprintln!("error occurred: {}", e);
         process::exit(2);
     }
 }
\ No newline at end of file
@@ -0,0 +1,215 @@
+//! Named messages hidden in a png, one self-describing `meSg` chunk each.
+
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::TypedChunk;
+use crate::output;
+use crate::png::Png;
+
+pub const DEFAULT_CONTENT_TYPE: &str = "text/plain";
+
+/*
+meSg layout
+name and content type are UTF-8 and end with a null byte, followed by the
+creation time as big-endian seconds since the unix epoch, then the content
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub name: String,
+    pub content_type: String,
+    pub created: SystemTime,
+    pub content: Vec<u8>,
+}
+
+impl Message {
+    /// Creates a message stamped with the current time.
+    pub fn new(name: &str, content_type: &str, content: Vec<u8>) -> Result<Self> {
+        let message = Message {
+            name: name.to_string(),
+            content_type: content_type.to_string(),
+            // the chunk only keeps whole seconds
+            created: UNIX_EPOCH + Duration::from_secs(unix_seconds(SystemTime::now())),
+            content,
+        };
+        message.validate()?;
+        Ok(message)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() || self.name.len() > 79 {
+            return Err(anyhow!("message name must be 1 to 79 bytes, got {}", self.name.len()));
+        }
+        if self.content_type.is_empty() || self.content_type.len() > 79 {
+            return Err(anyhow!("content type must be 1 to 79 bytes, got {}", self.content_type.len()));
+        }
+        if self.name.contains('\0') || self.content_type.contains('\0') {
+            return Err(anyhow!("message name and content type must not contain null bytes"));
+        }
+        Ok(())
+    }
+
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.name.len() + self.content_type.len() + 10 + self.content.len());
+        bytes.extend_from_slice(self.name.as_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(self.content_type.as_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(&unix_seconds(self.created).to_be_bytes());
+        bytes.extend_from_slice(&self.content);
+        bytes
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::new(ChunkType::MESSAGE, self.as_bytes())
+    }
+
+    /// The content as text, if it is valid UTF-8.
+    pub fn text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.content).ok()
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
+/// Splits a null-terminated UTF-8 field off the front of `bytes`.
+fn split_field<'a>(bytes: &'a [u8], field: &str) -> Result<(String, &'a [u8])> {
+    let end = bytes.iter()
+        .position(|&b| b == 0)
+        .ok_or_else(|| anyhow!("meSg chunk has no {} terminator", field))?;
+    Ok((String::from_utf8(bytes[..end].to_vec())?, &bytes[end + 1..]))
+}
+
+impl TypedChunk for Message {
+    const TYPE: ChunkType = ChunkType::MESSAGE;
+    const MULTIPLE: bool = true;
+
+    fn decode(data: &[u8]) -> Result<Self> {
+        Message::try_from(data)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        self.validate()?;
+        Ok(self.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for Message {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self> {
+        let (name, rest) = split_field(value, "name")?;
+        let (content_type, rest) = split_field(rest, "content type")?;
+        if rest.len() < 8 {
+            return Err(anyhow!("meSg chunk is missing its creation time"));
+        }
+        let (created, content) = rest.split_at(8);
+        let message = Message {
+            name,
+            content_type,
+            created: UNIX_EPOCH + Duration::from_secs(u64::from_be_bytes(created.try_into()?)),
+            content: content.to_vec(),
+        };
+        message.validate()?;
+        Ok(message)
+    }
+}
+
+impl TryFrom<&Chunk> for Message {
+    type Error = Error;
+
+    fn try_from(chunk: &Chunk) -> Result<Self> {
+        if chunk.chunk_type() != ChunkType::MESSAGE {
+            return Err(anyhow!("expected meSg chunk, got {}", chunk.chunk_type()));
+        }
+        Message::try_from(chunk.data())
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}, {} bytes)", self.name, self.content_type, self.content.len())
+    }
+}
+
+/// What `messages list` reports about a message, without its content.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MessageInfo {
+    pub name: String,
+    pub content_type: String,
+    pub created: String,
+    pub size: usize,
+}
+
+impl MessageInfo {
+    pub fn new(message: &Message) -> Self {
+        MessageInfo {
+            name: message.name.clone(),
+            content_type: message.content_type.clone(),
+            created: output::format_timestamp(unix_seconds(message.created)),
+            size: message.content.len(),
+        }
+    }
+}
+
+/// CRUD access to the named messages of a `Png`. Names are unique: putting
+/// a message replaces any message with the same name.
+pub struct MessageStore {
+    png: Png,
+}
+
+impl MessageStore {
+    pub fn new(png: Png) -> Self {
+        MessageStore { png }
+    }
+
+    pub fn png(&self) -> &Png {
+        &self.png
+    }
+
+    pub fn into_png(self) -> Png {
+        self.png
+    }
+
+    /// Every message, in file order. Fails on the first malformed meSg chunk.
+    pub fn list(&self) -> Result<Vec<Message>> {
+        self.png.chunks_of_type(&ChunkType::MESSAGE)
+            .map(Message::try_from)
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<Message>> {
+        Ok(self.list()?.into_iter().find(|message| message.name == name))
+    }
+
+    /// Stores `message`, returning the message it replaced, if any.
+    pub fn put(&mut self, message: Message) -> Result<Option<Message>> {
+        message.validate()?;
+        let replaced = self.remove(&message.name)?;
+        self.png.append_chunk(message.to_chunk());
+        Ok(replaced)
+    }
+
+    /// Removes the message called `name`.
+    pub fn delete(&mut self, name: &str) -> Result<Message> {
+        self.remove(name)?.ok_or_else(|| anyhow!("no message named {:?}", name))
+    }
+
+    fn remove(&mut self, name: &str) -> Result<Option<Message>> {
+        let removed = self.get(name)?;
+        if removed.is_some() {
+            self.png.retain_chunks(|chunk| {
+                chunk.chunk_type() != ChunkType::MESSAGE
+                    || !Message::try_from(chunk.data()).is_ok_and(|message| message.name == name)
+            });
+        }
+        Ok(removed)
+    }
+}
@@ -0,0 +1,172 @@
+//! Memory-mapped access to large PNG files.
+//!
+//! `MappedPng` walks the chunk table straight from the mapping, so looking
+//! up one chunk only touches the pages holding the chunk headers on the way
+//! and the data of the chunk itself, instead of reading the whole file.
+
+use std::convert::TryFrom;
+use std::fs::File;
+use std::path::Path;
+use anyhow::anyhow;
+use memmap2::Mmap;
+
+use crate::Result;
+use crate::chunk::{self, Chunk};
+use crate::chunk_type::ChunkType;
+use crate::chunks::{self, Ihdr};
+use crate::output::ChunkSummary;
+use crate::png::Png;
+
+pub struct MappedPng {
+    map: Mmap,
+}
+
+impl MappedPng {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is only read. Like every other reader of the
+        // file we rely on nobody truncating it while it is mapped.
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < Png::STANDARD_HEADER.len() || map[..Png::STANDARD_HEADER.len()] != Png::STANDARD_HEADER {
+            return Err(anyhow!("invalid header"));
+        }
+        Ok(MappedPng { map })
+    }
+
+    /// Size of the mapped file in bytes.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Iterates over the chunks in file order, reading each header only when
+    /// the iterator gets to it. Stops after IEND or at the first malformed chunk.
+    pub fn chunks(&self) -> MappedChunks<'_> {
+        MappedChunks {
+            bytes: &self.map,
+            pos: Png::STANDARD_HEADER.len(),
+            done: false,
+        }
+    }
+
+    /// The first chunk of type `chunk_type`, checking its CRC.
+    pub fn find(&self, chunk_type: &ChunkType) -> Result<Option<ChunkRef<'_>>> {
+        for chunk in self.chunks() {
+            let chunk = chunk?;
+            if chunk.chunk_type() == chunk_type {
+                if !chunk.is_crc_valid() {
+                    return Err(anyhow!("crc mismatched"));
+                }
+                return Ok(Some(chunk));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Copies the whole file into a regular `Png`.
+    pub fn to_png(&self) -> Result<Png> {
+        Png::try_from(&self.map[..])
+    }
+}
+
+/// A chunk borrowed from a mapping.
+pub struct ChunkRef<'a> {
+    offset: usize,
+    chunk_type: ChunkType,
+    data: &'a [u8],
+    crc: u32,
+}
+
+impl<'a> ChunkRef<'a> {
+    /// Absolute offset of the chunk's length field in the file.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn length(&self) -> u32 {
+        self.data.len() as u32
+    }
+
+    pub fn chunk_type(&self) -> &ChunkType {
+        &self.chunk_type
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn crc(&self) -> u32 {
+        self.crc
+    }
+
+    pub fn is_crc_valid(&self) -> bool {
+        chunk::compute_crc(&self.chunk_type, self.data) == self.crc
+    }
+
+    pub fn to_chunk(&self) -> Chunk {
+        Chunk::with_stored_crc(self.chunk_type.clone(), self.data.to_vec(), self.crc)
+    }
+
+    /// See `ChunkSummary::new` for the meaning of `ihdr`. The CRC is only
+    /// checked if `verify_crc`, as that reads all of the chunk's data.
+    pub fn summary(&self, ihdr: Option<&Ihdr>, verify_crc: bool) -> ChunkSummary {
+        ChunkSummary {
+            offset: self.offset,
+            length: self.length(),
+            chunk_type: self.chunk_type.clone(),
+            crc: self.crc,
+            crc_valid: verify_crc.then(|| self.is_crc_valid()),
+            decoded: chunks::describe_data(&self.chunk_type, self.data, ihdr),
+        }
+    }
+}
+
+pub struct MappedChunks<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    done: bool,
+}
+
+impl<'a> Iterator for MappedChunks<'a> {
+    type Item = Result<ChunkRef<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.pos >= self.bytes.len() {
+            return None;
+        }
+        let offset = self.pos;
+        let header = match self.bytes.get(offset..offset + 8) {
+            Some(header) => header,
+            None => {
+                self.done = true;
+                return Some(Err(anyhow!("truncated chunk at offset {}", offset)));
+            },
+        };
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let chunk_type = match ChunkType::try_from([header[4], header[5], header[6], header[7]]) {
+            Ok(chunk_type) => chunk_type,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            },
+        };
+        let crc = match self.bytes.get(offset + 8 + length..offset + 12 + length) {
+            Some(crc) => u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]),
+            None => {
+                self.done = true;
+                return Some(Err(anyhow!("chunk {} is truncated", chunk_type)));
+            },
+        };
+        self.pos = offset + 12 + length;
+        self.done = chunk_type == ChunkType::IEND;
+        Some(Ok(ChunkRef {
+            offset,
+            data: &self.bytes[offset + 8..offset + 8 + length],
+            chunk_type,
+            crc,
+        }))
+    }
+}
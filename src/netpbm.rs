@@ -0,0 +1,285 @@
+//! Reading and writing the binary Netpbm formats: PBM, PGM, PPM and PAM.
+//!
+//! http://netpbm.sourceforge.net/doc/pbm.html and its siblings describe the
+//! layouts. The plain (ASCII) variants P1 to P3 are not supported.
+
+use std::str::FromStr;
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunks::ColorType;
+use crate::pixels::Rgba16;
+
+/// One of the Netpbm formats, named after its file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Netpbm {
+    /// Bitmaps, black and white only.
+    Pbm,
+    Pgm,
+    Ppm,
+    /// Arbitrary tuples, used here for gray or RGB with alpha.
+    Pam,
+}
+
+impl Netpbm {
+    pub const NAMES: [&'static str; 4] = ["pbm", "pgm", "ppm", "pam"];
+
+    pub fn magic(&self) -> &'static [u8; 2] {
+        match self {
+            Netpbm::Pbm => b"P4",
+            Netpbm::Pgm => b"P5",
+            Netpbm::Ppm => b"P6",
+            Netpbm::Pam => b"P7",
+        }
+    }
+
+    /// The format whose magic number starts `bytes`.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        [Netpbm::Pbm, Netpbm::Pgm, Netpbm::Ppm, Netpbm::Pam].iter().copied()
+            .find(|format| bytes.starts_with(format.magic()))
+    }
+}
+
+impl FromStr for Netpbm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pbm" => Ok(Netpbm::Pbm),
+            "pgm" => Ok(Netpbm::Pgm),
+            "ppm" => Ok(Netpbm::Ppm),
+            "pam" => Ok(Netpbm::Pam),
+            _ => Err(anyhow!("unknown netpbm format {}, expected one of {:?}", s, Netpbm::NAMES)),
+        }
+    }
+}
+
+/// A decoded Netpbm image with the png color type and bit depth that hold
+/// its samples without loss.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetpbmImage {
+    pub format: Netpbm,
+    pub image: Rgba16,
+    pub color_type: ColorType,
+    pub bit_depth: u8,
+}
+
+/// Width, height, samples per pixel and maxval from a header.
+struct Header {
+    width: u32,
+    height: u32,
+    depth: usize,
+    maxval: u32,
+}
+
+pub fn decode(bytes: &[u8]) -> Result<NetpbmImage> {
+    let format = Netpbm::detect(bytes)
+        .ok_or_else(|| anyhow!("not a binary netpbm file"))?;
+    let (header, raster) = match format {
+        Netpbm::Pam => pam_header(bytes)?,
+        _ => pnm_header(bytes, format)?,
+    };
+    if header.width == 0 || header.height == 0 {
+        return Err(anyhow!("invalid image size {}x{}", header.width, header.height));
+    }
+    if !(1..=0xffff).contains(&header.maxval) {
+        return Err(anyhow!("invalid maxval {}", header.maxval));
+    }
+    let pixels = header.width as usize * header.height as usize;
+    let samples = match format {
+        Netpbm::Pbm => unpack_bits(raster, header.width as usize, header.height as usize)?,
+        _ => read_samples(raster, pixels * header.depth, header.maxval)?,
+    };
+
+    let scale = |v: u32| ((v * 0xffff + header.maxval / 2) / header.maxval) as u16;
+    let mut data = Vec::with_capacity(4 * pixels);
+    for pixel in samples.chunks(header.depth) {
+        let rgba = match *pixel {
+            [gray] => [gray, gray, gray, header.maxval],
+            [gray, alpha] => [gray, gray, gray, alpha],
+            [red, green, blue] => [red, green, blue, header.maxval],
+            [red, green, blue, alpha, ..] => [red, green, blue, alpha],
+            [] => unreachable!("depth is at least 1"),
+        };
+        data.extend(rgba.iter().map(|&v| scale(v)));
+    }
+
+    let sample_depth = match header.maxval {
+        1 => 1,
+        3 => 2,
+        15 => 4,
+        0..=255 => 8,
+        _ => 16,
+    };
+    let (color_type, bit_depth) = match header.depth {
+        1 => (ColorType::Grayscale, sample_depth),
+        2 => (ColorType::GrayscaleAlpha, sample_depth.max(8)),
+        3 => (ColorType::Rgb, sample_depth.max(8)),
+        _ => (ColorType::Rgba, sample_depth.max(8)),
+    };
+    let image = Rgba16 { width: header.width, height: header.height, data };
+    Ok(NetpbmImage { format, image, color_type, bit_depth })
+}
+
+/// Writes `image` as `format` with 8 or 16 bits per sample. PBM, PGM and
+/// PPM drop alpha; PBM and PGM store the luma of colored pixels, and PBM
+/// makes pixels darker than mid-gray black.
+pub fn encode(image: &Rgba16, format: Netpbm, bit_depth: u8) -> Result<Vec<u8>> {
+    let maxval: u32 = match bit_depth {
+        8 => 0xff,
+        16 => 0xffff,
+        _ => return Err(anyhow!("netpbm samples must be 8 or 16 bits, got {}", bit_depth)),
+    };
+    let has_alpha = image.pixels().any(|[.., alpha]| alpha != 0xffff);
+    let is_gray = image.pixels().all(|[r, g, b, _]| r == g && g == b);
+    let mut out = Vec::new();
+    let samples: Vec<u16> = match format {
+        Netpbm::Pbm => {
+            out.extend_from_slice(format!("P4\n{} {}\n", image.width, image.height).as_bytes());
+            let width = image.width as usize;
+            let row_bytes = width.div_ceil(8);
+            let mut raster = vec![0u8; row_bytes * image.height as usize];
+            for (i, pixel) in image.pixels().enumerate() {
+                if luma(pixel) < 0x8000 {
+                    let (y, x) = (i / width, i % width);
+                    raster[y * row_bytes + x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+            out.extend_from_slice(&raster);
+            return Ok(out);
+        },
+        Netpbm::Pgm => {
+            out.extend_from_slice(format!("P5\n{} {}\n{}\n", image.width, image.height, maxval).as_bytes());
+            image.pixels().map(luma).collect()
+        },
+        Netpbm::Ppm => {
+            out.extend_from_slice(format!("P6\n{} {}\n{}\n", image.width, image.height, maxval).as_bytes());
+            image.pixels().flat_map(|[r, g, b, _]| [r, g, b]).collect()
+        },
+        Netpbm::Pam => {
+            let (depth, tuple_type) = match (is_gray, has_alpha) {
+                (true, false) => (1, "GRAYSCALE"),
+                (true, true) => (2, "GRAYSCALE_ALPHA"),
+                (false, false) => (3, "RGB"),
+                (false, true) => (4, "RGB_ALPHA"),
+            };
+            out.extend_from_slice(format!("P7\nWIDTH {}\nHEIGHT {}\nDEPTH {}\nMAXVAL {}\nTUPLTYPE {}\nENDHDR\n",
+                                          image.width, image.height, depth, maxval, tuple_type).as_bytes());
+            image.pixels()
+                .flat_map(|[r, g, b, a]| match depth {
+                    1 => vec![r],
+                    2 => vec![r, a],
+                    3 => vec![r, g, b],
+                    _ => vec![r, g, b, a],
+                })
+                .collect()
+        },
+    };
+    for sample in samples {
+        match bit_depth {
+            8 => out.push(((sample as u32 * 0xff + 0x7fff) / 0xffff) as u8),
+            _ => out.extend_from_slice(&sample.to_be_bytes()),
+        }
+    }
+    Ok(out)
+}
+
+/// BT.601 luma, as `ppmtopgm` computes it.
+fn luma([r, g, b, _]: [u16; 4]) -> u16 {
+    ((299 * r as u32 + 587 * g as u32 + 114 * b as u32 + 500) / 1000) as u16
+}
+
+/// Parses the header of a PBM, PGM or PPM file, returning it and the raster
+/// that follows the single whitespace character after it.
+fn pnm_header(bytes: &[u8], format: Netpbm) -> Result<(Header, &[u8])> {
+    let fields = if format == Netpbm::Pbm { 2 } else { 3 };
+    let mut values = Vec::with_capacity(fields);
+    let mut pos = 2;
+    while values.len() < fields {
+        match bytes.get(pos) {
+            Some(b'#') => {
+                while bytes.get(pos).is_some_and(|&b| b != b'\n') {
+                    pos += 1;
+                }
+            },
+            Some(b) if b.is_ascii_whitespace() => pos += 1,
+            Some(b) if b.is_ascii_digit() => {
+                let start = pos;
+                while bytes.get(pos).is_some_and(u8::is_ascii_digit) {
+                    pos += 1;
+                }
+                values.push(std::str::from_utf8(&bytes[start..pos])?.parse::<u32>()?);
+            },
+            Some(&b) => return Err(anyhow!("unexpected byte {:#04x} in netpbm header", b)),
+            None => return Err(anyhow!("netpbm header is truncated")),
+        }
+    }
+    if !bytes.get(pos).is_some_and(u8::is_ascii_whitespace) {
+        return Err(anyhow!("netpbm header must end with a whitespace character"));
+    }
+    let (depth, maxval) = match format {
+        Netpbm::Pbm => (1, 1),
+        Netpbm::Ppm => (3, values[2]),
+        _ => (1, values[2]),
+    };
+    Ok((Header { width: values[0], height: values[1], depth, maxval }, &bytes[pos + 1..]))
+}
+
+/// Parses a PAM header, which is a list of `KEY value` lines ending with
+/// ENDHDR.
+fn pam_header(bytes: &[u8]) -> Result<(Header, &[u8])> {
+    let end = bytes.windows(7)
+        .position(|window| window == b"ENDHDR\n")
+        .ok_or_else(|| anyhow!("PAM header has no ENDHDR line"))?;
+    let text = std::str::from_utf8(&bytes[..end])?;
+    let (mut width, mut height, mut depth, mut maxval) = (None, None, None, None);
+    for line in text.lines().skip(1).map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let (key, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let number = || value.trim().parse::<u32>().map_err(|_| anyhow!("invalid PAM {} {:?}", key, value));
+        match key {
+            "WIDTH" => width = Some(number()?),
+            "HEIGHT" => height = Some(number()?),
+            "DEPTH" => depth = Some(number()?),
+            "MAXVAL" => maxval = Some(number()?),
+            "TUPLTYPE" => {},
+            _ => return Err(anyhow!("unknown PAM header line {:?}", line)),
+        }
+    }
+    let missing = |key: &str| anyhow!("PAM header has no {}", key);
+    let depth = depth.ok_or_else(|| missing("DEPTH"))? as usize;
+    if !(1..=4).contains(&depth) {
+        return Err(anyhow!("PAM depth must be 1 to 4, got {}", depth));
+    }
+    let header = Header {
+        width: width.ok_or_else(|| missing("WIDTH"))?,
+        height: height.ok_or_else(|| missing("HEIGHT"))?,
+        depth,
+        maxval: maxval.ok_or_else(|| missing("MAXVAL"))?,
+    };
+    Ok((header, &bytes[end + 7..]))
+}
+
+/// Reads `count` samples of one byte, or two when `maxval` needs them.
+fn read_samples(raster: &[u8], count: usize, maxval: u32) -> Result<Vec<u32>> {
+    let width = if maxval > 0xff { 2 } else { 1 };
+    let raster = raster.get(..count * width)
+        .ok_or_else(|| anyhow!("netpbm raster is {} bytes, expected {}", raster.len(), count * width))?;
+    let samples: Vec<u32> = match width {
+        1 => raster.iter().map(|&v| v as u32).collect(),
+        _ => raster.chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]) as u32).collect(),
+    };
+    if let Some(sample) = samples.iter().find(|&&sample| sample > maxval) {
+        return Err(anyhow!("sample {} exceeds maxval {}", sample, maxval));
+    }
+    Ok(samples)
+}
+
+/// Unpacks a PBM raster, where 1 is black, into gray samples with maxval 1.
+fn unpack_bits(raster: &[u8], width: usize, height: usize) -> Result<Vec<u32>> {
+    let row_bytes = width.div_ceil(8);
+    let raster = raster.get(..row_bytes * height)
+        .ok_or_else(|| anyhow!("PBM raster is {} bytes, expected {}", raster.len(), row_bytes * height))?;
+    Ok(raster.chunks(row_bytes)
+        .flat_map(|row| (0..width).map(move |x| 1 - ((row[x / 8] >> (7 - x % 8)) & 1) as u32))
+        .collect())
+}
@@ -0,0 +1,245 @@
+use std::fmt::Write;
+use std::str::FromStr;
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{self, time, Ihdr};
+use crate::chunks::exif::Field;
+use crate::messages::MessageInfo;
+use crate::palette::PaletteEntry;
+use crate::png::Png;
+
+/// Output formats accepted by the `--format` flag of read-only commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Yaml,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 3] = ["table", "json", "yaml"];
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            _ => Err(anyhow!("unknown output format {}, expected one of {:?}", s, Format::NAMES)),
+        }
+    }
+}
+
+/// What `print` reports about a chunk found at `offset` in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkSummary {
+    pub offset: usize,
+    pub length: u32,
+    pub chunk_type: ChunkType,
+    pub crc: u32,
+    /// None if the CRC was not checked.
+    pub crc_valid: Option<bool>,
+    pub decoded: Option<String>,
+}
+
+impl ChunkSummary {
+    /// `ihdr` is the header of the image the chunk belongs to, needed to
+    /// decode chunks whose layout depends on the color type.
+    pub fn new(offset: usize, chunk: &Chunk, ihdr: Option<&Ihdr>) -> Self {
+        ChunkSummary {
+            offset,
+            length: chunk.length(),
+            chunk_type: chunk.chunk_type(),
+            crc: chunk.crc(),
+            crc_valid: Some(chunk.is_crc_valid()),
+            decoded: chunks::describe_data(&chunk.chunk_type(), chunk.data(), ihdr),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ChunkSummary {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let chunk_type = &self.chunk_type;
+        let mut state = serializer.serialize_struct("ChunkSummary", 10)?;
+        state.serialize_field("offset", &self.offset)?;
+        state.serialize_field("length", &self.length)?;
+        state.serialize_field("type", chunk_type)?;
+        state.serialize_field("critical", &chunk_type.is_critical())?;
+        state.serialize_field("public", &chunk_type.is_public())?;
+        state.serialize_field("reserved_bit_valid", &chunk_type.is_reserved_bit_valid())?;
+        state.serialize_field("safe_to_copy", &chunk_type.is_safe_to_copy())?;
+        state.serialize_field("crc", &self.crc)?;
+        state.serialize_field("crc_valid", &self.crc_valid)?;
+        state.serialize_field("decoded", &self.decoded)?;
+        state.end()
+    }
+}
+
+/// The chunk table of a `Png`, as reported by `print`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PngSummary {
+    pub size: usize,
+    pub chunks: Vec<ChunkSummary>,
+}
+
+impl PngSummary {
+    pub fn new(png: &Png) -> Self {
+        let ihdr = png.ihdr().ok();
+        PngSummary::from_chunks(png.chunks_with_offsets()
+            .map(|(offset, chunk)| ChunkSummary::new(offset, chunk, ihdr.as_ref()))
+            .collect())
+    }
+
+    pub fn from_chunks(chunks: Vec<ChunkSummary>) -> Self {
+        let size = chunks.last()
+            .map_or(Png::STANDARD_HEADER.len(), |last| last.offset + last.length as usize + 12);
+        PngSummary { size, chunks }
+    }
+}
+
+/// The message found by `decode`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DecodedMessage {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub chunk_type: String,
+    pub offset: usize,
+    pub message: String,
+}
+
+/// What `validate` reports about a file.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Validation {
+    pub valid: bool,
+    pub problems: Vec<String>,
+}
+
+/// What `time get` reports about a file.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TimeReport {
+    /// The tIME as RFC 3339.
+    pub time: String,
+}
+
+/// What `trailing` reports about the data after IEND.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TrailingData {
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Serializes `value` as JSON or YAML. `Format::Table` has no generic
+/// representation, callers render their own table for it.
+#[cfg(feature = "serde")]
+pub fn to_string<T: serde::Serialize>(value: &T, format: Format) -> Result<String> {
+    match format {
+        Format::Json => Ok(serde_json::to_string_pretty(value)?),
+        Format::Yaml => Ok(serde_yaml::to_string(value)?.trim_end().to_string()),
+        Format::Table => Err(anyhow!("table output is not serializable")),
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+pub fn to_string<T>(_value: &T, format: Format) -> Result<String> {
+    Err(anyhow!("{:?} output requires pngme to be built with the `serde` feature", format))
+}
+
+/// Reads a `Png` back from the output of `dump` in the given format.
+#[cfg(feature = "serde")]
+pub fn png_from_dump(content: &str, format: Format) -> Result<Png> {
+    match format {
+        Format::Json => Ok(serde_json::from_str(content)?),
+        Format::Yaml => Ok(serde_yaml::from_str(content)?),
+        Format::Table => Err(anyhow!("dumps are json or yaml")),
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+pub fn png_from_dump(_content: &str, _format: Format) -> Result<Png> {
+    Err(anyhow!("reading dumps requires pngme to be built with the `serde` feature"))
+}
+
+/// Renders the chunk list of `summary` as a plain text table.
+pub fn chunk_table(summary: &PngSummary) -> String {
+    let mut table = format!("{:>10}  {:>10}  {:<4}  {:<30}  {:>10}  {:<6}  {}\n",
+                            "OFFSET", "LENGTH", "TYPE", "PROPERTIES", "CRC", "CRC OK", "DECODED");
+    for chunk in summary.chunks.iter() {
+        let line = format!("{:>10}  {:>10}  {:<4}  {:<30}  {:>10}  {:<6}  {}",
+                 chunk.offset,
+                 chunk.length,
+                 chunk.chunk_type,
+                 properties(&chunk.chunk_type),
+                 format!("{:08x}", chunk.crc),
+                 match chunk.crc_valid {
+                     Some(true) => "yes",
+                     Some(false) => "no",
+                     None => "-",
+                 },
+                 chunk.decoded.as_deref().unwrap_or(""));
+        writeln!(table, "{}", line.trim_end()).expect("writing to a String never fails");
+    }
+    table
+}
+
+/// Renders the `messages list` output as a plain text table.
+pub fn message_table(messages: &[MessageInfo]) -> String {
+    let mut table = format!("{:<24}  {:<24}  {:<20}  {:>10}\n", "NAME", "CONTENT TYPE", "CREATED", "SIZE");
+    for message in messages {
+        writeln!(table, "{:<24}  {:<24}  {:<20}  {:>10}",
+                 message.name, message.content_type, message.created, message.size)
+            .expect("writing to a String never fails");
+    }
+    table
+}
+
+/// Renders the `palette show` output as a plain text table.
+pub fn palette_table(entries: &[PaletteEntry]) -> String {
+    let mut table = format!("{:>5}  {:<7}  {:>5}\n", "INDEX", "COLOR", "ALPHA");
+    for entry in entries {
+        writeln!(table, "{:>5}  {:<7}  {:>5}", entry.index, entry.color, entry.alpha)
+            .expect("writing to a String never fails");
+    }
+    table
+}
+
+/// Renders the `exif show` output as a plain text table.
+pub fn exif_table(fields: &[Field]) -> String {
+    let mut table = format!("{:<9}  {:<6}  {:<28}  {}\n", "GROUP", "TAG", "NAME", "VALUE");
+    for field in fields {
+        writeln!(table, "{:<9}  0x{:04x}  {:<28}  {}", field.group, field.tag, field.name, field.value)
+            .expect("writing to a String never fails");
+    }
+    table
+}
+
+/// Formats seconds since the unix epoch as an RFC 3339 UTC timestamp.
+pub fn format_timestamp(seconds: u64) -> String {
+    let (year, month, day) = time::civil_from_days((seconds / 86400) as i64);
+    let time = seconds % 86400;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, time / 3600, time / 60 % 60, time % 60)
+}
+
+/// Describes the property bits of a chunk type in words.
+pub fn properties(chunk_type: &ChunkType) -> String {
+    [
+        if chunk_type.is_critical() { "critical" } else { "ancillary" },
+        if chunk_type.is_public() { "public" } else { "private" },
+        if chunk_type.is_reserved_bit_valid() { "" } else { "reserved" },
+        if chunk_type.is_safe_to_copy() { "safe" } else { "unsafe" },
+    ].iter()
+        .filter(|s| !s.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,209 @@
+//! Reading and editing the palette of a png, keeping its tRNS chunk and the
+//! palette indices in the pixel data consistent with it.
+
+use std::cmp::Reverse;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use anyhow::anyhow;
+
+use crate::{Error, Result};
+use crate::chunk_type::ChunkType;
+use crate::chunks::{Background, ColorType, Histogram, Ihdr, Palette, Transparency};
+use crate::image::RawImage;
+use crate::png::Png;
+
+/// One palette entry as reported by `palette show`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PaletteEntry {
+    pub index: usize,
+    pub color: String,
+    pub alpha: u8,
+}
+
+/// Orders `sort` can put a palette in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Darkest first.
+    Luminance,
+    /// By red, then green, then blue.
+    Rgb,
+    /// Most used by the pixels first.
+    Usage,
+    /// Most transparent first, which keeps the tRNS chunk short.
+    Alpha,
+}
+
+impl SortKey {
+    pub const NAMES: [&'static str; 4] = ["luminance", "rgb", "usage", "alpha"];
+}
+
+impl FromStr for SortKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "luminance" => Ok(SortKey::Luminance),
+            "rgb" => Ok(SortKey::Rgb),
+            "usage" => Ok(SortKey::Usage),
+            "alpha" => Ok(SortKey::Alpha),
+            _ => Err(anyhow!("unknown sort key {}, expected one of {:?}", s, SortKey::NAMES)),
+        }
+    }
+}
+
+/// Parses a `#rrggbb` color, the `#` being optional.
+pub fn parse_color(s: &str) -> Result<[u8; 3]> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(anyhow!("invalid color {:?}, expected #rrggbb", s));
+    }
+    let mut rgb = [0u8; 3];
+    for (i, value) in rgb.iter_mut().enumerate() {
+        *value = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .map_err(|_| anyhow!("invalid color {:?}, expected #rrggbb", s))?;
+    }
+    Ok(rgb)
+}
+
+pub fn format_color(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+/// The palette entries of `png` with their alpha from tRNS.
+pub fn entries(png: &Png) -> Result<Vec<PaletteEntry>> {
+    let ihdr = png.ihdr()?;
+    let (_, palette) = find_palette(png)?;
+    let transparency = find_transparency(png, &ihdr)?;
+    Ok(palette.entries.iter().enumerate()
+        .map(|(index, &rgb)| PaletteEntry {
+            index,
+            color: format_color(rgb),
+            alpha: transparency.as_ref().map_or(255, |trns| trns.alpha(index)),
+        })
+        .collect())
+}
+
+/// Sets palette entry `index` to `color`, and its alpha if given. An index
+/// one past the end appends an entry, if the bit depth leaves room for it.
+pub fn set(png: &mut Png, index: usize, color: [u8; 3], alpha: Option<u8>) -> Result<()> {
+    let ihdr = png.ihdr()?;
+    let (position, mut palette) = find_palette(png)?;
+    if alpha.is_some() && ihdr.color_type != ColorType::Indexed {
+        return Err(anyhow!("alpha can only be set in the palette of an indexed image"));
+    }
+    if index > palette.len() {
+        return Err(anyhow!("palette index {} out of range, the palette has {} entries", index, palette.len()));
+    }
+    if index == palette.len() {
+        palette.entries.push(color);
+    } else {
+        palette.entries[index] = color;
+    }
+    palette.validate_for(&ihdr)?;
+
+    let mut alphas = palette_alphas(png, &ihdr, palette.len())?;
+    png.replace_chunk(position, palette.to_chunk())?;
+    if let Some(alpha) = alpha {
+        alphas[index] = alpha;
+        write_alphas(png, position, alphas)?;
+    }
+    Ok(())
+}
+
+/// Reorders the palette of an indexed image by `key`, remapping the pixel
+/// data, bKGD and hIST to match. Returns false if the palette was already in
+/// order.
+///
+/// The image data is re-encoded, without interlacing.
+pub fn sort(png: &mut Png, key: SortKey) -> Result<bool> {
+    let ihdr = png.ihdr()?;
+    if ihdr.color_type != ColorType::Indexed {
+        return Err(anyhow!("only the palette of an indexed image can be sorted"));
+    }
+    let (position, palette) = find_palette(png)?;
+    let alphas = palette_alphas(png, &ihdr, palette.len())?;
+    let background = png.chunks_of_type(&ChunkType::BKGD).next()
+        .map(|chunk| Background::from_chunk(chunk, &ihdr))
+        .transpose()?;
+    let histogram = png.get::<Histogram>()?;
+    if let Some(histogram) = &histogram {
+        histogram.validate_for(&palette)?;
+    }
+    let mut image = RawImage::decode(png)?;
+    let usage = image.index_usage()?;
+
+    let mut order: Vec<usize> = (0..palette.len()).collect();
+    match key {
+        SortKey::Luminance => order.sort_by_key(|&i| {
+            let [r, g, b] = palette.entries[i];
+            299 * r as u32 + 587 * g as u32 + 114 * b as u32
+        }),
+        SortKey::Rgb => order.sort_by_key(|&i| palette.entries[i]),
+        SortKey::Usage => order.sort_by_key(|&i| Reverse(usage[i])),
+        SortKey::Alpha => order.sort_by_key(|&i| alphas[i]),
+    }
+    if order.iter().enumerate().all(|(new, &old)| new == old) {
+        return Ok(false);
+    }
+
+    // indices past the end of the palette are invalid, but left untouched
+    let mut map: Vec<u8> = (0..=255).collect();
+    for (new, &old) in order.iter().enumerate() {
+        map[old] = new as u8;
+    }
+    image.remap_indices(&map)?;
+
+    let sorted = Palette::new(order.iter().map(|&i| palette.entries[i]).collect())?;
+    png.replace_chunk(position, sorted.to_chunk())?;
+    write_alphas(png, position, order.iter().map(|&i| alphas[i]).collect())?;
+    if let Some(Background::PaletteIndex(index)) = background {
+        let position = png.positions_of(&ChunkType::BKGD)[0];
+        png.replace_chunk(position, Background::PaletteIndex(map[index as usize]).to_chunk())?;
+    }
+    if let Some(histogram) = histogram {
+        png.set(&Histogram { frequencies: order.iter().map(|&i| histogram.frequencies[i]).collect() })?;
+    }
+    image.store(png)?;
+    Ok(true)
+}
+
+fn find_palette(png: &Png) -> Result<(usize, Palette)> {
+    let position = *png.positions_of(&ChunkType::PLTE).first()
+        .ok_or_else(|| anyhow!("png has no PLTE chunk"))?;
+    Ok((position, Palette::try_from(&png.chunks()[position])?))
+}
+
+fn find_transparency(png: &Png, ihdr: &Ihdr) -> Result<Option<Transparency>> {
+    png.chunks_of_type(&ChunkType::TRNS).next()
+        .map(|chunk| Transparency::from_chunk(chunk, ihdr))
+        .transpose()
+}
+
+/// The alpha of each of the `len` palette entries, 255 for entries that are
+/// not in tRNS. Images that are not indexed have no palette alpha.
+fn palette_alphas(png: &Png, ihdr: &Ihdr, len: usize) -> Result<Vec<u8>> {
+    let transparency = match ihdr.color_type {
+        ColorType::Indexed => find_transparency(png, ihdr)?,
+        _ => None,
+    };
+    Ok((0..len).map(|i| transparency.as_ref().map_or(255, |trns| trns.alpha(i))).collect())
+}
+
+/// Writes palette alpha values to the tRNS chunk, leaving off the opaque
+/// entries at the end and dropping the chunk if every entry is opaque.
+fn write_alphas(png: &mut Png, plte_position: usize, mut alphas: Vec<u8>) -> Result<()> {
+    while alphas.last() == Some(&255) {
+        alphas.pop();
+    }
+    let existing = png.positions_of(&ChunkType::TRNS).first().copied();
+    match (existing, alphas.is_empty()) {
+        (Some(_), true) => png.retain_chunks(|chunk| chunk.chunk_type() != ChunkType::TRNS),
+        (Some(position), false) => {
+            png.replace_chunk(position, Transparency::Palette(alphas).to_chunk())?;
+        },
+        (None, true) => {},
+        (None, false) => png.insert_chunk(plte_position + 1, Transparency::Palette(alphas).to_chunk())?,
+    }
+    Ok(())
+}
@@ -0,0 +1,333 @@
+//! Converting pixel data of every color type and bit depth to and from
+//! canonical RGBA buffers with 8 or 16 bits per sample.
+
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use anyhow::anyhow;
+
+use crate::Result;
+use crate::builder::PngBuilder;
+use crate::chunk_type::ChunkType;
+use crate::chunks::{ColorType, Gamma, Ihdr, Palette, Transparency};
+use crate::image::{read_bits, RawImage};
+use crate::png::Png;
+
+/// What besides the samples is needed to interpret pixel data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelFormat {
+    pub ihdr: Ihdr,
+    pub palette: Option<Palette>,
+    pub transparency: Option<Transparency>,
+    pub gamma: Option<Gamma>,
+}
+
+impl PixelFormat {
+    pub fn from_png(png: &Png) -> Result<Self> {
+        let ihdr = png.ihdr()?;
+        let palette = png.chunks_of_type(&ChunkType::PLTE).next().map(Palette::try_from).transpose()?;
+        let transparency = png.chunks_of_type(&ChunkType::TRNS).next()
+            .map(|chunk| Transparency::from_chunk(chunk, &ihdr))
+            .transpose()?;
+        let format = PixelFormat { ihdr, palette, transparency, gamma: png.get::<Gamma>()? };
+        format.validate()?;
+        Ok(format)
+    }
+
+    /// Checks that an indexed image has a palette and that the palette and
+    /// tRNS fit the header.
+    pub fn validate(&self) -> Result<()> {
+        self.ihdr.validate()?;
+        match &self.palette {
+            Some(palette) => palette.validate_for(&self.ihdr)?,
+            None if self.ihdr.color_type == ColorType::Indexed => return Err(anyhow!("indexed image has no palette")),
+            None => {},
+        }
+        if let Some(transparency) = &self.transparency {
+            transparency.validate_for(&self.ihdr, self.palette.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Largest sample value at the header's bit depth.
+    fn max_sample(&self) -> u32 {
+        (1 << self.ihdr.bit_depth) - 1
+    }
+}
+
+/// An image with four samples per pixel, red, green, blue and straight
+/// (not premultiplied) alpha, in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rgba<T> {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<T>,
+}
+
+pub type Rgba8 = Rgba<u8>;
+pub type Rgba16 = Rgba<u16>;
+
+impl<T: Copy> Rgba<T> {
+    pub fn pixel(&self, x: u32, y: u32) -> [T; 4] {
+        let i = 4 * (y as usize * self.width as usize + x as usize);
+        [self.data[i], self.data[i + 1], self.data[i + 2], self.data[i + 3]]
+    }
+
+    pub fn pixels(&self) -> impl Iterator<Item = [T; 4]> + '_ {
+        self.data.chunks_exact(4).map(|p| [p[0], p[1], p[2], p[3]])
+    }
+}
+
+impl Rgba16 {
+    /// Converts `image`, applying tRNS transparency. With a `display_gamma`
+    /// such as 2.2 and a gAMA in `format`, color samples are also gamma
+    /// corrected for that display; alpha never is.
+    pub fn from_raw(image: &RawImage, format: &PixelFormat, display_gamma: Option<f64>) -> Result<Self> {
+        format.validate()?;
+        let ihdr = &format.ihdr;
+        let depth = ihdr.bit_depth as usize;
+        let channels = ihdr.color_type.channels();
+        let scale = 0xffff / format.max_sample();
+        let transparency = format.transparency.as_ref();
+        let sample = |row: &[u8], i: usize| match depth {
+            16 => u16::from_be_bytes([row[2 * i], row[2 * i + 1]]) as u32,
+            _ => read_bits(row, i * depth, depth) as u32,
+        };
+
+        let mut data = Vec::with_capacity(4 * ihdr.width as usize * ihdr.height as usize);
+        for row in image.rows().take(ihdr.height as usize) {
+            for x in 0..ihdr.width as usize {
+                let mut raw = [0u32; 4];
+                for (c, value) in raw.iter_mut().take(channels).enumerate() {
+                    *value = sample(row, x * channels + c);
+                }
+                let pixel = match ihdr.color_type {
+                    ColorType::Indexed => {
+                        let index = raw[0] as usize;
+                        let palette = format.palette.as_ref().expect("indexed images are validated to have a palette");
+                        let [red, green, blue] = *palette.entries.get(index)
+                            .ok_or_else(|| anyhow!("palette index {} is out of range", index))?;
+                        let alpha = transparency.map_or(255, |trns| trns.alpha(index));
+                        [red, green, blue, alpha].map(|v| v as u16 * 257)
+                    },
+                    ColorType::Grayscale => {
+                        let opaque = !matches!(transparency, Some(Transparency::Gray(key)) if *key as u32 == raw[0]);
+                        let gray = (raw[0] * scale) as u16;
+                        [gray, gray, gray, if opaque { 0xffff } else { 0 }]
+                    },
+                    ColorType::Rgb => {
+                        let opaque = !matches!(transparency, Some(Transparency::Rgb { red, green, blue })
+                            if [*red, *green, *blue].map(u32::from) == [raw[0], raw[1], raw[2]]);
+                        let [red, green, blue] = [raw[0], raw[1], raw[2]].map(|v| (v * scale) as u16);
+                        [red, green, blue, if opaque { 0xffff } else { 0 }]
+                    },
+                    ColorType::GrayscaleAlpha => {
+                        let gray = (raw[0] * scale) as u16;
+                        [gray, gray, gray, (raw[1] * scale) as u16]
+                    },
+                    ColorType::Rgba => [raw[0], raw[1], raw[2], raw[3]].map(|v| (v * scale) as u16),
+                };
+                data.extend_from_slice(&pixel);
+            }
+        }
+        if data.len() != 4 * ihdr.width as usize * ihdr.height as usize {
+            return Err(anyhow!("image data is too short for a {}x{} image", ihdr.width, ihdr.height));
+        }
+
+        if let (Some(display), Some(gamma)) = (display_gamma, format.gamma) {
+            if display <= 0.0 {
+                return Err(anyhow!("display gamma must be positive, got {}", display));
+            }
+            let exponent = 1.0 / (gamma.gamma * display);
+            let table: Vec<u16> = (0..=0xffff)
+                .map(|v| ((v as f64 / 65535.0).powf(exponent) * 65535.0).round() as u16)
+                .collect();
+            for pixel in data.chunks_exact_mut(4) {
+                for sample in &mut pixel[..3] {
+                    *sample = table[*sample as usize];
+                }
+            }
+        }
+        Ok(Rgba { width: ihdr.width, height: ihdr.height, data })
+    }
+
+    /// Decodes the pixels of `png`; see `from_raw`.
+    pub fn from_png(png: &Png, display_gamma: Option<f64>) -> Result<Self> {
+        Rgba16::from_raw(&RawImage::decode(png)?, &PixelFormat::from_png(png)?, display_gamma)
+    }
+
+    pub fn to_rgba8(&self) -> Rgba8 {
+        let data = self.data.iter().map(|&v| scale_down(v, 0xff) as u8).collect();
+        Rgba { width: self.width, height: self.height, data }
+    }
+
+    /// Converts to pixel data of `color_type` and `bit_depth`, rounding
+    /// samples to the nearest value at that depth.
+    ///
+    /// Gray color types need every pixel to be gray. Color types without an
+    /// alpha channel only store fully opaque or fully transparent pixels,
+    /// the latter through a tRNS color that no opaque pixel uses. Indexed
+    /// images get a palette of the distinct 8-bit colors, in order of first
+    /// use, which must fit in `bit_depth` bits.
+    pub fn encode(&self, color_type: ColorType, bit_depth: u8) -> Result<(RawImage, PixelFormat)> {
+        let ihdr = Ihdr::new(self.width, self.height, bit_depth, color_type)?;
+        let expected = 4 * self.width as usize * self.height as usize;
+        if self.data.len() != expected {
+            return Err(anyhow!("expected {} samples for a {}x{} image, got {}",
+                               expected, self.width, self.height, self.data.len()));
+        }
+        let max = (1u32 << bit_depth) - 1;
+        if matches!(color_type, ColorType::Grayscale | ColorType::GrayscaleAlpha) {
+            if let Some(i) = self.pixels().position(|[r, g, b, _]| r != g || g != b) {
+                return Err(anyhow!("pixel {} is not gray, which {:?} cannot store", i, color_type));
+            }
+        }
+
+        let mut palette = None;
+        let mut transparency = None;
+        // packed samples, `channels` per pixel
+        let channels = color_type.channels();
+        let samples: Vec<u32> = match color_type {
+            ColorType::Indexed => {
+                let (entries, indices) = self.index_colors(max as usize)?;
+                let mut alpha: Vec<u8> = entries.iter().map(|entry| entry[3]).collect();
+                while alpha.last() == Some(&255) {
+                    alpha.pop();
+                }
+                if !alpha.is_empty() {
+                    transparency = Some(Transparency::Palette(alpha));
+                }
+                palette = Some(Palette::new(entries.iter().map(|&[r, g, b, _]| [r, g, b]).collect())?);
+                indices.into_iter().map(|index| index as u32).collect()
+            },
+            ColorType::GrayscaleAlpha => self.pixels()
+                .flat_map(|[gray, _, _, alpha]| [gray, alpha])
+                .map(|v| scale_down(v, max))
+                .collect(),
+            ColorType::Rgba => self.data.iter().map(|&v| scale_down(v, max)).collect(),
+            ColorType::Grayscale | ColorType::Rgb => {
+                let mut colors: Vec<u32> = self.data.chunks(4)
+                    .flat_map(|pixel| &pixel[..channels])
+                    .map(|&v| scale_down(v, max))
+                    .collect();
+                if let Some(key) = self.transparent_key(&colors, channels, max)? {
+                    for (color, [.., alpha]) in colors.chunks_mut(channels).zip(self.pixels()) {
+                        if alpha == 0 {
+                            color.copy_from_slice(&key);
+                        }
+                    }
+                    let key: Vec<u16> = key.into_iter().map(|v| v as u16).collect();
+                    transparency = Some(match color_type {
+                        ColorType::Grayscale => Transparency::Gray(key[0]),
+                        _ => Transparency::Rgb { red: key[0], green: key[1], blue: key[2] },
+                    });
+                }
+                colors
+            },
+        };
+
+        let row_bytes = ihdr.row_bytes(ihdr.width);
+        let depth = bit_depth as usize;
+        let mut data = vec![0u8; row_bytes * ihdr.height as usize];
+        for (y, row) in samples.chunks(self.width as usize * channels).enumerate() {
+            let out = &mut data[y * row_bytes..(y + 1) * row_bytes];
+            for (i, &sample) in row.iter().enumerate() {
+                if depth == 16 {
+                    out[2 * i..2 * i + 2].copy_from_slice(&(sample as u16).to_be_bytes());
+                } else {
+                    let bit = i * depth;
+                    out[bit / 8] |= (sample as u8) << (8 - depth - bit % 8);
+                }
+            }
+        }
+        let format = PixelFormat { ihdr: ihdr.clone(), palette, transparency, gamma: None };
+        Ok((RawImage { ihdr, data }, format))
+    }
+
+    /// Encodes this image into a new png; see `encode`.
+    pub fn to_png(&self, color_type: ColorType, bit_depth: u8) -> Result<Png> {
+        let (image, format) = self.encode(color_type, bit_depth)?;
+        let mut builder = PngBuilder::new(format.ihdr);
+        if let Some(palette) = format.palette {
+            builder = builder.palette(palette);
+        }
+        if let Some(transparency) = format.transparency {
+            builder = builder.transparency(transparency);
+        }
+        builder.rows(image.rows()).build()
+    }
+
+    /// The distinct 8-bit colors of the image and the index of each pixel's
+    /// color among them.
+    fn index_colors(&self, max_index: usize) -> Result<(Vec<[u8; 4]>, Vec<usize>)> {
+        let mut entries = Vec::new();
+        let mut positions = HashMap::new();
+        let mut indices = Vec::with_capacity(self.data.len() / 4);
+        for pixel in self.pixels() {
+            let color = pixel.map(|v| scale_down(v, 0xff) as u8);
+            let index = *positions.entry(color).or_insert_with(|| {
+                entries.push(color);
+                entries.len() - 1
+            });
+            if index > max_index {
+                return Err(anyhow!("image has more than {} colors, which do not fit in a palette of that size",
+                                   max_index + 1));
+            }
+            indices.push(index);
+        }
+        Ok((entries, indices))
+    }
+
+    /// A color for tRNS that no opaque pixel uses, if any pixel is
+    /// transparent. `colors` are the pixels already scaled to the target
+    /// depth, `channels` samples each.
+    fn transparent_key(&self, colors: &[u32], channels: usize, max: u32) -> Result<Option<Vec<u32>>> {
+        let alphas: Vec<u16> = self.pixels().map(|[.., alpha]| alpha).collect();
+        if let Some(i) = alphas.iter().position(|&alpha| alpha != 0 && alpha != 0xffff) {
+            return Err(anyhow!("pixel {} is partially transparent, which needs an alpha channel", i));
+        }
+        let first = match alphas.iter().position(|&alpha| alpha == 0) {
+            Some(first) => first,
+            None => return Ok(None),
+        };
+        let opaque: HashSet<&[u32]> = colors.chunks(channels).zip(&alphas)
+            .filter(|(_, &alpha)| alpha != 0)
+            .map(|(color, _)| color)
+            .collect();
+        std::iter::once(colors[first * channels..(first + 1) * channels].to_vec())
+            .chain((0..=max).map(|v| vec![v; channels]))
+            .find(|candidate| !opaque.contains(candidate.as_slice()))
+            .map(Some)
+            .ok_or_else(|| anyhow!("every color is used by an opaque pixel, so none can mark transparency"))
+    }
+}
+
+impl Rgba8 {
+    /// Converts `image`; see `Rgba16::from_raw`.
+    pub fn from_raw(image: &RawImage, format: &PixelFormat, display_gamma: Option<f64>) -> Result<Self> {
+        Rgba16::from_raw(image, format, display_gamma).map(|image| image.to_rgba8())
+    }
+
+    /// Decodes the pixels of `png`; see `Rgba16::from_raw`.
+    pub fn from_png(png: &Png, display_gamma: Option<f64>) -> Result<Self> {
+        Rgba16::from_png(png, display_gamma).map(|image| image.to_rgba8())
+    }
+
+    pub fn to_rgba16(&self) -> Rgba16 {
+        Rgba { width: self.width, height: self.height, data: self.data.iter().map(|&v| v as u16 * 257).collect() }
+    }
+
+    /// See `Rgba16::encode`.
+    pub fn encode(&self, color_type: ColorType, bit_depth: u8) -> Result<(RawImage, PixelFormat)> {
+        self.to_rgba16().encode(color_type, bit_depth)
+    }
+
+    /// See `Rgba16::to_png`.
+    pub fn to_png(&self, color_type: ColorType, bit_depth: u8) -> Result<Png> {
+        self.to_rgba16().to_png(color_type, bit_depth)
+    }
+}
+
+/// Rounds a 16-bit sample to the nearest value in `0..=max`.
+fn scale_down(value: u16, max: u32) -> u32 {
+    (value as u32 * max + 0x7fff) / 0xffff
+}
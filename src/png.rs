@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt;
 use std::fmt::Formatter;
@@ -9,10 +10,17 @@ use crate::chunk::Chunk;
 use anyhow::anyhow;
 use crate::{Error, Result};
 use crate::chunk_type::ChunkType;
+use crate::chunks::{Ihdr, Placement, TypedChunk};
+use crate::color::ColorInfo;
+use crate::query::ChunkQuery;
 
 pub struct Png{
     header: [u8; 8],
     chunks: Vec<Chunk>,
+    /// Positions of the chunks of every type, rebuilt whenever `chunks` changes.
+    index: HashMap<ChunkType, Vec<usize>>,
+    /// Bytes after the IEND chunk, kept so they survive a round-trip.
+    trailing: Vec<u8>,
 }
 
 impl Png {
@@ -21,21 +29,32 @@ impl Png {
 
     /// Creates a `Png` from a list of chunks using the correct header
     pub fn from_chunks(chunks: Vec<Chunk>) -> Self {
-        Png {
+        let mut png = Png {
             header: Png::STANDARD_HEADER,
-            chunks
-        }
+            chunks,
+            index: HashMap::new(),
+            trailing: Vec::new(),
+        };
+        png.reindex();
+        png
     }
 
     /// Creates a `Png` from a file path
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let mut file = File::open(path)?;
+        Png::from_reader(File::open(path)?)
+    }
+
+    /// Creates a `Png` from everything `reader` yields, such as stdin
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
         let mut content = Vec::new();
-        file.read_to_end(&mut content)?;
+        reader.read_to_end(&mut content)?;
         Png::from_str(content.as_slice())
     }
 
     fn from_str(content: &[u8]) -> Result<Self> {
+        if content.len() < Png::STANDARD_HEADER.len() {
+            return Err(anyhow!("invalid header"));
+        }
         let (header, chunks_u8) = content.split_at(Png::STANDARD_HEADER.len());
         let header = <[u8; 8]>::try_from(header)?;
         if header != Png::STANDARD_HEADER {
@@ -43,51 +62,212 @@ impl Png {
         }
         let mut chunks = Vec::new();
         let mut pos = 0usize;
-        let mut should_exit = false;
-        loop {
-            if should_exit || pos >= chunks_u8.len() {
-                break;
-            }
-            let length = match <[u8; 4]>::try_from(&chunks_u8[pos..pos+4]) {
-                Ok(tmp) => u32::from_be_bytes(tmp),
-                Err(e) => return Err(e.into()),
+        while pos < chunks_u8.len() {
+            let length = match chunks_u8.get(pos..pos+4).map(<[u8; 4]>::try_from) {
+                Some(Ok(tmp)) => u32::from_be_bytes(tmp),
+                _ => return Err(anyhow!("truncated chunk at offset {}", pos + Png::STANDARD_HEADER.len())),
             };
-            let chunk_type = match <[u8; 4]>::try_from(&chunks_u8[pos+4..pos+8]) {
-                Ok(tmp) => ChunkType::try_from(tmp)?,
-                Err(e) => return Err(e.into()),
-            };
-            let chunk = Chunk::try_from(&chunks_u8[pos..pos+(length as usize)+12])?;
+            let end = (pos + length as usize + 12).min(chunks_u8.len());
+            let chunk = Chunk::try_from(&chunks_u8[pos..end])?;
+            let is_iend = chunk.chunk_type() == ChunkType::IEND;
             chunks.push(chunk);
             pos += length as usize + 12;
+            // whatever follows IEND is not part of the image
+            if is_iend {
+                break;
+            }
+        }
+        let mut png = Png::from_chunks(chunks);
+        png.trailing = chunks_u8[pos.min(chunks_u8.len())..].to_vec();
+        Ok(png)
+    }
+
+    fn reindex(&mut self) {
+        self.index.clear();
+        for (pos, chunk) in self.chunks.iter().enumerate() {
+            self.index.entry(chunk.chunk_type()).or_default().push(pos);
         }
-        Ok(Png {
-            header,
-            chunks,
-        })
     }
 
-    /// Appends a chunk to the end of this `Png` file's `Chunk` list.
+    /// Appends a chunk to the end of this `Png` file's `Chunk` list, just
+    /// before the last chunk, which is normally IEND.
     pub fn append_chunk(&mut self, chunk: Chunk) {
-        self.chunks.insert(self.chunks.len()-1, chunk);
+        if self.chunks.is_empty() {
+            self.chunks.push(chunk);
+        } else {
+            self.chunks.insert(self.chunks.len() - 1, chunk);
+        }
+        self.reindex();
+    }
+
+    /// Inserts `chunk` at position `index` of the chunk list.
+    pub fn insert_chunk(&mut self, index: usize, chunk: Chunk) -> Result<()> {
+        if index > self.chunks.len() {
+            return Err(anyhow!("chunk index {} out of range", index));
+        }
+        self.chunks.insert(index, chunk);
+        self.reindex();
+        Ok(())
+    }
+
+    /// Replaces the chunk at position `index`, returning the old one.
+    pub fn replace_chunk(&mut self, index: usize, chunk: Chunk) -> Result<Chunk> {
+        let slot = self.chunks.get_mut(index)
+            .ok_or_else(|| anyhow!("chunk index {} out of range", index))?;
+        let old = std::mem::replace(slot, chunk);
+        self.reindex();
+        Ok(old)
+    }
+
+    /// Replaces the first chunk of the same type as `chunk`, or appends it if
+    /// there is none. Returns the replaced chunk.
+    pub fn set_chunk(&mut self, chunk: Chunk) -> Option<Chunk> {
+        match self.positions_of(&chunk.chunk_type()).first().copied() {
+            Some(position) => Some(std::mem::replace(&mut self.chunks[position], chunk)),
+            None => {
+                self.append_chunk(chunk);
+                None
+            },
+        }
     }
 
     /// Searches for a `Chunk` with the specified `chunk_type` and removes the first
     /// matching `Chunk` from this `Png` list of chunks.
     pub fn remove_chunk(&mut self, chunk_type: &str) -> Result<Chunk> {
         let chunk_type = ChunkType::from_str(chunk_type)?;
-        let pos = self.chunks.iter()
-            .position(|chunk| chunk.chunk_type() == chunk_type);
-        if let Some(pos) = pos {
-            return Ok(self.chunks.remove(pos));
+        if let Some(&pos) = self.positions_of(&chunk_type).first() {
+            let chunk = self.chunks.remove(pos);
+            self.reindex();
+            return Ok(chunk);
         }
         Err(anyhow!("Chunk type not found, type = {:?} ", chunk_type))
     }
 
+    /// Keeps only the chunks for which `keep` returns true.
+    pub fn retain_chunks<F: FnMut(&Chunk) -> bool>(&mut self, keep: F) {
+        self.chunks.retain(keep);
+        self.reindex();
+    }
+
+    /// Returns the first `Chunk` of type `chunk_type`, if the name is a valid type.
     pub fn get_chunk(&self, chunk_type: &str) -> Option<&Chunk> {
         let chunk_type = ChunkType::from_str(chunk_type).ok()?;
-        self.chunks.iter()
-            .position(|chunk| chunk.chunk_type() == chunk_type)
-            .and_then(|pos| self.chunks.get(pos))
+        self.chunks_of_type(&chunk_type).next()
+    }
+
+    /// Positions in `chunks()` of every chunk of type `chunk_type`, in file order.
+    pub fn positions_of(&self, chunk_type: &ChunkType) -> &[usize] {
+        self.index.get(chunk_type).map_or(&[], Vec::as_slice)
+    }
+
+    /// Iterates over the chunks of type `chunk_type`, in file order.
+    pub fn chunks_of_type<'a>(&'a self, chunk_type: &ChunkType) -> impl Iterator<Item = &'a Chunk> + 'a {
+        self.positions_of(chunk_type).iter().map(move |&pos| &self.chunks[pos])
+    }
+
+    /// Returns the first chunk matching `predicate`.
+    pub fn find<P: FnMut(&&Chunk) -> bool>(&self, predicate: P) -> Option<&Chunk> {
+        self.chunks.iter().find(predicate)
+    }
+
+    /// Iterates over every chunk matching `predicate`.
+    pub fn find_all<'a, P>(&'a self, predicate: P) -> impl Iterator<Item = &'a Chunk> + 'a
+        where P: FnMut(&&Chunk) -> bool + 'a
+    {
+        self.chunks.iter().filter(predicate)
+    }
+
+    /// Iterates over the chunks selected by `query`, using the type index
+    /// when the query names a chunk type.
+    pub fn query<'a>(&'a self, query: &'a ChunkQuery) -> impl Iterator<Item = &'a Chunk> + 'a {
+        let positions: Box<dyn Iterator<Item = usize>> = match query.get_chunk_type() {
+            Some(chunk_type) => Box::new(self.positions_of(chunk_type).iter().copied()),
+            None => Box::new(0..self.chunks.len()),
+        };
+        positions.map(move |pos| &self.chunks[pos])
+            .filter(move |chunk| query.matches(chunk))
+    }
+
+    /// Decodes the first chunk of type `T::TYPE`, if there is one.
+    pub fn get<T: TypedChunk>(&self) -> Result<Option<T>> {
+        self.chunks_of_type(&T::TYPE).next().map(|chunk| T::decode(chunk.data())).transpose()
+    }
+
+    /// Decodes every chunk of type `T::TYPE`, in file order.
+    pub fn get_all<T: TypedChunk>(&self) -> Result<Vec<T>> {
+        self.chunks_of_type(&T::TYPE).map(|chunk| T::decode(chunk.data())).collect()
+    }
+
+    /// Stores `value`. A type that may appear only once replaces the existing
+    /// chunk in place and drops any duplicates; otherwise the chunk is
+    /// inserted where `T::PLACEMENT` allows. Fails, leaving the png as it
+    /// was, if `value` cannot be encoded.
+    pub fn set<T: TypedChunk>(&mut self, value: &T) -> Result<()> {
+        let chunk = Chunk::new(T::TYPE, value.encode()?);
+        if !T::MULTIPLE {
+            let positions = self.positions_of(&T::TYPE).to_vec();
+            if let Some((&first, duplicates)) = positions.split_first() {
+                for &position in duplicates.iter().rev() {
+                    self.chunks.remove(position);
+                }
+                self.chunks[first] = chunk;
+                self.reindex();
+                return Ok(());
+            }
+        }
+        let position = self.insert_position(T::PLACEMENT);
+        self.chunks.insert(position, chunk);
+        self.reindex();
+        Ok(())
+    }
+
+    /// Where a new chunk with `placement` goes: as late as the placement
+    /// allows, but after IHDR and before IEND.
+    fn insert_position(&self, placement: Placement) -> usize {
+        let first = |chunk_type: &ChunkType| self.positions_of(chunk_type).first().copied();
+        let end = first(&ChunkType::IEND).unwrap_or(self.chunks.len());
+        let position = match placement {
+            Placement::Anywhere => end,
+            Placement::BeforeIdat | Placement::AfterPalette => first(&ChunkType::IDAT).unwrap_or(end),
+            Placement::BeforePalette => first(&ChunkType::PLTE).or_else(|| first(&ChunkType::IDAT)).unwrap_or(end),
+        };
+        match self.chunks.first() {
+            Some(chunk) if chunk.chunk_type() == ChunkType::IHDR => position.max(1),
+            _ => position,
+        }
+    }
+
+    /// Decodes the IHDR chunk, which the spec requires to come first.
+    pub fn ihdr(&self) -> Result<Ihdr> {
+        match self.chunks.first() {
+            Some(chunk) if chunk.chunk_type() == ChunkType::IHDR => Ihdr::try_from(chunk.data()),
+            _ => Err(anyhow!("png does not start with an IHDR chunk")),
+        }
+    }
+
+    /// The color space declared by the color management chunks.
+    pub fn color_info(&self) -> ColorInfo {
+        ColorInfo::new(self)
+    }
+
+    /// The bytes found after the IEND chunk, such as an appended archive.
+    pub fn trailing_data(&self) -> &[u8] {
+        &self.trailing
+    }
+
+    /// Byte offset of the trailing data in the encoded file.
+    pub fn trailing_offset(&self) -> usize {
+        self.chunks.iter().fold(self.header.len(), |offset, chunk| offset + chunk.length() as usize + 12)
+    }
+
+    /// Replaces the bytes written after the IEND chunk.
+    pub fn set_trailing_data(&mut self, trailing: Vec<u8>) {
+        self.trailing = trailing;
+    }
+
+    /// Removes and returns the bytes after the IEND chunk.
+    pub fn take_trailing_data(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.trailing)
     }
 
     /// The header of this PNG.
@@ -100,26 +280,33 @@ impl Png {
         &self.chunks
     }
 
+    /// Pairs every `Chunk` with its absolute byte offset in the encoded file.
+    pub fn chunks_with_offsets(&self) -> impl Iterator<Item = (usize, &Chunk)> {
+        self.chunks.iter()
+            .scan(self.header.len(), |offset, chunk| {
+                let current = *offset;
+                *offset += chunk.length() as usize + 12;
+                Some((current, chunk))
+            })
+    }
+
     /// Searches for a `Chunk` with the specified `chunk_type` and returns the first
-    /// matching `Chunk` from this `Png`.
+    /// matching `Chunk` from this `Png`. Same as `get_chunk`.
     pub fn chunk_by_type(&self, chunk_type: &str) -> Option<&Chunk> {
-        let pos = self.chunks.iter()
-            .position(|chunk| chunk.chunk_type().bytes()[..].as_ref() == chunk_type.as_bytes());
-        if let Some(pos) = pos {
-            return Some(&self.chunks[pos]);
-        }
-        None
+        self.get_chunk(chunk_type)
     }
 
     /// Returns this `Png` as a byte sequence.
-    /// These bytes will contain the header followed by the bytes of all of the chunks.
+    /// These bytes will contain the header followed by the bytes of all of the chunks,
+    /// then any trailing data.
     pub fn as_bytes(&self) -> Vec<u8> {
-        let mut bytes: Vec<u8> = Vec::from(self.header.clone());
+        let mut bytes: Vec<u8> = Vec::from(self.header);
         let mut chunks = self.chunks.
             iter().
             flat_map(|chunk| chunk.as_bytes()).
             collect::<Vec<u8>>();
         bytes.append(&mut chunks);
+        bytes.extend_from_slice(&self.trailing);
         bytes
     }
 }
@@ -137,4 +324,42 @@ impl fmt::Display for Png {
         write!(f, "{:?}", self.as_bytes())?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for Png {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        use base64::Engine;
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Png", 2)?;
+        state.serialize_field("chunks", &self.chunks)?;
+        if self.trailing.is_empty() {
+            state.skip_field("trailing")?;
+        } else {
+            state.serialize_field("trailing", &base64::engine::general_purpose::STANDARD.encode(&self.trailing))?;
+        }
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Png {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        use base64::Engine;
+
+        #[derive(serde::Deserialize)]
+        struct PngRepr {
+            chunks: Vec<Chunk>,
+            /// Base64 of the bytes after IEND.
+            #[serde(default)]
+            trailing: String,
+        }
+
+        let repr = PngRepr::deserialize(deserializer)?;
+        let mut png = Png::from_chunks(repr.chunks);
+        png.trailing = base64::engine::general_purpose::STANDARD
+            .decode(repr.trailing.split_whitespace().collect::<String>())
+            .map_err(serde::de::Error::custom)?;
+        Ok(png)
+    }
+}
@@ -0,0 +1,61 @@
+//! Selecting chunks by type and by the property bits of their type.
+
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+
+/// A filter over chunks. Every criterion left unset matches any chunk.
+///
+/// ```
+/// use pngme::query::ChunkQuery;
+///
+/// let private_ancillary = ChunkQuery::new().critical(false).public(false);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkQuery {
+    chunk_type: Option<ChunkType>,
+    critical: Option<bool>,
+    public: Option<bool>,
+    safe_to_copy: Option<bool>,
+}
+
+impl ChunkQuery {
+    pub fn new() -> Self {
+        ChunkQuery::default()
+    }
+
+    /// Only matches chunks of type `chunk_type`.
+    pub fn chunk_type(mut self, chunk_type: ChunkType) -> Self {
+        self.chunk_type = Some(chunk_type);
+        self
+    }
+
+    /// Only matches critical chunks if `critical`, ancillary ones otherwise.
+    pub fn critical(mut self, critical: bool) -> Self {
+        self.critical = Some(critical);
+        self
+    }
+
+    /// Only matches public chunks if `public`, private ones otherwise.
+    pub fn public(mut self, public: bool) -> Self {
+        self.public = Some(public);
+        self
+    }
+
+    /// Only matches chunks that are safe to copy if `safe_to_copy`, unsafe ones otherwise.
+    pub fn safe_to_copy(mut self, safe_to_copy: bool) -> Self {
+        self.safe_to_copy = Some(safe_to_copy);
+        self
+    }
+
+    pub fn get_chunk_type(&self) -> Option<&ChunkType> {
+        self.chunk_type.as_ref()
+    }
+
+    pub fn matches(&self, chunk: &Chunk) -> bool {
+        let chunk_type = chunk.chunk_type();
+        self.chunk_type.as_ref().is_none_or(|expected| *expected == chunk_type)
+            && self.critical.is_none_or(|critical| chunk_type.is_critical() == critical)
+            && self.public.is_none_or(|public| chunk_type.is_public() == public)
+            && self.safe_to_copy.is_none_or(|safe| chunk_type.is_safe_to_copy() == safe)
+    }
+}
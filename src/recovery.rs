@@ -0,0 +1,206 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+
+use crate::chunk::Chunk;
+use crate::chunk_type::ChunkType;
+use crate::png::Png;
+
+/// Length, type and CRC fields that surround every chunk's data.
+const CHUNK_OVERHEAD: usize = 12;
+
+/// A problem found, and worked around, while recovering a damaged file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum Issue {
+    /// The file did not start with the PNG signature.
+    InvalidHeader,
+    /// A chunk's stored CRC did not match its contents.
+    CrcMismatch { offset: usize, chunk_type: String, stored: u32, computed: u32 },
+    /// Bytes that could not be parsed as a chunk were skipped.
+    Garbage { offset: usize, length: usize },
+    /// The file ended in the middle of a chunk.
+    Truncated { offset: usize, chunk_type: String, declared: usize, available: usize },
+    /// The file had no IEND chunk.
+    MissingIend,
+    /// Bytes followed IEND; they are kept as the png's trailing data.
+    TrailingData { offset: usize, length: usize },
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::InvalidHeader => write!(f, "invalid PNG signature, replaced with the standard one"),
+            Issue::CrcMismatch { offset, chunk_type, stored, computed } =>
+                write!(f, "{} at offset {}: crc {:08x} does not match computed {:08x}", chunk_type, offset, stored, computed),
+            Issue::Garbage { offset, length } =>
+                write!(f, "skipped {} unparseable bytes at offset {}", length, offset),
+            Issue::Truncated { offset, chunk_type, declared, available } =>
+                write!(f, "{} at offset {}: file ends inside the chunk, {} of {} data bytes present", chunk_type, offset, available, declared),
+            Issue::MissingIend => write!(f, "missing IEND chunk, synthesized one"),
+            Issue::TrailingData { offset, length } =>
+                write!(f, "{} bytes of data after IEND at offset {}", length, offset),
+        }
+    }
+}
+
+/// The result of parsing a possibly damaged PNG.
+///
+/// Chunks keep their stored CRC, so `png` still reports mismatches; use
+/// `repaired` to get a file with every CRC recomputed.
+pub struct Recovery {
+    pub png: Png,
+    pub issues: Vec<Issue>,
+}
+
+impl Recovery {
+    /// Parses `content` without giving up on bad CRCs, garbage between
+    /// chunks, a truncated final chunk or a missing IEND.
+    pub fn from_bytes(content: &[u8]) -> Self {
+        let mut issues = Vec::new();
+        let header_len = Png::STANDARD_HEADER.len();
+        if content.len() < header_len || content[..header_len] != Png::STANDARD_HEADER {
+            issues.push(Issue::InvalidHeader);
+        }
+
+        let mut chunks = Vec::new();
+        let mut pos = header_len.min(content.len());
+        while pos < content.len() {
+            let header = ChunkHeader::read(content, pos);
+            let chunk = header.as_ref()
+                .filter(|header| header.fits(content))
+                .map(|header| (header, header.chunk(content)))
+                // A bad CRC may come from a corrupted length field, so only keep
+                // the chunk if another chunk, or the end of the file, follows it
+                .filter(|(header, chunk)| chunk.is_crc_valid() || header.is_followed(content));
+            if let Some((header, chunk)) = chunk {
+                if !chunk.is_crc_valid() {
+                    issues.push(Issue::CrcMismatch {
+                        offset: pos,
+                        chunk_type: header.chunk_type.to_string(),
+                        stored: chunk.crc(),
+                        computed: chunk.computed_crc(),
+                    });
+                }
+                pos = header.end();
+                let is_iend = chunk.chunk_type() == ChunkType::IEND;
+                chunks.push(chunk);
+                if is_iend {
+                    break;
+                }
+                continue;
+            }
+
+            if let Some(next) = resync(content, pos + 1) {
+                issues.push(Issue::Garbage { offset: pos, length: next - pos });
+                pos = next;
+                continue;
+            }
+
+            match header {
+                Some(header) if !header.fits(content) => {
+                    let data_end = (pos + 8 + header.length).min(content.len());
+                    let data = content[pos + 8..data_end].to_vec();
+                    issues.push(Issue::Truncated {
+                        offset: pos,
+                        chunk_type: header.chunk_type.to_string(),
+                        declared: header.length,
+                        available: data.len(),
+                    });
+                    chunks.push(Chunk::new(header.chunk_type, data));
+                },
+                _ => issues.push(Issue::Garbage { offset: pos, length: content.len() - pos }),
+            }
+            pos = content.len();
+        }
+
+        let mut trailing = Vec::new();
+        if pos < content.len() {
+            issues.push(Issue::TrailingData { offset: pos, length: content.len() - pos });
+            trailing = content[pos..].to_vec();
+        }
+        if chunks.last().map(|chunk| chunk.chunk_type()) != Some(ChunkType::IEND) {
+            issues.push(Issue::MissingIend);
+            chunks.push(Chunk::new(ChunkType::IEND, Vec::new()));
+        }
+
+        let mut png = Png::from_chunks(chunks);
+        png.set_trailing_data(trailing);
+        Recovery { png, issues }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The recovered file with every chunk's CRC recomputed. Any data after
+    /// IEND is kept.
+    pub fn repaired(&self) -> Png {
+        let chunks = self.png.chunks().iter()
+            .map(|chunk| Chunk::new(chunk.chunk_type(), chunk.data().to_vec()))
+            .collect();
+        let mut png = Png::from_chunks(chunks);
+        png.set_trailing_data(self.png.trailing_data().to_vec());
+        png
+    }
+}
+
+/// The length and type fields at the start of a chunk.
+struct ChunkHeader {
+    offset: usize,
+    length: usize,
+    chunk_type: ChunkType,
+}
+
+impl ChunkHeader {
+    /// Reads a header at `offset` if it looks like one: the type must be four
+    /// letters with a valid reserved bit and the length must be in range.
+    fn read(content: &[u8], offset: usize) -> Option<Self> {
+        let bytes = content.get(offset..offset + 8)?;
+        let length = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let chunk_type = ChunkType::try_from([bytes[4], bytes[5], bytes[6], bytes[7]]).ok()?;
+        if length > i32::MAX as u32 || !chunk_type.is_valid() {
+            return None;
+        }
+        Some(ChunkHeader {
+            offset,
+            length: length as usize,
+            chunk_type,
+        })
+    }
+
+    fn end(&self) -> usize {
+        self.offset + self.length + CHUNK_OVERHEAD
+    }
+
+    fn fits(&self, content: &[u8]) -> bool {
+        self.end() <= content.len()
+    }
+
+    /// Whether the chunk ends at IEND, at the end of `content` or right
+    /// before something that looks like another chunk header.
+    fn is_followed(&self, content: &[u8]) -> bool {
+        self.chunk_type == ChunkType::IEND
+            || self.end() == content.len()
+            || ChunkHeader::read(content, self.end()).is_some()
+    }
+
+    fn chunk(&self, content: &[u8]) -> Chunk {
+        let data = content[self.offset + 8..self.end() - 4].to_vec();
+        let crc = &content[self.end() - 4..self.end()];
+        let crc = u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]);
+        Chunk::with_stored_crc(self.chunk_type.clone(), data, crc)
+    }
+}
+
+/// Finds the first offset at or after `from` that holds a complete chunk
+/// with a matching CRC. Requiring the CRC keeps random bytes that happen to
+/// look like a chunk header from being picked up.
+fn resync(content: &[u8], from: usize) -> Option<usize> {
+    (from..content.len().saturating_sub(CHUNK_OVERHEAD - 1))
+        .find(|&offset| {
+            ChunkHeader::read(content, offset)
+                .filter(|header| header.fits(content))
+                .is_some_and(|header| header.chunk(content).is_crc_valid())
+        })
+}
@@ -0,0 +1,45 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use crate::Result;
+use crate::png::Png;
+
+/// The path that stands for stdin when reading and stdout when writing.
+pub const STDIO_PATH: &str = "-";
+
+pub fn is_stdio(path: &Path) -> bool {
+    path == Path::new(STDIO_PATH)
+}
+
+/// Reads all of `path`, or of stdin when it is `-`.
+pub fn read(path: &Path) -> Result<Vec<u8>> {
+    if is_stdio(path) {
+        let mut content = Vec::new();
+        io::stdin().lock().read_to_end(&mut content)?;
+        Ok(content)
+    } else {
+        Ok(fs::read(path)?)
+    }
+}
+
+/// Writes `bytes` to `path`, or to stdout when it is `-`.
+pub fn write(path: &Path, bytes: &[u8]) -> Result<()> {
+    if is_stdio(path) {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(bytes)?;
+        stdout.flush()?;
+    } else {
+        fs::write(path, bytes)?;
+    }
+    Ok(())
+}
+
+/// Parses the PNG at `path`, or on stdin when it is `-`.
+pub fn read_png(path: &Path) -> Result<Png> {
+    if is_stdio(path) {
+        Png::from_reader(io::stdin().lock())
+    } else {
+        Png::from_file(path)
+    }
+}
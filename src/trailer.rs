@@ -0,0 +1,64 @@
+//! Payloads hidden after the IEND chunk, where most readers never look.
+//!
+//! A payload is framed as `MAGIC`, its length as a big-endian u32, the
+//! payload itself and the CRC-32 of the payload. The frame is appended to
+//! whatever other data already follows IEND.
+
+use std::convert::{TryFrom, TryInto};
+use anyhow::anyhow;
+
+use crate::Result;
+use crate::chunk;
+use crate::png::Png;
+
+pub const MAGIC: &[u8; 8] = b"PNGMETRL";
+const FRAME_OVERHEAD: usize = MAGIC.len() + 8;
+
+/// Frames `payload` for storage after IEND. Fails if the payload is too
+/// long for its length field.
+pub fn frame(payload: &[u8]) -> Result<Vec<u8>> {
+    let length = u32::try_from(payload.len())
+        .map_err(|_| anyhow!("trailer payload of {} bytes is too long", payload.len()))?;
+    let mut frame = Vec::with_capacity(FRAME_OVERHEAD + payload.len());
+    frame.extend_from_slice(MAGIC);
+    frame.extend_from_slice(&length.to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame.extend_from_slice(&chunk::CRC32.checksum(payload).to_be_bytes());
+    Ok(frame)
+}
+
+/// Finds the first frame in `trailing`, returning its offset in `trailing`
+/// and its payload. Fails if a frame is found but is truncated or corrupt.
+pub fn read(trailing: &[u8]) -> Result<Option<(usize, Vec<u8>)>> {
+    let start = match trailing.windows(MAGIC.len()).position(|window| window == MAGIC) {
+        Some(start) => start,
+        None => return Ok(None),
+    };
+    let frame = &trailing[start..];
+    let length = frame.get(MAGIC.len()..MAGIC.len() + 4)
+        .map(|length| u32::from_be_bytes(length.try_into().expect("slice is 4 bytes")) as usize)
+        .ok_or_else(|| anyhow!("trailer at offset {} is truncated", start))?;
+    let payload_start = MAGIC.len() + 4;
+    let (payload, crc) = match (frame.get(payload_start..payload_start + length),
+                                frame.get(payload_start + length..payload_start + length + 4)) {
+        (Some(payload), Some(crc)) => (payload, u32::from_be_bytes(crc.try_into().expect("slice is 4 bytes"))),
+        _ => return Err(anyhow!("trailer at offset {} is truncated", start)),
+    };
+    if chunk::CRC32.checksum(payload) != crc {
+        return Err(anyhow!("trailer at offset {}: crc mismatched", start));
+    }
+    Ok(Some((start, payload.to_vec())))
+}
+
+/// Stores `payload` after the IEND chunk of `png`, replacing a frame
+/// written before and keeping any other trailing data.
+pub fn write(png: &mut Png, payload: &[u8]) -> Result<()> {
+    let frame = frame(payload)?;
+    let mut trailing = png.take_trailing_data();
+    if let Some((start, old)) = read(&trailing)? {
+        trailing.drain(start..start + FRAME_OVERHEAD + old.len());
+    }
+    trailing.extend_from_slice(&frame);
+    png.set_trailing_data(trailing);
+    Ok(())
+}
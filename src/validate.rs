@@ -0,0 +1,188 @@
+use std::convert::TryFrom;
+use anyhow::anyhow;
+
+use crate::chunk_type::ChunkType;
+use crate::chunks::{
+    Background, ColorType, ContentLightLevel, Exif, Histogram, Ihdr, MasteringDisplay, Palette, SignificantBits,
+    Transparency,
+};
+use crate::png::Png;
+
+/// Chunks that may appear at most once.
+const UNIQUE: [ChunkType; 16] = [
+    ChunkType::IHDR, ChunkType::PLTE, ChunkType::TRNS, ChunkType::IEND,
+    ChunkType::CHRM, ChunkType::CICP, ChunkType::CLLI, ChunkType::GAMA, ChunkType::ICCP, ChunkType::MDCV, ChunkType::SRGB,
+    ChunkType::BKGD, ChunkType::EXIF, ChunkType::HIST, ChunkType::PHYS, ChunkType::SBIT,
+];
+
+/// Chunks that must come before PLTE and IDAT.
+pub(crate) const COLOR_CHUNKS: [ChunkType; 8] = [
+    ChunkType::CHRM, ChunkType::CICP, ChunkType::CLLI, ChunkType::GAMA, ChunkType::ICCP, ChunkType::MDCV,
+    ChunkType::SBIT, ChunkType::SRGB,
+];
+
+/// Chunks that must come after PLTE, if there is one, and before IDAT.
+pub(crate) const PALETTE_CHUNKS: [ChunkType; 3] = [ChunkType::BKGD, ChunkType::HIST, ChunkType::TRNS];
+
+/// Other chunks that must come before IDAT.
+pub(crate) const BEFORE_IDAT: [ChunkType; 3] = [ChunkType::EXIF, ChunkType::PHYS, ChunkType::SPLT];
+
+/// Checks the chunk structure of `png` against the PNG spec and returns a
+/// description of every violation found; an empty list means it is valid.
+pub fn validate(png: &Png) -> Vec<String> {
+    let mut problems = Vec::new();
+    let chunks = png.chunks();
+    let types: Vec<ChunkType> = chunks.iter().map(|chunk| chunk.chunk_type()).collect();
+    let positions = |wanted: &ChunkType| -> Vec<usize> {
+        types.iter().enumerate()
+            .filter(|(_, chunk_type)| *chunk_type == wanted)
+            .map(|(i, _)| i)
+            .collect()
+    };
+
+    let ihdr = match chunks.first() {
+        Some(chunk) if chunk.chunk_type() == ChunkType::IHDR => match Ihdr::try_from(chunk.data()) {
+            Ok(ihdr) => Some(ihdr),
+            Err(e) => {
+                problems.push(format!("invalid IHDR: {}", e));
+                None
+            },
+        },
+        _ => {
+            problems.push("first chunk is not IHDR".to_string());
+            None
+        },
+    };
+    for unique in UNIQUE.iter() {
+        if positions(unique).len() > 1 {
+            problems.push(format!("more than one {} chunk", unique));
+        }
+    }
+    match chunks.last() {
+        Some(chunk) if chunk.chunk_type() == ChunkType::IEND => {
+            if chunk.length() != 0 {
+                problems.push("IEND chunk is not empty".to_string());
+            }
+        },
+        _ => problems.push("last chunk is not IEND".to_string()),
+    }
+
+    let idat = positions(&ChunkType::IDAT);
+    match (idat.first(), idat.last()) {
+        (Some(&first), Some(&last)) => {
+            if last - first + 1 != idat.len() {
+                problems.push("IDAT chunks are not consecutive".to_string());
+            }
+            if positions(&ChunkType::PLTE).iter().any(|&plte| plte > first) {
+                problems.push("PLTE chunk after IDAT".to_string());
+            }
+            for chunk_type in PALETTE_CHUNKS.iter().chain(BEFORE_IDAT.iter()) {
+                if positions(chunk_type).iter().any(|&position| position > first) {
+                    problems.push(format!("{} chunk after IDAT", chunk_type));
+                }
+            }
+        },
+        _ => problems.push("no IDAT chunk".to_string()),
+    }
+    for color_chunk in COLOR_CHUNKS.iter() {
+        if let Some(&position) = positions(color_chunk).first() {
+            if positions(&ChunkType::PLTE).iter().any(|&plte| plte < position) {
+                problems.push(format!("{} chunk after PLTE", color_chunk));
+            }
+            if idat.first().is_some_and(|&first| position > first) {
+                problems.push(format!("{} chunk after IDAT", color_chunk));
+            }
+        }
+    }
+    if !positions(&ChunkType::ICCP).is_empty() && !positions(&ChunkType::SRGB).is_empty() {
+        problems.push("both iCCP and sRGB chunks".to_string());
+    }
+    if let Some(Err(e)) = png.chunks_of_type(&ChunkType::EXIF).next().map(Exif::try_from) {
+        problems.push(format!("invalid eXIf: {}", e));
+    }
+    if let Some(Err(e)) = png.chunks_of_type(&ChunkType::MDCV).next().map(MasteringDisplay::try_from) {
+        problems.push(format!("invalid mDCv: {}", e));
+    }
+    if let Some(Err(e)) = png.chunks_of_type(&ChunkType::CLLI).next().map(ContentLightLevel::try_from) {
+        problems.push(format!("invalid cLLI: {}", e));
+    }
+    if let Some(ihdr) = ihdr {
+        let has_plte = !positions(&ChunkType::PLTE).is_empty();
+        match ihdr.color_type {
+            ColorType::Indexed if !has_plte => problems.push("indexed image without PLTE chunk".to_string()),
+            ColorType::Grayscale | ColorType::GrayscaleAlpha if has_plte =>
+                problems.push("grayscale image with PLTE chunk".to_string()),
+            _ => {},
+        }
+
+        // a grayscale image's PLTE has already been reported above
+        let palette = png.chunks_of_type(&ChunkType::PLTE).next()
+            .filter(|_| !matches!(ihdr.color_type, ColorType::Grayscale | ColorType::GrayscaleAlpha))
+            .map(|chunk| Palette::try_from(chunk).and_then(|palette| {
+                palette.validate_for(&ihdr)?;
+                Ok(palette)
+            }));
+        let palette = match palette {
+            Some(Ok(palette)) => Some(palette),
+            Some(Err(e)) => {
+                problems.push(format!("invalid PLTE: {}", e));
+                None
+            },
+            None => None,
+        };
+        if let Some(chunk) = png.chunks_of_type(&ChunkType::TRNS).next() {
+            let transparency = Transparency::from_bytes(chunk.data(), ihdr.color_type)
+                .and_then(|trns| trns.validate_for(&ihdr, palette.as_ref()));
+            if let Err(e) = transparency {
+                problems.push(format!("invalid tRNS: {}", e));
+            }
+        }
+        if let Some(chunk) = png.chunks_of_type(&ChunkType::BKGD).next() {
+            let background = Background::from_bytes(chunk.data(), ihdr.color_type)
+                .and_then(|bkgd| bkgd.validate_for(&ihdr, palette.as_ref()));
+            if let Err(e) = background {
+                problems.push(format!("invalid bKGD: {}", e));
+            }
+        }
+        if let Some(chunk) = png.chunks_of_type(&ChunkType::SBIT).next() {
+            let bits = SignificantBits::from_bytes(chunk.data(), ihdr.color_type)
+                .and_then(|sbit| sbit.validate_for(&ihdr));
+            if let Err(e) = bits {
+                problems.push(format!("invalid sBIT: {}", e));
+            }
+        }
+        if let Some(chunk) = png.chunks_of_type(&ChunkType::HIST).next() {
+            let histogram = Histogram::try_from(chunk).and_then(|hist| match palette.as_ref() {
+                Some(palette) => hist.validate_for(palette),
+                None => Err(anyhow!("no palette to describe")),
+            });
+            if let Err(e) = histogram {
+                problems.push(format!("invalid hIST: {}", e));
+            }
+        }
+    }
+    if let Some(&plte) = positions(&ChunkType::PLTE).first() {
+        for chunk_type in PALETTE_CHUNKS.iter() {
+            if positions(chunk_type).first().is_some_and(|&position| position < plte) {
+                problems.push(format!("{} chunk before PLTE", chunk_type));
+            }
+        }
+    }
+
+    for (i, chunk) in chunks.iter().enumerate() {
+        let chunk_type = chunk.chunk_type();
+        if !chunk_type.is_valid() {
+            problems.push(format!("chunk #{} has invalid type {}", i, chunk_type));
+        } else if chunk_type.is_critical() && !is_known_critical(&chunk_type) {
+            problems.push(format!("chunk #{} is an unknown critical chunk {}", i, chunk_type));
+        }
+        if !chunk.is_crc_valid() {
+            problems.push(format!("chunk #{} ({}) has a bad crc", i, chunk_type));
+        }
+    }
+    problems
+}
+
+fn is_known_critical(chunk_type: &ChunkType) -> bool {
+    [ChunkType::IHDR, ChunkType::PLTE, ChunkType::IDAT, ChunkType::IEND].contains(chunk_type)
+}
@@ -0,0 +1,126 @@
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use pngme::append;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::png::Png;
+
+    fn testing_png() -> Png {
+        Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("FrSt").unwrap(), b"I am the first chunk".to_vec()),
+            Chunk::new(ChunkType::from_str("miDl").unwrap(), b"I am another chunk".to_vec()),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ])
+    }
+
+    fn testing_chunk() -> Chunk {
+        Chunk::new(ChunkType::from_str("ruSt").unwrap(), b"appended".to_vec())
+    }
+
+    fn testing_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("pngme_append_{}_{}.png", name, std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_append_chunk() {
+        let path = testing_file("append", &testing_png().as_bytes());
+        append::append_chunk(&path, &testing_chunk()).unwrap();
+
+        let mut expected = testing_png();
+        expected.append_chunk(testing_chunk());
+        assert_eq!(fs::read(&path).unwrap(), expected.as_bytes());
+        assert!(!append::journal_path(&path).exists());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_append_keeps_trailing_data() {
+        let mut bytes = testing_png().as_bytes();
+        bytes.extend_from_slice(b"trailing");
+        let path = testing_file("trailing", &bytes);
+        append::append_chunk(&path, &testing_chunk()).unwrap();
+
+        let mut expected = testing_png();
+        expected.append_chunk(testing_chunk());
+        let mut expected = expected.as_bytes();
+        expected.extend_from_slice(b"trailing");
+        assert_eq!(fs::read(&path).unwrap(), expected);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_iend() {
+        let bytes = testing_png().as_bytes();
+        let path = testing_file("no_iend", &bytes[..bytes.len() - 12]);
+        assert!(append::append_chunk(&path, &testing_chunk()).is_err());
+        assert_eq!(fs::read(&path).unwrap(), &bytes[..bytes.len() - 12]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_last_chunk_overruns_file() {
+        let mut bytes = testing_png().as_bytes();
+        let length = bytes.len() - 12;
+        bytes.truncate(length);
+        // miDl claims more data than is left in the file
+        let midl = bytes.len() - 18 - 12;
+        bytes[midl..midl + 4].copy_from_slice(&1000u32.to_be_bytes());
+        let path = testing_file("overrun", &bytes);
+        let error = append::append_chunk(&path, &testing_chunk()).unwrap_err();
+        assert!(error.to_string().contains("truncated"), "{}", error);
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+        assert!(!append::journal_path(&path).exists());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_iend() {
+        let mut bytes = testing_png().as_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        let path = testing_file("bad_iend", &bytes);
+        assert!(append::append_chunk(&path, &testing_chunk()).is_err());
+        assert_eq!(fs::read(&path).unwrap(), bytes);
+        assert!(!append::journal_path(&path).exists());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_recover_interrupted_append() {
+        let original = testing_png().as_bytes();
+        let path = testing_file("recover", &original);
+        append::append_chunk(&path, &testing_chunk()).unwrap();
+
+        // Forge the journal an interrupted append would have left behind.
+        let iend = (original.len() - 12) as u64;
+        let mut journal = b"PNGMEJNL".to_vec();
+        journal.extend_from_slice(&(original.len() as u64).to_be_bytes());
+        journal.extend_from_slice(&iend.to_be_bytes());
+        journal.extend_from_slice(&original[iend as usize..]);
+        let crc = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC).checksum(&journal);
+        journal.extend_from_slice(&crc.to_be_bytes());
+        fs::write(append::journal_path(&path), journal).unwrap();
+
+        assert!(append::recover(&path).unwrap());
+        assert_eq!(fs::read(&path).unwrap(), original);
+        assert!(!append::journal_path(&path).exists());
+        assert!(!append::recover(&path).unwrap());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_incomplete_journal_is_discarded() {
+        let original = testing_png().as_bytes();
+        let path = testing_file("incomplete", &original);
+        fs::write(append::journal_path(&path), b"PNGMEJNL\0\0").unwrap();
+
+        assert!(append::recover(&path).unwrap());
+        assert_eq!(fs::read(&path).unwrap(), original);
+        assert!(!append::journal_path(&path).exists());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,94 @@
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use anyhow::anyhow;
+    use pngme::batch::{self, BatchOptions, Outcome, Summary};
+    use pngme::stdio;
+    use pngme::Result;
+
+    /// Creates a fresh directory tree under the system temp dir:
+    /// a.png, b.txt, sub/c.png and sub/deeper/d.PNG.
+    fn testing_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pngme_batch_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub/deeper")).unwrap();
+        for file in ["a.png", "b.txt", "sub/c.png", "sub/deeper/d.PNG"].iter() {
+            fs::write(dir.join(file), file.as_bytes()).unwrap();
+        }
+        dir
+    }
+
+    fn names(results: &[batch::FileResult]) -> Vec<String> {
+        results.iter()
+            .map(|result| result.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_recursive_directory() {
+        let dir = testing_dir("recursive");
+        let options = BatchOptions { recursive: true, jobs: 4 };
+        let results = batch::run(&[dir.display().to_string()], &options, |path| {
+            Ok(Outcome::Succeeded(fs::read_to_string(path)?))
+        });
+        assert_eq!(names(&results), vec!["a.png", "c.png", "d.PNG"]);
+        assert!(matches!(&results[1].outcome, Ok(Outcome::Succeeded(s)) if s == "sub/c.png"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_directory_without_recursive_is_skipped() {
+        let dir = testing_dir("skipped");
+        let options = BatchOptions { recursive: false, jobs: 1 };
+        let results = batch::run(&[dir.display().to_string()], &options, |_| -> Result<Outcome> {
+            panic!("directories are not processed")
+        });
+        assert_eq!(Summary::new(&results), Summary { succeeded: 0, failed: 0, skipped: 1 });
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_glob_and_failures() {
+        let dir = testing_dir("glob");
+        let inputs = [
+            dir.join("*.png").display().to_string(),
+            dir.join("sub/*.png").display().to_string(),
+            dir.join("missing*.png").display().to_string(),
+        ];
+        let results = batch::run(&inputs, &BatchOptions::default(), |path| {
+            if path.ends_with("c.png") {
+                Err(anyhow!("broken"))
+            } else {
+                Ok(Outcome::Succeeded(String::new()))
+            }
+        });
+        assert_eq!(names(&results), vec!["a.png", "c.png", "missing*.png"]);
+        assert_eq!(Summary::new(&results), Summary { succeeded: 1, failed: 2, skipped: 0 });
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_input() {
+        let dir = testing_dir("corrupt");
+        fs::write(dir.join("a.png"), b"\x89PNG\r\n\x1a\n\x00\x00\x10\x00\xff\xfe\xfd\xfcabcdefgh").unwrap();
+        let inputs = [dir.join("*.png").display().to_string(), dir.join("sub/*.png").display().to_string()];
+        let results = batch::run(&inputs, &BatchOptions::default(), |path| {
+            if path.ends_with("c.png") {
+                panic!("worker bug");
+            }
+            stdio::read_png(path)?;
+            Ok(Outcome::Succeeded(String::new()))
+        });
+        assert_eq!(names(&results), vec!["a.png", "c.png"]);
+        assert!(matches!(&results[0].outcome, Err(e) if e.to_string().contains("truncated")));
+        assert!(matches!(&results[1].outcome, Err(e) if e.to_string().contains("worker bug")));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_pattern() {
+        assert!(batch::is_pattern("*.png"));
+        assert!(batch::is_pattern("img[0-9].png"));
+        assert!(!batch::is_pattern("dir/img.png"));
+    }
+}
@@ -0,0 +1,60 @@
+mod tests {
+    use std::convert::TryFrom;
+    use pngme::builder::PngBuilder;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{Background, ColorType, Gamma, Ihdr, Palette, RenderingIntent, Text, Transparency};
+    use pngme::image::RawImage;
+    use pngme::png::Png;
+    use pngme::validate::validate;
+
+    fn types(png: &Png) -> Vec<String> {
+        png.chunks().iter().map(|chunk| chunk.chunk_type().to_string()).collect()
+    }
+
+    #[test]
+    fn test_build_orders_chunks() {
+        let png = PngBuilder::new(Ihdr::new(2, 2, 8, ColorType::Indexed).unwrap())
+            .text("Author", "x")
+            .background(Background::PaletteIndex(1))
+            .transparency(Transparency::Palette(vec![0]))
+            .palette(Palette::new(vec![[0, 0, 0], [255, 255, 255]]).unwrap())
+            .gamma(0.45455)
+            .dpi(72.0)
+            .rows(vec![[0u8, 1].as_ref(), [1, 0].as_ref()])
+            .build()
+            .unwrap();
+        assert_eq!(types(&png), vec!["IHDR", "gAMA", "PLTE", "bKGD", "tRNS", "pHYs", "tEXt", "IDAT", "IEND"]);
+        assert!(validate(&png).is_empty());
+        assert_eq!(RawImage::decode(&png).unwrap().data, vec![0, 1, 1, 0]);
+        let text = Text::try_from(png.chunks_of_type(&ChunkType::TEXT).next().unwrap()).unwrap();
+        assert_eq!(text, Text::new("Author", "x").unwrap());
+    }
+
+    #[test]
+    fn test_set_replaces_unique_chunks() {
+        let png = PngBuilder::new(Ihdr::new(1, 1, 16, ColorType::Rgb).unwrap())
+            .gamma(1.0)
+            .gamma(0.45455)
+            .srgb(RenderingIntent::Perceptual)
+            .text("Title", "a")
+            .text("Title", "b")
+            .row(&[0; 6])
+            .build()
+            .unwrap();
+        assert_eq!(types(&png), vec!["IHDR", "gAMA", "sRGB", "tEXt", "tEXt", "IDAT", "IEND"]);
+        assert_eq!(Gamma::try_from(&png.chunks()[1]).unwrap().gamma, 0.45455);
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let gray = || PngBuilder::new(Ihdr::new(2, 1, 8, ColorType::Grayscale).unwrap());
+        assert!(gray().row(&[0]).build().is_err());
+        assert!(gray().build().is_err());
+        assert!(gray().text("", "keyword is empty").row(&[0, 0]).build().is_err());
+        assert!(gray().gamma(0.0).row(&[0, 0]).build().is_err());
+        assert!(gray().palette(Palette::new(vec![[0, 0, 0]]).unwrap()).row(&[0, 0]).build().is_err());
+        let indexed = PngBuilder::new(Ihdr::new(1, 1, 8, ColorType::Indexed).unwrap()).row(&[0]);
+        let error = indexed.build().err().unwrap();
+        assert_eq!(error.to_string(), "built png is invalid: indexed image without PLTE chunk");
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(unused_imports)]
+
 mod tests {
     use std::{assert_eq, format};
     use std::convert::TryFrom;
@@ -59,6 +61,13 @@ mod tests {
         assert_eq!(chunk.crc(), 2882656334);
     }
 
+    #[test]
+    fn test_chunk_crc_valid() {
+        let chunk = testing_chunk();
+        assert_eq!(chunk.computed_crc(), chunk.crc());
+        assert!(chunk.is_crc_valid());
+    }
+
     #[test]
     fn test_valid_chunk_from_bytes() {
         let data_length: u32 = 42;
@@ -107,6 +116,12 @@ mod tests {
         assert!(chunk.is_err());
     }
 
+    #[test]
+    fn test_truncated_chunk_with_binary_type() {
+        let chunk = Chunk::try_from(&b"\x00\x00\x10\x00\xff\xfe\xfd\xfcabcdefgh"[..]);
+        assert!(chunk.unwrap_err().to_string().contains("truncated"));
+    }
+
     #[test]
     pub fn test_chunk_trait_impls() {
         let data_length: u32 = 42;
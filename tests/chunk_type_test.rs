@@ -1,8 +1,12 @@
+#![allow(unused_imports)]
+
 mod tests {
     use super::*;
     use std::convert::TryFrom;
     use std::str::FromStr;
     use pngme::chunk_type::ChunkType;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
 
     #[test]
     pub fn test_chunk_type_from_bytes() {
@@ -19,6 +23,18 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    pub fn test_chunk_type_new() {
+        const RUST: ChunkType = ChunkType::new(*b"RuSt");
+        assert_eq!(RUST, ChunkType::from_str("RuSt").unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_chunk_type_new_invalid() {
+        ChunkType::new(*b"Ru1t");
+    }
+
     #[test]
     pub fn test_chunk_type_is_critical() {
         let chunk = ChunkType::from_str("RuSt").unwrap();
@@ -88,6 +104,12 @@ mod tests {
         assert_eq!(&chunk.to_string(), "RuSt");
     }
 
+    #[test]
+    pub fn test_chunk_type_string_not_utf8() {
+        let chunk = ChunkType::try_from([0xff, 0xfe, 0xfd, 0xfc]).unwrap();
+        assert_eq!(chunk.to_string(), "\u{fffd}".repeat(4));
+    }
+
     #[test]
     pub fn test_chunk_type_trait_impls() {
         let chunk_type_1: ChunkType = TryFrom::try_from([82, 117, 83, 116]).unwrap();
@@ -95,4 +117,30 @@ mod tests {
         let _chunk_string = format!("{}", chunk_type_1);
         let _are_chunks_equal = chunk_type_1 == chunk_type_2;
     }
+
+    fn is_private_ancillary(chunk_type: &ChunkType) -> bool {
+        chunk_type.is_valid()
+            && !chunk_type.is_critical()
+            && !chunk_type.is_public()
+            && chunk_type.is_safe_to_copy()
+    }
+
+    #[test]
+    pub fn test_random_private_ancillary() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..1000 {
+            let chunk_type = ChunkType::random_private_ancillary(&mut rng);
+            assert!(is_private_ancillary(&chunk_type), "{}", chunk_type);
+            assert_ne!(chunk_type.to_string(), "ruSt");
+        }
+    }
+
+    #[test]
+    pub fn test_derive() {
+        let chunk_type = ChunkType::derive(b"secret", "label");
+        assert!(is_private_ancillary(&chunk_type));
+        assert_eq!(chunk_type, ChunkType::derive(b"secret", "label"));
+        assert_ne!(chunk_type, ChunkType::derive(b"other secret", "label"));
+        assert_ne!(chunk_type, ChunkType::derive(b"secret", "other label"));
+    }
 }
@@ -0,0 +1,418 @@
+mod tests {
+    use std::convert::TryFrom;
+    use std::time::{Duration, UNIX_EPOCH};
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{Background, Chromaticities, Chromaticity, Cicp, ColorType, ContentLightLevel, Gamma, Histogram, IccProfile, Ihdr, MasteringDisplay,
+                        Palette,
+                        PhysicalDimensions, RenderingIntent, SignificantBits, Srgb, SuggestedColor, SuggestedPalette,
+                        Text, Time, Transparency};
+    use pngme::chunks::exif::{self, ByteOrder, Entry, Exif, Group, Ifd, Value};
+    use pngme::chunks::phys::Unit;
+
+    fn testing_ihdr() -> Ihdr {
+        Ihdr::new(640, 480, 8, ColorType::Rgba).unwrap()
+    }
+
+    #[test]
+    fn test_ihdr_round_trip() {
+        let ihdr = testing_ihdr();
+        let bytes = ihdr.as_bytes();
+        assert_eq!(bytes, vec![0, 0, 2, 128, 0, 0, 1, 224, 8, 6, 0, 0, 0]);
+        assert_eq!(Ihdr::try_from(bytes.as_slice()).unwrap(), ihdr);
+    }
+
+    #[test]
+    fn test_ihdr_from_chunk() {
+        let chunk = testing_ihdr().to_chunk();
+        assert_eq!(chunk.chunk_type(), ChunkType::IHDR);
+        assert_eq!(Ihdr::try_from(&chunk).unwrap().width, 640);
+    }
+
+    #[test]
+    fn test_ihdr_invalid_bit_depth() {
+        assert!(Ihdr::new(1, 1, 4, ColorType::Rgb).is_err());
+        assert!(Ihdr::new(1, 1, 16, ColorType::Indexed).is_err());
+        assert!(Ihdr::new(1, 1, 2, ColorType::Grayscale).is_ok());
+    }
+
+    #[test]
+    fn test_ihdr_invalid_fields() {
+        assert!(Ihdr::new(0, 1, 8, ColorType::Rgb).is_err());
+        let mut bytes = testing_ihdr().as_bytes();
+        bytes[9] = 5;
+        assert!(Ihdr::try_from(bytes.as_slice()).is_err());
+        assert!(Ihdr::try_from(&bytes[..12]).is_err());
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let text = Text::new("Author", "Ren\u{e9}").unwrap();
+        let bytes = text.as_bytes().unwrap();
+        assert_eq!(bytes, b"Author\0Ren\xe9".to_vec());
+        assert_eq!(Text::try_from(bytes.as_slice()).unwrap(), text);
+        assert!(Text { keyword: "Author".to_string(), text: "\u{263a}".to_string() }.as_bytes().is_err());
+    }
+
+    #[test]
+    fn test_text_invalid() {
+        assert!(Text::new("", "text").is_err());
+        assert!(Text::new(" Author", "text").is_err());
+        assert!(Text::new("Author", "\u{263a}").is_err());
+        assert!(Text::try_from(&b"no separator"[..]).is_err());
+    }
+
+    #[test]
+    fn test_text_from_wrong_chunk() {
+        let chunk = Chunk::new(ChunkType::IEND, vec![]);
+        assert!(Text::try_from(&chunk).is_err());
+    }
+
+    #[test]
+    fn test_palette_round_trip() {
+        let palette = Palette::new(vec![[255, 0, 0], [0, 0, 255]]).unwrap();
+        let chunk = palette.to_chunk();
+        assert_eq!(chunk.chunk_type(), ChunkType::PLTE);
+        assert_eq!(chunk.data(), &[255, 0, 0, 0, 0, 255]);
+        assert_eq!(Palette::try_from(&chunk).unwrap(), palette);
+        assert!(Palette::try_from(&[1, 2, 3, 4][..]).is_err());
+        assert!(Palette::new(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_palette_validate_for() {
+        let palette = Palette::new(vec![[0, 0, 0]; 3]).unwrap();
+        assert!(palette.validate_for(&Ihdr::new(1, 1, 2, ColorType::Indexed).unwrap()).is_ok());
+        assert!(palette.validate_for(&Ihdr::new(1, 1, 1, ColorType::Indexed).unwrap()).is_err());
+        assert!(palette.validate_for(&Ihdr::new(1, 1, 8, ColorType::Grayscale).unwrap()).is_err());
+        assert!(palette.validate_for(&Ihdr::new(1, 1, 8, ColorType::Rgb).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn test_transparency_layouts() {
+        let gray = Transparency::from_bytes(&[0, 7], ColorType::Grayscale).unwrap();
+        assert_eq!(gray, Transparency::Gray(7));
+        let rgb = Transparency::from_bytes(&[0, 1, 0, 2, 0, 3], ColorType::Rgb).unwrap();
+        assert_eq!(rgb, Transparency::Rgb { red: 1, green: 2, blue: 3 });
+        assert_eq!(rgb.as_bytes(), vec![0, 1, 0, 2, 0, 3]);
+        let alpha = Transparency::from_bytes(&[0, 128], ColorType::Indexed).unwrap();
+        assert_eq!(alpha.alpha(1), 128);
+        assert_eq!(alpha.alpha(2), 255);
+        assert!(Transparency::from_bytes(&[0, 7], ColorType::Rgb).is_err());
+        assert!(Transparency::from_bytes(&[0], ColorType::Rgba).is_err());
+    }
+
+    #[test]
+    fn test_transparency_validate_for() {
+        let ihdr = Ihdr::new(1, 1, 2, ColorType::Grayscale).unwrap();
+        assert!(Transparency::Gray(3).validate_for(&ihdr, None).is_ok());
+        assert!(Transparency::Gray(4).validate_for(&ihdr, None).is_err());
+        let ihdr = Ihdr::new(1, 1, 8, ColorType::Indexed).unwrap();
+        let palette = Palette::new(vec![[0, 0, 0]; 2]).unwrap();
+        assert!(Transparency::Palette(vec![0; 2]).validate_for(&ihdr, Some(&palette)).is_ok());
+        assert!(Transparency::Palette(vec![0; 3]).validate_for(&ihdr, Some(&palette)).is_err());
+        assert!(Transparency::Gray(0).validate_for(&ihdr, None).is_err());
+    }
+
+    #[test]
+    fn test_gamma_fixed_point() {
+        let gamma = Gamma::new(1.0 / 2.2).unwrap();
+        assert_eq!(gamma.as_bytes().unwrap(), 45455u32.to_be_bytes().to_vec());
+        let parsed = Gamma::try_from(&gamma.to_chunk().unwrap()).unwrap();
+        assert!((parsed.gamma - 0.45455).abs() < 1e-9);
+        assert!(Gamma::try_from(&[0, 0, 0, 0][..]).is_err());
+        assert!(Gamma::new(-1.0).is_err());
+        assert!(Gamma { gamma: -1.0 }.to_chunk().is_err());
+    }
+
+    #[test]
+    fn test_chromaticities_round_trip() {
+        let bytes = Chromaticities::SRGB.as_bytes().unwrap();
+        assert_eq!(bytes.len(), Chromaticities::LENGTH);
+        assert_eq!(&bytes[..8], &[0, 0, 0x7a, 0x26, 0, 0, 0x80, 0x84]);
+        assert_eq!(Chromaticities::try_from(bytes.as_slice()).unwrap(), Chromaticities::SRGB);
+        assert!(Chromaticities::try_from(&bytes[..31]).is_err());
+    }
+
+    #[test]
+    fn test_srgb_round_trip() {
+        let srgb = Srgb::new(RenderingIntent::Saturation);
+        assert_eq!(srgb.as_bytes(), vec![2]);
+        assert_eq!(Srgb::try_from(&srgb.to_chunk()).unwrap(), srgb);
+        assert!(Srgb::try_from(&[4][..]).is_err());
+    }
+
+    #[test]
+    fn test_icc_profile_round_trip() {
+        let icc = IccProfile::new("Display P3", vec![7; 500]).unwrap();
+        let bytes = icc.as_bytes().unwrap();
+        assert!(bytes.starts_with(b"Display P3\0\0"));
+        assert!(bytes.len() < 100);
+        assert_eq!(IccProfile::try_from(&icc.to_chunk().unwrap()).unwrap(), icc);
+        assert!(IccProfile::new(" padded", Vec::new()).is_err());
+        assert!(IccProfile::try_from(&b"name\0\x01data"[..]).is_err());
+    }
+
+    #[test]
+    fn test_cicp_round_trip() {
+        let cicp = Cicp::try_from(&[9, 16, 0, 1][..]).unwrap();
+        assert_eq!(cicp.to_string(), "BT.2020 primaries, PQ transfer, full range");
+        assert_eq!(cicp.as_bytes(), vec![9, 16, 0, 1]);
+        assert!(Cicp::try_from(&[1, 1, 1, 1][..]).is_err());
+        assert!(Cicp::try_from(&[1, 1, 0, 2][..]).is_err());
+    }
+
+    #[test]
+    fn test_time_round_trip() {
+        let time = Time::new(2024, 2, 29, 23, 59, 60).unwrap();
+        let bytes = time.as_bytes();
+        assert_eq!(bytes, vec![7, 232, 2, 29, 23, 59, 60]);
+        assert_eq!(Time::try_from(&time.to_chunk()).unwrap(), time);
+        assert!(Time::new(2023, 2, 29, 0, 0, 0).is_err());
+        assert!(Time::new(2024, 4, 31, 0, 0, 0).is_err());
+        assert!(Time::new(2024, 1, 1, 24, 0, 0).is_err());
+        assert!(Time::try_from(&bytes[..6]).is_err());
+    }
+
+    #[test]
+    fn test_time_system_time() {
+        let time = Time::from_system_time(UNIX_EPOCH + Duration::from_secs(1_700_000_000)).unwrap();
+        assert_eq!(time.to_string(), "2023-11-14T22:13:20Z");
+        assert_eq!(time.to_system_time(), UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        let before = Time::from_system_time(UNIX_EPOCH - Duration::from_secs(86400)).unwrap();
+        assert_eq!(before.to_string(), "1969-12-31T00:00:00Z");
+        assert_eq!(before.to_system_time(), UNIX_EPOCH - Duration::from_secs(86400));
+    }
+
+    #[test]
+    fn test_time_rfc3339() {
+        let time: Time = "2024-05-01T12:30:00Z".parse().unwrap();
+        assert_eq!(time, Time::new(2024, 5, 1, 12, 30, 0).unwrap());
+        let time: Time = "2024-01-01 01:15:09.125+02:00".parse().unwrap();
+        assert_eq!(time.to_string(), "2023-12-31T23:15:09Z");
+        let leap: Time = "2016-12-31T23:59:60Z".parse().unwrap();
+        assert_eq!(leap.second, 60);
+        for invalid in ["2024-05-01", "2024-05-01T12:30:00", "2024-05-01T12:30:00+0200", "2024/05/01T12:30:00Z"] {
+            assert!(invalid.parse::<Time>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_physical_dimensions_round_trip() {
+        let phys = PhysicalDimensions::from_dpi(300.0, 72.0).unwrap();
+        assert_eq!(phys, PhysicalDimensions { x: 11811, y: 2835, unit: Unit::Meter });
+        let bytes = phys.as_bytes();
+        assert_eq!(bytes, vec![0, 0, 0x2e, 0x23, 0, 0, 0x0b, 0x13, 1]);
+        assert_eq!(PhysicalDimensions::try_from(&phys.to_chunk()).unwrap(), phys);
+        let (x, y) = phys.dpi().unwrap();
+        assert_eq!((x.round(), y.round()), (300.0, 72.0));
+        let aspect = PhysicalDimensions::try_from([0, 0, 0, 2, 0, 0, 0, 1, 0].as_ref()).unwrap();
+        assert_eq!(aspect.dpi(), None);
+        assert_eq!(aspect.to_string(), "2:1 aspect ratio");
+        assert!(PhysicalDimensions::try_from([0, 0, 0, 2, 0, 0, 0, 1, 2].as_ref()).is_err());
+    }
+
+    #[test]
+    fn test_significant_bits_per_color_type() {
+        let cases: [(ColorType, &[u8]); 5] = [
+            (ColorType::Grayscale, &[5]),
+            (ColorType::GrayscaleAlpha, &[5, 3]),
+            (ColorType::Rgb, &[5, 6, 5]),
+            (ColorType::Indexed, &[4, 4, 4]),
+            (ColorType::Rgba, &[5, 5, 5, 1]),
+        ];
+        for (color_type, bytes) in cases.iter() {
+            let sbit = SignificantBits::from_bytes(bytes, *color_type).unwrap();
+            assert_eq!(sbit.as_bytes(), bytes.to_vec());
+            assert!(sbit.validate_for(&Ihdr::new(1, 1, 8, *color_type).unwrap()).is_ok());
+        }
+        assert!(SignificantBits::from_bytes(&[5, 6], ColorType::Rgb).is_err());
+        let ihdr = Ihdr::new(1, 1, 4, ColorType::Grayscale).unwrap();
+        assert!(SignificantBits::from_chunk(&SignificantBits::Gray(5).to_chunk(), &ihdr).is_err());
+        assert!(SignificantBits::Gray(0).validate_for(&ihdr).is_err());
+    }
+
+    #[test]
+    fn test_background_per_color_type() {
+        let gray = Background::from_bytes(&[0x01, 0x02], ColorType::GrayscaleAlpha).unwrap();
+        assert_eq!(gray, Background::Gray(0x0102));
+        let rgb = Background::from_bytes(&[0, 1, 0, 2, 0, 3], ColorType::Rgb).unwrap();
+        assert_eq!(rgb, Background::Rgb { red: 1, green: 2, blue: 3 });
+        assert_eq!(rgb.as_bytes(), vec![0, 1, 0, 2, 0, 3]);
+        assert_eq!(Background::from_bytes(&[7], ColorType::Indexed).unwrap(), Background::PaletteIndex(7));
+        assert!(Background::from_bytes(&[7], ColorType::Rgb).is_err());
+
+        let ihdr = Ihdr::new(1, 1, 8, ColorType::Grayscale).unwrap();
+        assert!(Background::from_chunk(&gray.to_chunk(), &ihdr).is_err());
+        let indexed = Ihdr::new(1, 1, 8, ColorType::Indexed).unwrap();
+        let palette = Palette::new(vec![[0, 0, 0]]).unwrap();
+        assert!(Background::PaletteIndex(0).validate_for(&indexed, Some(&palette)).is_ok());
+        assert!(Background::PaletteIndex(1).validate_for(&indexed, Some(&palette)).is_err());
+    }
+
+    #[test]
+    fn test_histogram_round_trip() {
+        let histogram = Histogram { frequencies: vec![1, 0x0203] };
+        assert_eq!(histogram.as_bytes(), vec![0, 1, 2, 3]);
+        assert_eq!(Histogram::try_from(&histogram.to_chunk()).unwrap(), histogram);
+        assert!(Histogram::try_from([0, 1, 2].as_ref()).is_err());
+        assert!(histogram.validate_for(&Palette::new(vec![[0, 0, 0], [1, 1, 1]]).unwrap()).is_ok());
+        assert!(histogram.validate_for(&Palette::new(vec![[0, 0, 0]]).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_suggested_palette_round_trip() {
+        let color = SuggestedColor { red: 1, green: 2, blue: 3, alpha: 255, frequency: 10 };
+        let palette = SuggestedPalette { name: "web".to_string(), sample_depth: 8, entries: vec![color] };
+        let bytes = palette.as_bytes().unwrap();
+        assert_eq!(bytes, b"web\0\x08\x01\x02\x03\xff\x00\x0a".to_vec());
+        assert_eq!(SuggestedPalette::try_from(&palette.to_chunk().unwrap()).unwrap(), palette);
+
+        let deep = SuggestedPalette { sample_depth: 16, entries: vec![SuggestedColor { red: 0x1234, ..color }], ..palette };
+        assert_eq!(deep.as_bytes().unwrap().len(), 5 + 10);
+        assert_eq!(SuggestedPalette::try_from(deep.as_bytes().unwrap().as_slice()).unwrap(), deep);
+        assert!(SuggestedPalette::try_from(&bytes[..bytes.len() - 1]).is_err());
+        assert!(SuggestedPalette::try_from(b"web\0\x04".as_ref()).is_err());
+        assert!(deep.validate().is_ok());
+        assert!(SuggestedPalette { sample_depth: 8, ..deep }.validate().is_err());
+    }
+
+    fn ascii(tag: u16, text: &str) -> Entry {
+        let mut data = text.as_bytes().to_vec();
+        data.push(0);
+        Entry { tag, field_type: 2, count: data.len() as u32, data }
+    }
+
+    fn rationals(tag: u16, values: &[(u32, u32)]) -> Entry {
+        let data = values.iter().flat_map(|(n, d)| [n.to_be_bytes(), d.to_be_bytes()].concat()).collect();
+        Entry { tag, field_type: 5, count: values.len() as u32, data }
+    }
+
+    fn testing_exif() -> Exif {
+        let gps = Ifd {
+            entries: vec![
+                ascii(1, "N"),
+                rationals(2, &[(37, 1), (46, 1), (2994, 100)]),
+                ascii(3, "W"),
+                rationals(4, &[(122, 1), (25, 1), (960, 100)]),
+            ],
+            sub_ifds: Vec::new(),
+        };
+        let exif_ifd = Ifd { entries: vec![rationals(0x829d, &[(28, 10)]), ascii(0xa431, "0123456789")], sub_ifds: Vec::new() };
+        Exif {
+            byte_order: ByteOrder::BigEndian,
+            ifd0: Ifd {
+                entries: vec![ascii(0x010f, "Canon"), Entry { tag: 0x0112, field_type: 3, count: 1, data: vec![0, 6] }],
+                sub_ifds: vec![(exif::EXIF_IFD, exif_ifd), (exif::GPS_IFD, gps)],
+            },
+            ifd1: None,
+            thumbnail: None,
+        }
+    }
+
+    #[test]
+    fn test_exif_parse() {
+        let bytes = b"II*\0\x08\0\0\0\x02\0\x0f\x01\x02\0\x04\0\0\0Sny\0\x12\x01\x03\0\x01\0\0\0\x06\0\0\0\0\0\0\0";
+        let exif = Exif::try_from(bytes.as_ref()).unwrap();
+        assert_eq!(exif.byte_order, ByteOrder::LittleEndian);
+        assert_eq!(exif.value(Group::Image, 0x010f), Some(Value::Ascii("Sny".to_string())));
+        assert_eq!(exif.value(Group::Image, 0x0112), Some(Value::Short(vec![6])));
+        assert_eq!(exif.as_bytes(), bytes.to_vec());
+        let prefixed = [b"Exif\0\0".as_ref(), bytes.as_ref()].concat();
+        assert_eq!(Exif::try_from(prefixed.as_slice()).unwrap(), exif);
+        assert!(Exif::try_from(&bytes[..20]).is_err());
+        assert!(Exif::try_from(b"JFIF\0\0\0\0".as_ref()).is_err());
+        assert!(Exif::try_from(b"II*\0".as_ref()).is_err());
+        assert!(Exif::try_from(b"MM\0*\0\0".as_ref()).is_err());
+    }
+
+    #[test]
+    fn test_exif_repeated_ifds() {
+        let sub_ifd_entry = |offset: u8| [&b"\x69\x87\x04\0\x01\0\0\0"[..], &[offset, 0, 0, 0]].concat();
+        let tiff = |entries: &[Vec<u8>]| {
+            let mut bytes = b"II*\0\x08\0\0\0".to_vec();
+            bytes.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+            entries.iter().for_each(|entry| bytes.extend_from_slice(entry));
+            bytes.extend_from_slice(&[0; 4 + 6]);
+            bytes
+        };
+        // the sub-IFD is the empty one right after the entries of IFD0
+        let sub_ifd = |entries: u8| 8 + 2 + 12 * entries + 4;
+        assert!(Exif::try_from(tiff(&[sub_ifd_entry(sub_ifd(1))]).as_slice()).is_ok());
+        assert!(Exif::try_from(tiff(&[sub_ifd_entry(sub_ifd(2)), sub_ifd_entry(sub_ifd(2))]).as_slice()).is_err());
+        assert!(Exif::try_from(tiff(&[sub_ifd_entry(8)]).as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_exif_round_trip() {
+        let exif = testing_exif();
+        let chunk = exif.to_chunk();
+        assert_eq!(chunk.chunk_type(), ChunkType::EXIF);
+        let parsed = Exif::try_from(&chunk).unwrap();
+        assert_eq!(parsed, exif);
+        assert_eq!(parsed.as_bytes(), chunk.data());
+        assert_eq!(exif.to_string(), "8 tags, big-endian, with GPS");
+        assert_eq!(exif.value(Group::Exif, 0x829d).unwrap().to_string(), "28/10");
+    }
+
+    #[test]
+    fn test_exif_gps_and_fields() {
+        let exif = testing_exif();
+        let (latitude, longitude) = exif.gps_position().unwrap();
+        assert!((latitude - 37.774983).abs() < 1e-6);
+        assert!((longitude + 122.419333).abs() < 1e-6);
+        let fields = exif.fields();
+        assert_eq!(fields.len(), 8);
+        assert_eq!((fields[0].name.as_str(), fields[0].value.as_str()), ("Make", "Canon"));
+        let latitude = fields.iter().find(|field| field.name == "GPSLatitude").unwrap();
+        assert_eq!((latitude.group, latitude.value.as_str()), (Group::Gps, "37.774983"));
+        assert_eq!(exif::tag_name(Group::Gps, 0x010f), None);
+    }
+
+    #[test]
+    fn test_exif_thumbnail() {
+        let long = |tag: u16| Entry { tag, field_type: 4, count: 1, data: vec![0; 4] };
+        let exif = Exif {
+            ifd1: Some(Ifd { entries: vec![long(0x0201), long(0x0202)], sub_ifds: Vec::new() }),
+            thumbnail: Some(vec![0xff, 0xd8, 0xff, 0xd9, 0]),
+            ..testing_exif()
+        };
+        let bytes = exif.as_bytes();
+        let parsed = Exif::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(parsed.thumbnail, exif.thumbnail);
+        assert_eq!(parsed.ifd0, exif.ifd0);
+        assert_eq!(parsed.as_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_mastering_display_round_trip() {
+        let display = MasteringDisplay {
+            red: Chromaticity { x: 0.708, y: 0.292 },
+            green: Chromaticity { x: 0.17, y: 0.797 },
+            blue: Chromaticity { x: 0.131, y: 0.046 },
+            white: Chromaticity { x: 0.3127, y: 0.329 },
+            max_luminance: 1000.0,
+            min_luminance: 0.005,
+        };
+        let bytes = display.as_bytes().unwrap();
+        assert_eq!(bytes.len(), MasteringDisplay::LENGTH);
+        assert_eq!(&bytes[..4], &[0x8a, 0x48, 0x39, 0x08]);
+        assert_eq!(&bytes[16..], &[0, 0x98, 0x96, 0x80, 0, 0, 0, 50]);
+        let parsed = MasteringDisplay::try_from(&display.to_chunk().unwrap()).unwrap();
+        assert_eq!(parsed, display);
+        assert_eq!(parsed.as_bytes().unwrap(), bytes);
+        assert!(MasteringDisplay { min_luminance: 2000.0, ..display }.validate().is_err());
+        assert!(MasteringDisplay { white: Chromaticity { x: 1.5, y: 0.3 }, ..display }.validate().is_err());
+        assert!(MasteringDisplay::try_from(&bytes[..20]).is_err());
+    }
+
+    #[test]
+    fn test_content_light_level_round_trip() {
+        let level = ContentLightLevel { max_content: 1000.0, max_frame_average: 400.5 };
+        let bytes = level.as_bytes().unwrap();
+        assert_eq!(bytes, vec![0, 0x98, 0x96, 0x80, 0, 0x3d, 0x1c, 0x88]);
+        assert_eq!(ContentLightLevel::try_from(&level.to_chunk().unwrap()).unwrap(), level);
+        assert_eq!(level.to_string(), "MaxCLL 1000 cd/m², MaxFALL 400.5 cd/m²");
+        assert!(ContentLightLevel { max_content: -1.0, ..level }.validate().is_err());
+    }
+}
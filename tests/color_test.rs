@@ -0,0 +1,84 @@
+mod tests {
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{Chromaticities, Cicp, ColorType, ContentLightLevel, Gamma, IccProfile, Ihdr, RenderingIntent, Srgb};
+    use pngme::color::{self, ColorSpace};
+    use pngme::png::Png;
+
+    fn testing_png(color_chunks: Vec<Chunk>) -> Png {
+        let mut chunks = vec![Ihdr::new(1, 1, 8, ColorType::Rgb).unwrap().to_chunk()];
+        chunks.extend(color_chunks);
+        chunks.push(Chunk::new(ChunkType::IDAT, Vec::new()));
+        chunks.push(Chunk::new(ChunkType::IEND, Vec::new()));
+        Png::from_chunks(chunks)
+    }
+
+    #[test]
+    fn test_unspecified() {
+        let info = testing_png(Vec::new()).color_info();
+        assert_eq!(info.color_space, ColorSpace::Unspecified);
+        assert!(info.notes.is_empty());
+    }
+
+    #[test]
+    fn test_calibrated() {
+        let png = testing_png(vec![Gamma::new(0.45455).unwrap().to_chunk().unwrap(), Chromaticities::SRGB.to_chunk().unwrap()]);
+        let info = png.color_info();
+        assert_eq!(info.color_space, ColorSpace::Calibrated);
+        assert_eq!(info.gamma, Some(0.45455));
+        assert_eq!(info.chromaticities, Some(Chromaticities::SRGB));
+    }
+
+    #[test]
+    fn test_precedence() {
+        let srgb = Srgb::new(RenderingIntent::Perceptual).to_chunk();
+        let gamma = Gamma::new(0.45455).unwrap().to_chunk().unwrap();
+        let info = testing_png(vec![srgb.clone(), gamma.clone()]).color_info();
+        assert_eq!(info.color_space, ColorSpace::Srgb);
+        assert_eq!(info.notes, vec!["gAMA overridden by sRGB"]);
+
+        let icc = IccProfile::new("profile", vec![1, 2, 3]).unwrap().to_chunk().unwrap();
+        let info = testing_png(vec![icc.clone(), gamma.clone()]).color_info();
+        assert_eq!(info.color_space, ColorSpace::IccProfile);
+        assert_eq!(info.icc_profile.unwrap().size, 3);
+
+        let cicp = Cicp { colour_primaries: 1, transfer_function: 13, matrix_coefficients: 0, video_full_range: true };
+        let info = testing_png(vec![cicp.to_chunk(), icc, srgb]).color_info();
+        assert_eq!(info.color_space, ColorSpace::Cicp);
+        assert_eq!(info.notes, vec!["iCCP and sRGB should not both be present", "iCCP, sRGB overridden by cICP"]);
+    }
+
+    #[test]
+    fn test_invalid_chunk_is_noted() {
+        let info = testing_png(vec![Chunk::new(ChunkType::SRGB, vec![9])]).color_info();
+        assert_eq!(info.color_space, ColorSpace::Unspecified);
+        assert_eq!(info.notes, vec!["invalid sRGB: invalid rendering intent 9"]);
+    }
+
+    #[test]
+    fn test_set_icc_profile() {
+        let mut png = testing_png(vec![Srgb::new(RenderingIntent::Perceptual).to_chunk()]);
+        assert!(color::icc_profile(&png).unwrap().is_none());
+        let icc = IccProfile::new("first", vec![1; 64]).unwrap();
+        assert!(color::set_icc_profile(&mut png, &icc).unwrap());
+        assert_eq!(png.positions_of(&ChunkType::ICCP), &[1]);
+        assert!(png.positions_of(&ChunkType::SRGB).is_empty());
+
+        let replacement = IccProfile::new("second", vec![2; 16]).unwrap();
+        assert!(!color::set_icc_profile(&mut png, &replacement).unwrap());
+        assert_eq!(png.chunks_of_type(&ChunkType::ICCP).count(), 1);
+        assert_eq!(color::icc_profile(&png).unwrap(), Some(replacement));
+    }
+
+    #[test]
+    fn test_hdr_metadata() {
+        let cicp = Cicp { colour_primaries: 9, transfer_function: 16, matrix_coefficients: 0, video_full_range: true };
+        let level = ContentLightLevel { max_content: 1000.0, max_frame_average: 400.0 };
+        let info = testing_png(vec![cicp.to_chunk(), level.to_chunk().unwrap()]).color_info();
+        assert_eq!(info.content_light_level, Some(level));
+        assert_eq!(info.mastering_display, None);
+        assert!(info.to_string().contains("cLLI: MaxCLL 1000 cd/m², MaxFALL 400 cd/m²"));
+        let info = testing_png(vec![Chunk::new(ChunkType::MDCV, vec![0; 3])]).color_info();
+        assert_eq!(info.notes, vec!["invalid mDCv: mDCv must be 24 bytes, got 3"]);
+    }
+}
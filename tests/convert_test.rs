@@ -0,0 +1,74 @@
+mod tests {
+    use std::convert::TryFrom;
+    use std::path::Path;
+    use pngme::builder::PngBuilder;
+    use pngme::chunks::{ColorType, Ihdr, Palette};
+    use pngme::convert::{parse_size, ImageFormat, Source};
+    use pngme::netpbm::Netpbm;
+    use pngme::png::Png;
+
+    fn indexed_png() -> Vec<u8> {
+        PngBuilder::new(Ihdr::new(2, 1, 1, ColorType::Indexed).unwrap())
+            .palette(Palette::new(vec![[255, 0, 0], [0, 0, 255]]).unwrap())
+            .row(&[0b0100_0000])
+            .build()
+            .unwrap()
+            .as_bytes()
+    }
+
+    #[test]
+    fn test_image_format() {
+        assert_eq!(ImageFormat::from_path(Path::new("out.PAM")), Some(ImageFormat::Netpbm(Netpbm::Pam)));
+        assert_eq!(ImageFormat::from_path(Path::new("out.rgba")), Some(ImageFormat::Raw));
+        assert_eq!(ImageFormat::from_path(Path::new("out")), None);
+        assert_eq!("ppm".parse::<ImageFormat>().unwrap(), ImageFormat::Netpbm(Netpbm::Ppm));
+        assert!("jpg".parse::<ImageFormat>().is_err());
+        assert_eq!(ImageFormat::detect(&indexed_png()), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::detect(b"P5 1 1 255\n\x00"), Some(ImageFormat::Netpbm(Netpbm::Pgm)));
+        assert_eq!(ImageFormat::detect(b"\x00\x00\x00\x00"), None);
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("640x480").unwrap(), (640, 480));
+        assert!(parse_size("640").is_err());
+        assert!(parse_size("0x480").is_err());
+    }
+
+    #[test]
+    fn test_png_to_ppm_and_back() {
+        let source = Source::read(&indexed_png(), None).unwrap();
+        assert_eq!((source.color_type, source.bit_depth), (ColorType::Indexed, 1));
+        let ppm = source.write(ImageFormat::Netpbm(Netpbm::Ppm), None).unwrap();
+        assert_eq!(ppm, b"P6\n2 1\n255\n\xff\x00\x00\x00\x00\xff".to_vec());
+
+        let png = Png::try_from(Source::read(&ppm, None).unwrap().write(ImageFormat::Png, None).unwrap().as_slice())
+            .unwrap();
+        assert_eq!(png.ihdr().unwrap().color_type, ColorType::Rgb);
+        assert_eq!(Source::read(&png.as_bytes(), None).unwrap().image, source.image);
+    }
+
+    #[test]
+    fn test_raw() {
+        let source = Source::read(&indexed_png(), None).unwrap();
+        let raw = source.write(ImageFormat::Raw, None).unwrap();
+        assert_eq!(raw, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+        let raw16 = source.write(ImageFormat::Raw, Some(16)).unwrap();
+        assert_eq!(raw16.len(), 16);
+
+        assert_eq!(Source::read(&raw, Some((2, 1))).unwrap().bit_depth, 8);
+        let read16 = Source::read(&raw16, Some((2, 1))).unwrap();
+        assert_eq!((read16.bit_depth, read16.image), (16, source.image));
+        assert!(Source::read(&raw, Some((3, 1))).is_err());
+        assert!(Source::read(&raw, None).is_err());
+    }
+
+    #[test]
+    fn test_write_depth() {
+        let source = Source::read(&indexed_png(), None).unwrap();
+        let png = Png::try_from(source.write(ImageFormat::Png, Some(8)).unwrap().as_slice()).unwrap();
+        assert_eq!(png.ihdr().unwrap().bit_depth, 8);
+        assert!(source.write(ImageFormat::Png, Some(16)).is_err());
+        assert!(source.write(ImageFormat::Raw, Some(4)).is_err());
+    }
+}
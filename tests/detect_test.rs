@@ -0,0 +1,103 @@
+mod tests {
+    use std::str::FromStr;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::detect::{self, DetectOptions, Finding};
+    use pngme::png::Png;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn testing_png(extra: Vec<Chunk>) -> Png {
+        let mut png = Png::from_chunks(vec![
+            Chunk::new(ChunkType::IHDR, vec![0, 0, 0, 1, 0, 0, 0, 1, 8, 2, 0, 0, 0]),
+            Chunk::new(ChunkType::IDAT, Vec::new()),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ]);
+        extra.into_iter().for_each(|chunk| png.append_chunk(chunk));
+        png
+    }
+
+    fn random_bytes(length: usize) -> Vec<u8> {
+        let mut rng = StdRng::seed_from_u64(3);
+        (0..length).map(|_| rng.gen()).collect()
+    }
+
+    /// A smooth signal with a little noise, like a row of a photograph.
+    fn smooth_samples(rng: &mut StdRng) -> Vec<u8> {
+        (0..40_000)
+            .map(|i| (128.0 + 90.0 * (i as f64 / 300.0).sin() + rng.gen_range(-2.0..2.0)) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_file() {
+        let report = detect::detect(&testing_png(Vec::new()).as_bytes(), &DetectOptions::default());
+        assert_eq!(report.risk, 0);
+        assert!(report.findings.is_empty());
+    }
+
+    #[test]
+    fn test_private_chunks() {
+        let png = testing_png(vec![
+            Chunk::new(ChunkType::from_str("zzAb").unwrap(), b"hidden".to_vec()),
+            Chunk::new(ChunkType::from_str("npTc").unwrap(), b"nine patch".to_vec()),
+            Chunk::new(ChunkType::from_str("ruSt").unwrap(), b"pngme".to_vec()),
+        ]);
+        let report = detect::detect(&png.as_bytes(), &DetectOptions::default());
+        assert_eq!(report.findings.len(), 2);
+        assert!(matches!(&report.findings[0], Finding::PrivateChunk { chunk_type, .. } if chunk_type == "zzAb"));
+        assert!(matches!(&report.findings[1], Finding::Signature { name, .. } if name.contains("pngme")));
+        assert_eq!(report.risk, 75);
+    }
+
+    #[test]
+    fn test_text_and_entropy() {
+        let mut text = b"Comment\0".to_vec();
+        text.resize(detect::TEXT_LIMIT + 100, b'a');
+        let png = testing_png(vec![
+            Chunk::new(ChunkType::TEXT, text),
+            Chunk::new(ChunkType::from_str("baKe").unwrap(), random_bytes(1024)),
+        ]);
+        let report = detect::detect(&png.as_bytes(), &DetectOptions::default());
+        assert!(report.findings.iter().any(|finding| matches!(finding, Finding::OversizedText { .. })));
+        assert!(report.findings.iter().any(|finding| matches!(finding, Finding::HighEntropy { .. })));
+    }
+
+    #[test]
+    fn test_trailing_archive() {
+        let mut bytes = testing_png(Vec::new()).as_bytes();
+        let offset = bytes.len();
+        bytes.extend_from_slice(b"PK\x03\x04 and the rest of a zip file");
+        let report = detect::detect(&bytes, &DetectOptions::default());
+        assert!(matches!(report.findings[0], Finding::TrailingData { offset: o, .. } if o == offset));
+        assert!(matches!(&report.findings[1], Finding::Signature { name, .. } if name == "zip archive"));
+        assert_eq!(report.risk, 90);
+    }
+
+    #[test]
+    fn test_entropy() {
+        assert_eq!(detect::entropy(&[]), 0.0);
+        assert_eq!(detect::entropy(&[7; 100]), 0.0);
+        assert!((detect::entropy(&(0..=255).collect::<Vec<u8>>()) - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_chi_square() {
+        let mut rng = StdRng::seed_from_u64(5);
+        // only even values: the pairs are as uneven as they can be
+        let clean: Vec<u8> = (0..20_000).map(|_| rng.gen::<u8>() & 0xfe).collect();
+        assert!(detect::chi_square_p(&clean) < 0.01);
+        let embedded: Vec<u8> = clean.iter().map(|&s| s | rng.gen_range(0..2)).collect();
+        assert!(detect::chi_square_p(&embedded) > 0.95);
+    }
+
+    #[test]
+    fn test_rs_analysis() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let clean = smooth_samples(&mut rng);
+        assert!(detect::rs_rate(&clean).unwrap() < 0.15);
+        let embedded: Vec<u8> = clean.iter().map(|&s| (s & !1) | rng.gen_range(0..2)).collect();
+        assert!(detect::rs_rate(&embedded).unwrap() > 0.6);
+        assert!(detect::rs_rate(&clean[..100]).is_none());
+    }
+}
@@ -0,0 +1,106 @@
+mod tests {
+    use std::str::FromStr;
+    use miniz_oxide::deflate::compress_to_vec_zlib;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{ColorType, Ihdr, Text};
+    use pngme::diff::{Change, PixelComparison, PngDiff};
+    use pngme::png::Png;
+
+    const SCANLINES: [u8; 4] = [0, 1, 0, 2];
+
+    fn testing_png(chunks: Vec<Chunk>, level: u8) -> Png {
+        let ihdr = Ihdr::new(1, 2, 8, ColorType::Grayscale).unwrap();
+        let mut all = vec![ihdr.to_chunk()];
+        all.extend(chunks);
+        all.push(Chunk::new(ChunkType::IDAT, compress_to_vec_zlib(&SCANLINES, level)));
+        all.push(Chunk::new(ChunkType::IEND, Vec::new()));
+        Png::from_chunks(all)
+    }
+
+    fn chunk(chunk_type: &str, data: &str) -> Chunk {
+        Chunk::new(ChunkType::from_str(chunk_type).unwrap(), data.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_identical() {
+        let png = testing_png(vec![chunk("ruSt", "hi")], 6);
+        let diff = PngDiff::new(&png, &png);
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged, 4);
+    }
+
+    #[test]
+    fn test_added_and_removed() {
+        let old = testing_png(vec![chunk("ruSt", "hi")], 6);
+        let new = testing_png(vec![chunk("abCd", "new")], 6);
+        let diff = PngDiff::new(&old, &new);
+        assert_eq!(diff.changes, vec![
+            Change::Removed { index: 1, chunk_type: "ruSt".to_string(), length: 2 },
+            Change::Added { index: 1, chunk_type: "abCd".to_string(), length: 3 },
+        ]);
+    }
+
+    #[test]
+    fn test_many_chunks() {
+        // a full table for these would take gigabytes
+        let chunks: Vec<Chunk> = (0..50_000).map(|i| chunk("ruSt", &i.to_string())).collect();
+        let old = testing_png(chunks.clone(), 6);
+        let mut edited = chunks;
+        edited[25_000] = chunk("ruSt", "edited");
+        edited.remove(25_002);
+        let diff = PngDiff::new(&old, &testing_png(edited, 6));
+        assert_eq!(diff.unchanged, 50_000 + 3 - 2);
+        assert_eq!(diff.changes.len(), 2);
+    }
+
+    #[test]
+    fn test_moved() {
+        let old = testing_png(vec![chunk("ruSt", "a"), chunk("abCd", "b")], 6);
+        let new = testing_png(vec![chunk("abCd", "b"), chunk("ruSt", "a")], 6);
+        let diff = PngDiff::new(&old, &new);
+        assert_eq!(diff.changes.len(), 1);
+        assert!(matches!(diff.changes[0], Change::Moved { .. }));
+    }
+
+    #[test]
+    fn test_modified_text() {
+        let old = testing_png(vec![Text::new("Author", "me").unwrap().to_chunk().unwrap(),
+                                   Text::new("Title", "x").unwrap().to_chunk().unwrap()], 6);
+        let new = testing_png(vec![Text::new("Author", "you").unwrap().to_chunk().unwrap(),
+                                   Text::new("Title", "x").unwrap().to_chunk().unwrap()], 6);
+        let diff = PngDiff::new(&old, &new);
+        assert_eq!(diff.changes, vec![Change::Modified {
+            old_index: 1,
+            new_index: 1,
+            chunk_type: "tEXt".to_string(),
+            details: vec!["Author \"me\" -> \"you\"".to_string()],
+        }]);
+    }
+
+    #[test]
+    fn test_modified_ihdr() {
+        let old = testing_png(vec![], 6);
+        let mut chunks = old.chunks().iter()
+            .map(|chunk| Chunk::new(chunk.chunk_type(), chunk.data().to_vec()))
+            .collect::<Vec<_>>();
+        chunks[0] = Ihdr::new(2, 1, 8, ColorType::Grayscale).unwrap().to_chunk();
+        let new = Png::from_chunks(chunks);
+        let diff = PngDiff::new(&old, &new).with_pixels(&old, &new);
+        match &diff.changes[0] {
+            Change::Modified { details, .. } =>
+                assert_eq!(details, &vec!["width 1 -> 2".to_string(), "height 2 -> 1".to_string()]),
+            change => panic!("unexpected change {:?}", change),
+        }
+        assert!(matches!(diff.pixels, Some(PixelComparison::Incomparable { .. })));
+    }
+
+    #[test]
+    fn test_recompressed_pixels_identical() {
+        let old = testing_png(vec![], 1);
+        let new = testing_png(vec![], 10);
+        let diff = PngDiff::new(&old, &new).with_pixels(&old, &new);
+        assert_eq!(diff.changes.len(), 1);
+        assert_eq!(diff.pixels, Some(PixelComparison::Identical));
+    }
+}
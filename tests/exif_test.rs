@@ -0,0 +1,71 @@
+mod tests {
+    use std::convert::TryFrom;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{ColorType, Exif, Ihdr};
+    use pngme::chunks::exif::{ByteOrder, Entry, Group, Ifd, EXIF_IFD, GPS_IFD};
+    use pngme::exif::{self, Sensitive};
+    use pngme::png::Png;
+
+    fn ascii(tag: u16, text: &str) -> Entry {
+        let mut data = text.as_bytes().to_vec();
+        data.push(0);
+        Entry { tag, field_type: 2, count: data.len() as u32, data }
+    }
+
+    fn testing_png() -> Png {
+        let exif = Exif {
+            byte_order: ByteOrder::LittleEndian,
+            ifd0: Ifd {
+                entries: vec![ascii(0x010f, "Canon"), ascii(0xc62f, "CAM-1")],
+                sub_ifds: vec![
+                    (EXIF_IFD, Ifd { entries: vec![ascii(0xa431, "0123456789"), ascii(0xa434, "50mm")], sub_ifds: Vec::new() }),
+                    (GPS_IFD, Ifd { entries: vec![ascii(1, "N"), ascii(3, "E")], sub_ifds: Vec::new() }),
+                ],
+            },
+            ifd1: None,
+            thumbnail: None,
+        };
+        Png::from_chunks(vec![
+            Ihdr::new(1, 1, 8, ColorType::Rgb).unwrap().to_chunk(),
+            exif.to_chunk(),
+            Chunk::new(ChunkType::IDAT, Vec::new()),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ])
+    }
+
+    #[test]
+    fn test_sensitive_from_str() {
+        assert_eq!("gps".parse::<Sensitive>().unwrap(), Sensitive::Gps);
+        assert_eq!("serials".parse::<Sensitive>().unwrap(), Sensitive::Serials);
+        assert!("faces".parse::<Sensitive>().is_err());
+    }
+
+    #[test]
+    fn test_fields() {
+        let names: Vec<String> = exif::fields(&testing_png()).unwrap().into_iter().map(|field| field.name).collect();
+        assert_eq!(names, vec!["Make", "CameraSerialNumber", "BodySerialNumber", "LensModel", "GPSLatitudeRef", "GPSLongitudeRef"]);
+        let png = Png::from_chunks(vec![Ihdr::new(1, 1, 8, ColorType::Rgb).unwrap().to_chunk()]);
+        assert!(exif::fields(&png).is_err());
+    }
+
+    #[test]
+    fn test_strip_gps() {
+        let mut png = testing_png();
+        assert_eq!(exif::strip(&mut png, &[Sensitive::Gps]).unwrap(), 2);
+        let exif = exif::find(&png).unwrap().unwrap();
+        assert!(exif.ifd(Group::Gps).is_none());
+        assert_eq!(exif.ifd(Group::Exif).unwrap().entries.len(), 2);
+        assert_eq!(png.chunks()[1].chunk_type(), ChunkType::EXIF);
+        assert_eq!(exif::strip(&mut png, &[Sensitive::Gps]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_strip_serials() {
+        let mut png = testing_png();
+        assert_eq!(exif::strip(&mut png, &Sensitive::ALL).unwrap(), 4);
+        let exif = Exif::try_from(&png.chunks()[1]).unwrap();
+        let names: Vec<String> = exif.fields().into_iter().map(|field| field.name).collect();
+        assert_eq!(names, vec!["Make", "LensModel"]);
+    }
+}
@@ -0,0 +1,99 @@
+mod tests {
+    use miniz_oxide::deflate::compress_to_vec_zlib;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{ColorType, Ihdr};
+    use pngme::image::RawImage;
+    use pngme::png::Png;
+
+    fn png_from_scanlines(ihdr: &Ihdr, scanlines: &[u8]) -> Png {
+        Png::from_chunks(vec![
+            ihdr.to_chunk(),
+            Chunk::new(ChunkType::IDAT, compress_to_vec_zlib(scanlines, 6)),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ])
+    }
+
+    #[test]
+    fn test_decode_unfiltered() {
+        let ihdr = Ihdr::new(2, 2, 8, ColorType::Rgb).unwrap();
+        let scanlines = [0, 1, 2, 3, 4, 5, 6, 0, 7, 8, 9, 10, 11, 12];
+        let image = RawImage::decode(&png_from_scanlines(&ihdr, &scanlines)).unwrap();
+        assert_eq!(image.data, vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+        assert_eq!(image.rows().count(), 2);
+    }
+
+    #[test]
+    fn test_decode_filters() {
+        let ihdr = Ihdr::new(2, 5, 8, ColorType::Grayscale).unwrap();
+        #[rustfmt::skip]
+        let scanlines = [
+            1, 10, 5,     // sub: 10, 15
+            2, 1, 1,      // up: 11, 16
+            3, 4, 2,      // average: 4 + 11/2 = 9, 2 + (9 + 16)/2 = 14
+            4, 1, 1,      // paeth: 1 + 9 = 10, 1 + paeth(10, 14, 9) = 15
+            0, 255, 0,    // none
+        ];
+        let image = RawImage::decode(&png_from_scanlines(&ihdr, &scanlines)).unwrap();
+        assert_eq!(image.data, vec![10, 15, 11, 16, 9, 14, 10, 15, 255, 0]);
+    }
+
+    #[test]
+    fn test_decode_sub_byte_interlaced() {
+        let mut ihdr = Ihdr::new(3, 2, 1, ColorType::Grayscale).unwrap();
+        ihdr.interlace_method = 1;
+        // pixels: row 0 = 1 0 1, row 1 = 0 1 1
+        // pass 1 holds (0,0); pass 4 holds (2,0); pass 6 holds (1,0); pass 7 holds row 1
+        let scanlines = [0, 0b1000_0000, 0, 0b1000_0000, 0, 0, 0, 0b0110_0000];
+        let image = RawImage::decode(&png_from_scanlines(&ihdr, &scanlines)).unwrap();
+        assert_eq!(image.data, vec![0b1010_0000, 0b0110_0000]);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let ihdr = Ihdr::new(2, 2, 8, ColorType::Rgb).unwrap();
+        assert!(RawImage::decode(&png_from_scanlines(&ihdr, &[0, 1, 2])).is_err());
+        assert!(RawImage::decode(&png_from_scanlines(&ihdr, &[9; 14])).is_err());
+        let no_idat = Png::from_chunks(vec![ihdr.to_chunk(), Chunk::new(ChunkType::IEND, Vec::new())]);
+        assert!(RawImage::decode(&no_idat).is_err());
+    }
+
+    #[test]
+    fn test_decode_huge_header() {
+        let mut ihdr = Ihdr::new(i32::MAX as u32, i32::MAX as u32, 16, ColorType::Rgba).unwrap();
+        assert!(RawImage::decode(&png_from_scanlines(&ihdr, &[0; 64])).is_err());
+        ihdr.interlace_method = 1;
+        assert!(RawImage::decode(&png_from_scanlines(&ihdr, &[0; 64])).is_err());
+        let ihdr = Ihdr::new(100_000, 100_000, 8, ColorType::Rgb).unwrap();
+        assert!(RawImage::decode(&png_from_scanlines(&ihdr, &[0; 64])).is_err());
+    }
+
+    #[test]
+    fn test_decode_ignores_extra_data() {
+        let ihdr = Ihdr::new(2, 1, 8, ColorType::Grayscale).unwrap();
+        let image = RawImage::decode(&png_from_scanlines(&ihdr, &[0, 1, 2, 0, 0, 0])).unwrap();
+        assert_eq!(image.data, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let ihdr = Ihdr::new(3, 3, 8, ColorType::Rgb).unwrap();
+        let data: Vec<u8> = (0..27).map(|i| (i * 37 % 256) as u8).collect();
+        let image = RawImage { ihdr: ihdr.clone(), data };
+        let mut png = png_from_scanlines(&ihdr, &[0; 30]);
+        image.store(&mut png).unwrap();
+        assert_eq!(png.chunks_of_type(&ChunkType::IDAT).count(), 1);
+        assert_eq!(RawImage::decode(&png).unwrap(), image);
+    }
+
+    #[test]
+    fn test_remap_indices() {
+        let ihdr = Ihdr::new(3, 2, 2, ColorType::Indexed).unwrap();
+        // indices: row 0 = 0 1 2, row 1 = 3 0 1
+        let mut image = RawImage { ihdr, data: vec![0b0001_1000, 0b1100_0100] };
+        assert_eq!(&image.index_usage().unwrap()[..4], &[2, 2, 1, 1]);
+        image.remap_indices(&[3, 2, 1, 0]).unwrap();
+        assert_eq!(image.data, vec![0b1110_0100, 0b0011_1000]);
+        assert!(image.remap_indices(&[0]).is_err());
+    }
+}
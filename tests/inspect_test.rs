@@ -0,0 +1,48 @@
+mod tests {
+    use std::str::FromStr;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{ColorType, Ihdr};
+    use pngme::inspect::{self, InspectOptions, DEFAULT_LIMIT};
+
+    #[test]
+    fn test_hexdump_line() {
+        let dump = inspect::hexdump(b"hello\x00world", 0x20, None);
+        assert_eq!(dump, "00000020: 68 65 6c 6c 6f 00 77 6f 72 6c 64                 hello.world\n");
+    }
+
+    #[test]
+    fn test_hexdump_offsets() {
+        let data: Vec<u8> = (0..40).collect();
+        let dump = inspect::hexdump(&data, 8, None);
+        let offsets: Vec<&str> = dump.lines().map(|line| &line[..8]).collect();
+        assert_eq!(offsets, vec!["00000008", "00000018", "00000028"]);
+    }
+
+    #[test]
+    fn test_hexdump_limit() {
+        let data = vec![0u8; DEFAULT_LIMIT + 10];
+        let dump = inspect::hexdump(&data, 0, Some(DEFAULT_LIMIT));
+        assert_eq!(dump.lines().count(), DEFAULT_LIMIT / 16 + 1);
+        assert!(dump.ends_with("... 10 more bytes\n"));
+    }
+
+    #[test]
+    fn test_inspect_chunk() {
+        let chunk = Ihdr::new(4, 2, 8, ColorType::Rgb).unwrap().to_chunk();
+        let report = inspect::inspect_chunk(8, &chunk, &InspectOptions::default());
+        assert!(report.starts_with("IHDR at offset 8 (0x8)\n"));
+        assert!(report.contains("(ok)"));
+        assert!(report.contains("decoded:    4x2, 8-bit Rgb"));
+        assert!(report.contains("00000010: 00 00 00 04"));
+    }
+
+    #[test]
+    fn test_inspect_chunk_color() {
+        let chunk = Chunk::new(ChunkType::from_str("ruSt").unwrap(), b"hi".to_vec());
+        let options = InspectOptions { full: false, color: true };
+        let report = inspect::inspect_chunk(0, &chunk, &options);
+        assert!(report.contains("\x1b[1;36mruSt\x1b[0m"));
+        assert!(!report.contains("decoded"));
+    }
+}
@@ -0,0 +1,67 @@
+mod tests {
+    use std::convert::TryFrom;
+    use std::time::{Duration, UNIX_EPOCH};
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::messages::{Message, MessageStore};
+    use pngme::png::Png;
+
+    fn testing_store() -> MessageStore {
+        MessageStore::new(Png::from_chunks(vec![
+            Chunk::new(ChunkType::IHDR, vec![0; 13]),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ]))
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        let message = Message {
+            name: "greeting".to_string(),
+            content_type: "text/plain".to_string(),
+            created: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            content: b"hello".to_vec(),
+        };
+        let chunk = message.to_chunk();
+        assert_eq!(chunk.chunk_type(), ChunkType::MESSAGE);
+        assert_eq!(Message::try_from(&chunk).unwrap(), message);
+    }
+
+    #[test]
+    fn test_invalid_message() {
+        assert!(Message::new("", "text/plain", Vec::new()).is_err());
+        assert!(Message::new("name", "text\0plain", Vec::new()).is_err());
+        assert!(Message::try_from(&b"name\0text/plain\0\0\0"[..]).is_err());
+        assert!(Message::try_from(&b"no terminator"[..]).is_err());
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let mut store = testing_store();
+        store.put(Message::new("first", "text/plain", b"one".to_vec()).unwrap()).unwrap();
+        store.put(Message::new("second", "text/plain", b"two".to_vec()).unwrap()).unwrap();
+        assert_eq!(store.get("first").unwrap().unwrap().content, b"one");
+        assert_eq!(store.get("second").unwrap().unwrap().text(), Some("two"));
+        assert!(store.get("third").unwrap().is_none());
+        assert_eq!(store.png().chunks().last().unwrap().chunk_type(), ChunkType::IEND);
+    }
+
+    #[test]
+    fn test_put_replaces_same_name() {
+        let mut store = testing_store();
+        store.put(Message::new("note", "text/plain", b"old".to_vec()).unwrap()).unwrap();
+        let replaced = store.put(Message::new("note", "text/plain", b"new".to_vec()).unwrap()).unwrap();
+        assert_eq!(replaced.unwrap().content, b"old");
+        let messages = store.list().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, b"new");
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut store = testing_store();
+        store.put(Message::new("note", "text/plain", b"text".to_vec()).unwrap()).unwrap();
+        assert_eq!(store.delete("note").unwrap().name, "note");
+        assert!(store.delete("note").is_err());
+        assert_eq!(store.into_png().chunks().len(), 2);
+    }
+}
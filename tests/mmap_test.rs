@@ -0,0 +1,68 @@
+#![cfg(feature = "mmap")]
+
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::mmap::MappedPng;
+    use pngme::png::Png;
+
+    fn testing_png() -> Png {
+        Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("FrSt").unwrap(), b"I am the first chunk".to_vec()),
+            Chunk::new(ChunkType::from_str("miDl").unwrap(), b"I am another chunk".to_vec()),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ])
+    }
+
+    fn testing_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("pngme_mmap_{}_{}.png", name, std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_chunks() {
+        let png = testing_png();
+        let path = testing_file("chunks", &png.as_bytes());
+        let mapped = MappedPng::open(&path).unwrap();
+        let chunks: Vec<_> = mapped.chunks().collect::<Result<_, _>>().unwrap();
+        let offsets: Vec<usize> = chunks.iter().map(|chunk| chunk.offset()).collect();
+        let expected: Vec<usize> = png.chunks_with_offsets().map(|(offset, _)| offset).collect();
+        assert_eq!(offsets, expected);
+        assert!(chunks.iter().all(|chunk| chunk.is_crc_valid()));
+        assert_eq!(mapped.to_png().unwrap().as_bytes(), png.as_bytes());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_find() {
+        let path = testing_file("find", &testing_png().as_bytes());
+        let mapped = MappedPng::open(&path).unwrap();
+        let chunk = mapped.find(&ChunkType::from_str("miDl").unwrap()).unwrap().unwrap();
+        assert_eq!(chunk.data(), b"I am another chunk");
+        assert_eq!(chunk.summary(None, false).length, 18);
+        assert_eq!(chunk.summary(None, false).crc_valid, None);
+        assert_eq!(chunk.summary(None, true).crc_valid, Some(true));
+        assert!(mapped.find(&ChunkType::from_str("ruSt").unwrap()).unwrap().is_none());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_truncated_chunk_with_binary_type() {
+        let path = testing_file("binary_type", b"\x89PNG\r\n\x1a\n\x00\x00\x10\x00\xff\xfe\xfd\xfcabcdefgh");
+        let mapped = MappedPng::open(&path).unwrap();
+        let error = mapped.chunks().next().unwrap().err().unwrap();
+        assert!(error.to_string().contains("truncated"));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let path = testing_file("header", b"not a png at all");
+        assert!(MappedPng::open(&path).is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+mod tests {
+    use pngme::chunks::ColorType;
+    use pngme::netpbm::{self, Netpbm};
+    use pngme::pixels::{Rgba16, Rgba8};
+
+    fn rgba8(width: u32, height: u32, pixels: &[[u8; 4]]) -> Rgba16 {
+        Rgba8 { width, height, data: pixels.concat() }.to_rgba16()
+    }
+
+    #[test]
+    fn test_decode_ppm() {
+        let mut bytes = b"P6\n# made by hand\n2 1\n255\n".to_vec();
+        bytes.extend_from_slice(&[255, 0, 0, 1, 2, 3]);
+        let decoded = netpbm::decode(&bytes).unwrap();
+        assert_eq!(decoded.format, Netpbm::Ppm);
+        assert_eq!((decoded.color_type, decoded.bit_depth), (ColorType::Rgb, 8));
+        assert_eq!(decoded.image, rgba8(2, 1, &[[255, 0, 0, 255], [1, 2, 3, 255]]));
+    }
+
+    #[test]
+    fn test_decode_pgm_maxval() {
+        let decoded = netpbm::decode(b"P5 3 1 3\n\x00\x01\x03").unwrap();
+        assert_eq!((decoded.color_type, decoded.bit_depth), (ColorType::Grayscale, 2));
+        assert_eq!(decoded.image, rgba8(3, 1, &[[0, 0, 0, 255], [85, 85, 85, 255], [255, 255, 255, 255]]));
+
+        let decoded = netpbm::decode(b"P5 1 1 1000\n\x01\xf4").unwrap();
+        assert_eq!(decoded.bit_depth, 16);
+        assert_eq!(decoded.image.data, vec![32768, 32768, 32768, 0xffff]);
+    }
+
+    #[test]
+    fn test_decode_pbm() {
+        let decoded = netpbm::decode(b"P4\n10 1\n\x80\x40").unwrap();
+        assert_eq!((decoded.color_type, decoded.bit_depth), (ColorType::Grayscale, 1));
+        let gray: Vec<u8> = decoded.image.to_rgba8().pixels().map(|[gray, ..]| gray).collect();
+        assert_eq!(gray, vec![0, 255, 255, 255, 255, 255, 255, 255, 255, 0]);
+    }
+
+    #[test]
+    fn test_decode_pam() {
+        let mut bytes = b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 2\nMAXVAL 255\nTUPLTYPE GRAYSCALE_ALPHA\nENDHDR\n".to_vec();
+        bytes.extend_from_slice(&[200, 100]);
+        let decoded = netpbm::decode(&bytes).unwrap();
+        assert_eq!((decoded.color_type, decoded.bit_depth), (ColorType::GrayscaleAlpha, 8));
+        assert_eq!(decoded.image, rgba8(1, 1, &[[200, 200, 200, 100]]));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(netpbm::decode(b"P3\n1 1\n255\n0 0 0").is_err());
+        assert!(netpbm::decode(b"P6\n2 1\n255\n\x00\x00\x00").is_err());
+        assert!(netpbm::decode(b"P5\n1 1\n100\n\xff").is_err());
+        assert!(netpbm::decode(b"P7\nWIDTH 1\nHEIGHT 1\nMAXVAL 255\nENDHDR\n\x00").is_err());
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let image = rgba8(2, 2, &[[10, 20, 30, 255], [40, 50, 60, 128], [0, 0, 0, 0], [255, 255, 255, 255]]);
+        for depth in [8, 16] {
+            let bytes = netpbm::encode(&image, Netpbm::Pam, depth).unwrap();
+            assert_eq!(netpbm::decode(&bytes).unwrap().image, image);
+        }
+        let bytes = netpbm::encode(&image, Netpbm::Ppm, 8).unwrap();
+        assert_eq!(&bytes[..11], b"P6\n2 2\n255\n");
+        assert_eq!(&bytes[11..], &[10, 20, 30, 40, 50, 60, 0, 0, 0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_encode_gray() {
+        let image = rgba8(3, 1, &[[255, 0, 0, 255], [100, 100, 100, 255], [255, 255, 255, 255]]);
+        assert_eq!(netpbm::encode(&image, Netpbm::Pgm, 8).unwrap(), b"P5\n3 1\n255\n\x4c\x64\xff".to_vec());
+        assert_eq!(netpbm::encode(&image, Netpbm::Pbm, 8).unwrap(), b"P4\n3 1\n\xc0".to_vec());
+        let pam = netpbm::encode(&rgba8(1, 1, &[[7, 7, 7, 255]]), Netpbm::Pam, 8).unwrap();
+        assert!(pam.starts_with(b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 1\n"));
+        assert!(netpbm::encode(&image, Netpbm::Ppm, 4).is_err());
+    }
+}
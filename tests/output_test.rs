@@ -0,0 +1,84 @@
+mod tests {
+    use std::str::FromStr;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{Background, ColorType, Ihdr, PhysicalDimensions};
+    use pngme::output::{self, Format};
+    use pngme::png::Png;
+
+    fn testing_png() -> Png {
+        Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("FrSt").unwrap(), b"I am the first chunk".to_vec()),
+            Chunk::new(ChunkType::from_str("ruSt").unwrap(), b"hidden".to_vec()),
+        ])
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!(Format::from_str("table").unwrap(), Format::Table);
+        assert_eq!(Format::from_str("json").unwrap(), Format::Json);
+        assert_eq!(Format::from_str("yaml").unwrap(), Format::Yaml);
+        assert!(Format::from_str("xml").is_err());
+    }
+
+    #[test]
+    fn test_chunk_table() {
+        let table = output::chunk_table(&output::PngSummary::new(&testing_png()));
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[2].contains("ruSt"));
+        assert!(lines[2].contains("ancillary,private,safe"));
+    }
+
+    #[test]
+    fn test_chunk_table_decoded() {
+        let ihdr = Ihdr::new(1, 1, 8, ColorType::Rgb).unwrap();
+        let png = Png::from_chunks(vec![
+            ihdr.to_chunk(),
+            Background::Rgb { red: 1, green: 2, blue: 3 }.to_chunk(),
+            PhysicalDimensions::from_dpi(72.0, 72.0).unwrap().to_chunk(),
+        ]);
+        let summary = output::PngSummary::new(&png);
+        assert_eq!(summary.chunks[1].decoded.as_deref(), Some("rgb 1 2 3"));
+        let table = output::chunk_table(&summary);
+        assert!(table.lines().next().unwrap().ends_with("DECODED"));
+        assert!(table.contains("2835x2835 pixels per meter (72x72 dpi)"));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(output::format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(output::format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(output::format_timestamp(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_png_to_json() {
+        let json = output::to_string(&output::PngSummary::new(&testing_png()), Format::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let chunks = value["chunks"].as_array().unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1]["offset"], 40);
+        assert_eq!(chunks[1]["type"], "ruSt");
+        assert_eq!(chunks[1]["critical"], false);
+        assert_eq!(chunks[1]["safe_to_copy"], true);
+        assert_eq!(chunks[1]["crc_valid"], true);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_png_to_yaml() {
+        let yaml = output::to_string(&output::PngSummary::new(&testing_png()), Format::Yaml).unwrap();
+        assert!(yaml.contains("type: FrSt"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_validation_to_json() {
+        let validation = output::Validation { valid: false, problems: vec!["missing IHDR".to_string()] };
+        let value: serde_json::Value = serde_json::from_str(&output::to_string(&validation, Format::Json).unwrap()).unwrap();
+        assert_eq!(value["valid"], false);
+        assert_eq!(value["problems"][0], "missing IHDR");
+    }
+}
@@ -0,0 +1,103 @@
+mod tests {
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{Background, ColorType, Histogram, Ihdr, Palette, Transparency};
+    use pngme::image::RawImage;
+    use pngme::palette::{self, SortKey};
+    use pngme::png::Png;
+
+    /// A 4x1 image with 2-bit indices 0 1 2 1 into a white, black, gray
+    /// palette, where black is half transparent.
+    fn testing_png() -> Png {
+        let ihdr = Ihdr::new(4, 1, 2, ColorType::Indexed).unwrap();
+        let mut png = Png::from_chunks(vec![
+            ihdr.to_chunk(),
+            Palette::new(vec![[255, 255, 255], [0, 0, 0], [128, 128, 128]]).unwrap().to_chunk(),
+            Transparency::Palette(vec![255, 128]).to_chunk(),
+            Chunk::new(ChunkType::IDAT, Vec::new()),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ]);
+        RawImage { ihdr, data: vec![0b0001_1001] }.store(&mut png).unwrap();
+        png
+    }
+
+    #[test]
+    fn test_entries() {
+        let entries = palette::entries(&testing_png()).unwrap();
+        let colors: Vec<(&str, u8)> = entries.iter().map(|entry| (entry.color.as_str(), entry.alpha)).collect();
+        assert_eq!(colors, vec![("#ffffff", 255), ("#000000", 128), ("#808080", 255)]);
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(palette::parse_color("#ff8000").unwrap(), [255, 128, 0]);
+        assert_eq!(palette::parse_color("0a0B0c").unwrap(), [10, 11, 12]);
+        assert!(palette::parse_color("#fff").is_err());
+        assert!(palette::parse_color("#gg0000").is_err());
+    }
+
+    #[test]
+    fn test_set() {
+        let mut png = testing_png();
+        palette::set(&mut png, 1, [1, 2, 3], Some(255)).unwrap();
+        palette::set(&mut png, 3, [4, 5, 6], Some(0)).unwrap();
+        let entries = palette::entries(&png).unwrap();
+        assert_eq!(entries[1].color, "#010203");
+        assert_eq!(entries[3].alpha, 0);
+        let trns = png.chunks_of_type(&ChunkType::TRNS).next().unwrap();
+        assert_eq!(trns.data(), &[255, 255, 255, 0]);
+        // 2-bit indices leave no room for a fifth entry
+        assert!(palette::set(&mut png, 4, [0, 0, 0], None).is_err());
+        assert!(palette::set(&mut png, 9, [0, 0, 0], None).is_err());
+    }
+
+    #[test]
+    fn test_sort_remaps_pixels() {
+        let mut png = testing_png();
+        assert!(palette::sort(&mut png, SortKey::Luminance).unwrap());
+        let colors: Vec<String> = palette::entries(&png).unwrap().into_iter().map(|entry| entry.color).collect();
+        assert_eq!(colors, vec!["#000000", "#808080", "#ffffff"]);
+        assert_eq!(png.chunks_of_type(&ChunkType::TRNS).next().unwrap().data(), &[128]);
+        // pixels were 0 1 2 1 (white black gray black)
+        assert_eq!(RawImage::decode(&png).unwrap().data, vec![0b1000_0100]);
+        assert!(!palette::sort(&mut png, SortKey::Luminance).unwrap());
+    }
+
+    #[test]
+    fn test_sort_remaps_background_and_histogram() {
+        let mut png = testing_png();
+        png.insert_chunk(3, Background::PaletteIndex(2).to_chunk()).unwrap();
+        png.set(&Histogram { frequencies: vec![1, 2, 1] }).unwrap();
+        palette::sort(&mut png, SortKey::Luminance).unwrap();
+        // gray moved from index 2 to 1, and the frequencies follow the colors
+        assert_eq!(png.chunks_of_type(&ChunkType::BKGD).next().unwrap().data(), &[1]);
+        assert_eq!(png.get::<Histogram>().unwrap().unwrap().frequencies, vec![2, 1, 1]);
+
+        png.set(&Histogram { frequencies: vec![1, 2] }).unwrap();
+        assert!(palette::sort(&mut png, SortKey::Rgb).is_err());
+    }
+
+    #[test]
+    fn test_sort_by_usage_and_alpha() {
+        let mut png = testing_png();
+        assert!(palette::sort(&mut png, SortKey::Usage).unwrap());
+        assert_eq!(palette::entries(&png).unwrap()[0].color, "#000000");
+        palette::sort(&mut png, SortKey::Rgb).unwrap();
+        palette::sort(&mut png, SortKey::Alpha).unwrap();
+        assert_eq!(palette::entries(&png).unwrap()[0].alpha, 128);
+    }
+
+    #[test]
+    fn test_sort_requires_indexed() {
+        let ihdr = Ihdr::new(1, 1, 8, ColorType::Rgb).unwrap();
+        let mut png = Png::from_chunks(vec![
+            ihdr.to_chunk(),
+            Palette::new(vec![[0, 0, 0]]).unwrap().to_chunk(),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ]);
+        assert!(palette::sort(&mut png, SortKey::Rgb).is_err());
+        assert!(palette::set(&mut png, 0, [1, 1, 1], Some(0)).is_err());
+        palette::set(&mut png, 1, [1, 1, 1], None).unwrap();
+        assert_eq!(palette::entries(&png).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,140 @@
+mod tests {
+    use pngme::builder::PngBuilder;
+    use pngme::chunks::{ColorType, Ihdr, Palette, Transparency};
+    use pngme::pixels::{Rgba16, Rgba8};
+    use pngme::validate::validate;
+
+    fn rgba8(width: u32, height: u32, pixels: &[[u8; 4]]) -> Rgba8 {
+        Rgba8 { width, height, data: pixels.concat() }
+    }
+
+    #[test]
+    fn test_decode_gray_bit_depths() {
+        let expected = |values: &[u8]| rgba8(4, 1, &values.iter().map(|&v| [v, v, v, 255]).collect::<Vec<_>>());
+        let cases: [(u8, u8, &[u8]); 4] = [
+            (1, 0b1010_0000, &[255, 0, 255, 0]),
+            (2, 0b0001_1011, &[0, 85, 170, 255]),
+            (4, 0x0f, &[0, 255, 0, 0]),
+            (8, 0x80, &[128, 0, 0, 0]),
+        ];
+        for (depth, first, values) in cases {
+            let ihdr = Ihdr::new(4, 1, depth, ColorType::Grayscale).unwrap();
+            let mut row = vec![0u8; ihdr.row_bytes(4)];
+            row[0] = first;
+            let png = PngBuilder::new(ihdr).row(&row).build().unwrap();
+            assert_eq!(Rgba8::from_png(&png, None).unwrap(), expected(values), "bit depth {}", depth);
+        }
+    }
+
+    #[test]
+    fn test_decode_gray_transparency() {
+        let png = PngBuilder::new(Ihdr::new(2, 1, 16, ColorType::Grayscale).unwrap())
+            .transparency(Transparency::Gray(0x1234))
+            .row(&[0x12, 0x34, 0xff, 0xff])
+            .build()
+            .unwrap();
+        let image = Rgba16::from_png(&png, None).unwrap();
+        assert_eq!(image.data, vec![0x1234, 0x1234, 0x1234, 0, 0xffff, 0xffff, 0xffff, 0xffff]);
+    }
+
+    #[test]
+    fn test_decode_rgb_transparency() {
+        let png = PngBuilder::new(Ihdr::new(2, 1, 8, ColorType::Rgb).unwrap())
+            .transparency(Transparency::Rgb { red: 1, green: 2, blue: 3 })
+            .row(&[1, 2, 3, 4, 5, 6])
+            .build()
+            .unwrap();
+        assert_eq!(Rgba8::from_png(&png, None).unwrap(), rgba8(2, 1, &[[1, 2, 3, 0], [4, 5, 6, 255]]));
+    }
+
+    #[test]
+    fn test_decode_indexed() {
+        let png = PngBuilder::new(Ihdr::new(3, 1, 2, ColorType::Indexed).unwrap())
+            .palette(Palette::new(vec![[10, 20, 30], [40, 50, 60], [70, 80, 90]]).unwrap())
+            .transparency(Transparency::Palette(vec![0, 128]))
+            .row(&[0b0001_1000])
+            .build()
+            .unwrap();
+        let expected = rgba8(3, 1, &[[10, 20, 30, 0], [40, 50, 60, 128], [70, 80, 90, 255]]);
+        assert_eq!(Rgba8::from_png(&png, None).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_decode_alpha_types() {
+        let png = PngBuilder::new(Ihdr::new(1, 1, 8, ColorType::GrayscaleAlpha).unwrap())
+            .row(&[100, 50])
+            .build()
+            .unwrap();
+        assert_eq!(Rgba8::from_png(&png, None).unwrap(), rgba8(1, 1, &[[100, 100, 100, 50]]));
+
+        let png = PngBuilder::new(Ihdr::new(1, 1, 16, ColorType::Rgba).unwrap())
+            .row(&[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0xff, 0x00])
+            .build()
+            .unwrap();
+        assert_eq!(Rgba16::from_png(&png, None).unwrap().data, vec![0x0102, 0x0304, 0x0506, 0xff00]);
+        assert_eq!(Rgba8::from_png(&png, None).unwrap(), rgba8(1, 1, &[[1, 3, 5, 254]]));
+    }
+
+    #[test]
+    fn test_decode_gamma() {
+        let png = PngBuilder::new(Ihdr::new(2, 1, 8, ColorType::GrayscaleAlpha).unwrap())
+            .gamma(1.0)
+            .row(&[0, 64, 128, 64])
+            .build()
+            .unwrap();
+        assert_eq!(Rgba8::from_png(&png, None).unwrap(), rgba8(2, 1, &[[0, 0, 0, 64], [128, 128, 128, 64]]));
+        let corrected = Rgba8::from_png(&png, Some(2.0)).unwrap();
+        assert_eq!(corrected, rgba8(2, 1, &[[0, 0, 0, 64], [181, 181, 181, 64]]));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let image = rgba8(3, 2, &[
+            [0, 0, 0, 255], [255, 255, 255, 255], [255, 0, 0, 0],
+            [255, 0, 0, 0], [0, 0, 0, 255], [0, 0, 0, 255],
+        ]);
+        let cases = [
+            (ColorType::Indexed, 2), (ColorType::Indexed, 8), (ColorType::Rgb, 8), (ColorType::Rgb, 16),
+            (ColorType::Rgba, 8), (ColorType::Rgba, 16),
+        ];
+        for (color_type, depth) in cases {
+            let png = image.to_png(color_type, depth).unwrap();
+            assert!(validate(&png).is_empty());
+            assert_eq!(Rgba8::from_png(&png, None).unwrap(), image, "{:?} {}", color_type, depth);
+        }
+    }
+
+    #[test]
+    fn test_encode_gray() {
+        let image = rgba8(4, 1, &[[0, 0, 0, 255], [85, 85, 85, 255], [170, 170, 170, 0], [255, 255, 255, 255]]);
+        let (raw, format) = image.encode(ColorType::Grayscale, 2).unwrap();
+        assert_eq!(raw.data, vec![0b0001_1011]);
+        assert_eq!(format.transparency, Some(Transparency::Gray(2)));
+        assert_eq!(Rgba8::from_raw(&raw, &format, None).unwrap(), image);
+
+        let (raw, format) = image.encode(ColorType::GrayscaleAlpha, 8).unwrap();
+        assert_eq!(raw.data, vec![0, 255, 85, 255, 170, 0, 255, 255]);
+        assert_eq!(format.transparency, None);
+    }
+
+    #[test]
+    fn test_encode_moves_transparent_key() {
+        let image = rgba8(2, 1, &[[0, 0, 0, 0], [0, 0, 0, 255]]);
+        let (raw, format) = image.encode(ColorType::Grayscale, 8).unwrap();
+        assert_eq!(raw.data, vec![1, 0]);
+        assert_eq!(format.transparency, Some(Transparency::Gray(1)));
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let image = rgba8(2, 1, &[[255, 0, 0, 255], [0, 0, 0, 100]]);
+        assert!(image.encode(ColorType::Grayscale, 8).is_err());
+        assert!(image.encode(ColorType::Rgb, 8).is_err());
+        assert!(image.encode(ColorType::Rgb, 4).is_err());
+        assert!(image.encode(ColorType::Indexed, 1).is_ok());
+
+        let image = rgba8(3, 1, &[[0, 0, 0, 255], [1, 1, 1, 255], [2, 2, 2, 255]]);
+        assert!(image.encode(ColorType::Indexed, 1).is_err());
+        assert!(Rgba8 { width: 2, height: 2, data: vec![0; 4] }.encode(ColorType::Rgba, 8).is_err());
+    }
+}
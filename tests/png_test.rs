@@ -1,3 +1,5 @@
+#![allow(unused_imports, clippy::vec_init_then_push)]
+
 mod tests {
     use super::*;
     use std::str::FromStr;
@@ -98,6 +100,15 @@ mod tests {
     }
 
 
+    #[test]
+    fn test_truncated_bytes() {
+        let bytes = testing_png().as_bytes();
+        assert!(Png::try_from(&bytes[..5]).is_err());
+        assert!(Png::try_from(&bytes[..bytes.len() - 6]).is_err());
+        assert!(Png::try_from(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Png::try_from(&b"\x89PNG\r\n\x1a\n\x00\x00\x10\x00\xff\xfe\xfd\xfcabcdefgh"[..]).is_err());
+    }
+
     #[test]
     fn test_list_chunks() {
         let png = testing_png();
@@ -105,6 +116,15 @@ mod tests {
         assert_eq!(chunks.len(), 3);
     }
 
+    #[test]
+    fn test_chunks_with_offsets() {
+        let png = testing_png();
+        let offsets: Vec<usize> = png.chunks_with_offsets()
+            .map(|(offset, _)| offset)
+            .collect();
+        assert_eq!(offsets, vec![8, 40, 70]);
+    }
+
     #[test]
     fn test_chunk_by_type() {
         let png = testing_png();
@@ -123,6 +143,14 @@ mod tests {
         assert_eq!(&chunk.data_as_string().unwrap(), "Message");
     }
 
+    #[test]
+    fn test_append_chunk_to_empty_png() {
+        let mut png = Png::from_chunks(Vec::new());
+        png.append_chunk(chunk_from_strings("TeSt", "Message").unwrap());
+        assert_eq!(png.chunks().len(), 1);
+        assert_eq!(png.positions_of(&"TeSt".parse().unwrap()), &[0]);
+    }
+
     #[test]
     fn test_remove_chunk() {
         let mut png = testing_png();
@@ -132,6 +160,144 @@ mod tests {
         assert!(chunk.is_none());
     }
 
+    #[test]
+    fn test_chunks_of_type() {
+        let mut png = testing_png();
+        png.append_chunk(chunk_from_strings("miDl", "I am a second middle chunk").unwrap());
+        let messages: Vec<String> = png.chunks_of_type(&"miDl".parse().unwrap())
+            .map(|chunk| chunk.data_as_string().unwrap())
+            .collect();
+        assert_eq!(messages, vec!["I am another chunk", "I am a second middle chunk"]);
+        assert_eq!(png.positions_of(&"miDl".parse().unwrap()), &[1, 2]);
+        assert!(png.positions_of(&"ruSt".parse().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_index_follows_mutations() {
+        let mut png = testing_png();
+        png.remove_chunk("FrSt").unwrap();
+        assert_eq!(png.positions_of(&"LASt".parse().unwrap()), &[1]);
+        png.retain_chunks(|chunk| chunk.chunk_type().is_public());
+        assert_eq!(png.positions_of(&"LASt".parse().unwrap()), &[0]);
+        assert!(png.get_chunk("miDl").is_none());
+    }
+
+    #[test]
+    fn test_insert_and_replace_chunk() {
+        let mut png = testing_png();
+        png.insert_chunk(1, chunk_from_strings("TeSt", "Message").unwrap()).unwrap();
+        assert_eq!(png.positions_of(&"miDl".parse().unwrap()), &[2]);
+        let old = png.replace_chunk(0, chunk_from_strings("NeWw", "Replaced").unwrap()).unwrap();
+        assert_eq!(&old.chunk_type().to_string(), "FrSt");
+        assert!(png.get_chunk("FrSt").is_none());
+        assert_eq!(png.positions_of(&"NeWw".parse().unwrap()), &[0]);
+        assert!(png.insert_chunk(9, chunk_from_strings("TeSt", "Message").unwrap()).is_err());
+        assert!(png.replace_chunk(9, chunk_from_strings("TeSt", "Message").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_set_chunk() {
+        let mut png = testing_png();
+        let replaced = png.set_chunk(chunk_from_strings("miDl", "I replace the middle").unwrap()).unwrap();
+        assert_eq!(replaced.data_as_string().unwrap(), "I am another chunk");
+        assert_eq!(png.positions_of(&"miDl".parse().unwrap()), &[1]);
+        assert!(png.set_chunk(chunk_from_strings("TeSt", "Message").unwrap()).is_none());
+        assert_eq!(png.chunks().len(), 4);
+    }
+
+    #[test]
+    fn test_find() {
+        let png = testing_png();
+        let chunk = png.find(|chunk| chunk.data().starts_with(b"I am the")).unwrap();
+        assert_eq!(&chunk.chunk_type().to_string(), "FrSt");
+        assert_eq!(png.find_all(|chunk| chunk.data().starts_with(b"I am the")).count(), 2);
+        assert!(png.find(|chunk| chunk.data().is_empty()).is_none());
+    }
+
+    fn testing_png_with_iend() -> Png {
+        let mut chunks = testing_chunks();
+        chunks.push(Chunk::new(ChunkType::IEND, Vec::new()));
+        Png::from_chunks(chunks)
+    }
+
+    #[test]
+    fn test_trailing_data() {
+        let mut bytes = testing_png_with_iend().as_bytes();
+        // a chunk after IEND is trailing data too, not a chunk
+        let mut trailing = chunk_from_strings("AftR", "after the end").unwrap().as_bytes();
+        trailing.extend_from_slice(b"PK\x03\x04 appended archive");
+        bytes.extend_from_slice(&trailing);
+
+        let mut png = Png::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(png.chunks().len(), 4);
+        assert_eq!(png.trailing_data(), trailing.as_slice());
+        assert_eq!(png.as_bytes(), bytes);
+
+        assert_eq!(png.take_trailing_data(), trailing);
+        assert!(png.trailing_data().is_empty());
+        assert_eq!(png.as_bytes(), testing_png_with_iend().as_bytes());
+    }
+
+    #[test]
+    fn test_trailing_garbage_is_not_parsed() {
+        let mut bytes = testing_png_with_iend().as_bytes();
+        bytes.extend_from_slice(&[0xff; 5]);
+        let png = Png::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(png.trailing_data(), &[0xff; 5]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_png_serde_trailing_data() {
+        let mut png = testing_png_with_iend();
+        png.set_trailing_data(b"trailing".to_vec());
+        let json = serde_json::to_string(&png).unwrap();
+        assert!(json.contains(r#""trailing":"dHJhaWxpbmc=""#));
+        let restored: Png = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.as_bytes(), png.as_bytes());
+        assert!(!serde_json::to_string(&testing_png_with_iend()).unwrap().contains("trailing"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_png_serde_round_trip() {
+        let png = Png::try_from(&PNG_FILE[..]).unwrap();
+        let json = serde_json::to_string(&png).unwrap();
+        let restored: Png = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.as_bytes(), png.as_bytes());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_png_deserialize_decoded_chunks() {
+        let json = r#"{
+            "chunks": [
+                {"type": "IHDR", "width": 2, "height": 3, "bit_depth": 8, "color_type": "rgb"},
+                {"type": "tEXt", "keyword": "Author", "text": "pngme"},
+                {"type": "ruSt", "hex": "6869"},
+                {"type": "IEND", "base64": ""}
+            ]
+        }"#;
+        let png: Png = serde_json::from_str(json).unwrap();
+        assert_eq!(png.chunks().len(), 4);
+        assert_eq!(png.chunks()[0].data(), &[0, 0, 0, 2, 0, 0, 0, 3, 8, 2, 0, 0, 0]);
+        assert_eq!(png.chunks()[1].data(), b"Author\0pngme");
+        assert_eq!(png.chunk_by_type("ruSt").unwrap().data_as_string().unwrap(), "hi");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_png_deserialize_mismatched_fields() {
+        let json = r#"{"chunks": [{"type": "ruSt", "keyword": "Author", "text": "pngme"}]}"#;
+        assert!(serde_json::from_str::<Png>(json).is_err());
+    }
+
+    #[test]
+    fn test_png_from_reader() {
+        let png = Png::from_reader(&PNG_FILE[..]).unwrap();
+        assert_eq!(png.as_bytes(), PNG_FILE.to_vec());
+    }
+
     #[test]
     fn test_png_from_image_file() {
         let png = Png::try_from(&PNG_FILE[..]);
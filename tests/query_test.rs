@@ -0,0 +1,50 @@
+mod tests {
+    use std::str::FromStr;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::png::Png;
+    use pngme::query::ChunkQuery;
+
+    fn testing_png() -> Png {
+        Png::from_chunks(vec![
+            Chunk::new(ChunkType::IHDR, vec![0; 13]),
+            Chunk::new(ChunkType::TEXT, b"Author\0pngme".to_vec()),
+            Chunk::new(ChunkType::from_str("ruSt").unwrap(), b"hidden".to_vec()),
+            Chunk::new(ChunkType::from_str("ruST").unwrap(), b"unsafe".to_vec()),
+            Chunk::new(ChunkType::IDAT, Vec::new()),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ])
+    }
+
+    fn types(png: &Png, query: &ChunkQuery) -> Vec<String> {
+        png.query(query).map(|chunk| chunk.chunk_type().to_string()).collect()
+    }
+
+    #[test]
+    fn test_empty_query_matches_everything() {
+        let png = testing_png();
+        assert_eq!(png.query(&ChunkQuery::new()).count(), 6);
+    }
+
+    #[test]
+    fn test_query_by_type() {
+        let png = testing_png();
+        assert_eq!(types(&png, &ChunkQuery::new().chunk_type(ChunkType::TEXT)), vec!["tEXt"]);
+    }
+
+    #[test]
+    fn test_query_by_properties() {
+        let png = testing_png();
+        let private = ChunkQuery::new().public(false);
+        assert_eq!(types(&png, &private), vec!["ruSt", "ruST"]);
+        assert_eq!(types(&png, &private.safe_to_copy(true)), vec!["ruSt"]);
+        assert_eq!(types(&png, &ChunkQuery::new().critical(true)), vec!["IHDR", "IDAT", "IEND"]);
+    }
+
+    #[test]
+    fn test_query_combines_type_and_properties() {
+        let png = testing_png();
+        let query = ChunkQuery::new().chunk_type(ChunkType::TEXT).public(false);
+        assert!(types(&png, &query).is_empty());
+    }
+}
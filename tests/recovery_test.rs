@@ -0,0 +1,83 @@
+mod tests {
+    use std::str::FromStr;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::png::Png;
+    use pngme::recovery::{Issue, Recovery};
+
+    fn testing_png() -> Png {
+        Png::from_chunks(vec![
+            Chunk::new(ChunkType::from_str("FrSt").unwrap(), b"I am the first chunk".to_vec()),
+            Chunk::new(ChunkType::from_str("miDl").unwrap(), b"I am another chunk".to_vec()),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ])
+    }
+
+    #[test]
+    fn test_clean_file() {
+        let bytes = testing_png().as_bytes();
+        let recovery = Recovery::from_bytes(&bytes);
+        assert!(recovery.is_clean());
+        assert_eq!(recovery.repaired().as_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_crc_mismatch() {
+        let mut bytes = testing_png().as_bytes();
+        bytes[16] ^= 0xff;
+        let recovery = Recovery::from_bytes(&bytes);
+        assert_eq!(recovery.issues.len(), 1);
+        assert!(matches!(recovery.issues[0], Issue::CrcMismatch { offset: 8, .. }));
+        assert!(!recovery.png.chunks()[0].is_crc_valid());
+        assert!(recovery.repaired().chunks()[0].is_crc_valid());
+        assert_eq!(recovery.repaired().chunks().len(), 3);
+    }
+
+    #[test]
+    fn test_garbage_between_chunks() {
+        let mut bytes = testing_png().as_bytes();
+        let second = 8 + 12 + 20;
+        bytes.splice(second..second, b"\x00\x01garbage".iter().copied());
+        let recovery = Recovery::from_bytes(&bytes);
+        assert_eq!(recovery.issues, vec![Issue::Garbage { offset: second, length: 9 }]);
+        assert_eq!(recovery.repaired().as_bytes(), testing_png().as_bytes());
+    }
+
+    #[test]
+    fn test_corrupted_length() {
+        let mut bytes = testing_png().as_bytes();
+        // still inside the file, but ends in the middle of the next chunk
+        bytes[11] = 24;
+        let recovery = Recovery::from_bytes(&bytes);
+        let second = 8 + 12 + 20;
+        assert_eq!(recovery.issues, vec![Issue::Garbage { offset: 8, length: second - 8 }]);
+        let repaired = recovery.repaired();
+        let types: Vec<String> = repaired.chunks().iter().map(|chunk| chunk.chunk_type().to_string()).collect();
+        assert_eq!(types, vec!["miDl", "IEND"]);
+    }
+
+    #[test]
+    fn test_truncated_final_chunk() {
+        let mut bytes = testing_png().as_bytes();
+        bytes.truncate(bytes.len() - 12 - 10);
+        let recovery = Recovery::from_bytes(&bytes);
+        assert!(matches!(recovery.issues[0], Issue::Truncated { declared: 18, available: 12, .. }));
+        assert_eq!(recovery.issues[1], Issue::MissingIend);
+        let repaired = recovery.repaired();
+        assert_eq!(repaired.chunks()[1].data(), b"I am another");
+        assert_eq!(repaired.chunks()[2].chunk_type(), ChunkType::IEND);
+    }
+
+    #[test]
+    fn test_invalid_header_and_trailing_data() {
+        let mut bytes = testing_png().as_bytes();
+        bytes[0] = 0;
+        bytes.extend_from_slice(b"trailer");
+        let recovery = Recovery::from_bytes(&bytes);
+        assert_eq!(recovery.issues[0], Issue::InvalidHeader);
+        assert!(matches!(recovery.issues[1], Issue::TrailingData { length: 7, .. }));
+        let mut expected = testing_png().as_bytes();
+        expected.extend_from_slice(b"trailer");
+        assert_eq!(recovery.repaired().as_bytes(), expected);
+    }
+}
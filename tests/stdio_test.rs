@@ -0,0 +1,24 @@
+mod tests {
+    use std::path::Path;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::png::Png;
+    use pngme::stdio;
+
+    #[test]
+    fn test_is_stdio() {
+        assert!(stdio::is_stdio(Path::new("-")));
+        assert!(!stdio::is_stdio(Path::new("./-")));
+        assert!(!stdio::is_stdio(Path::new("image.png")));
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("pngme_stdio_{}.png", std::process::id()));
+        let png = Png::from_chunks(vec![Chunk::new(ChunkType::IEND, Vec::new())]);
+        stdio::write(&path, &png.as_bytes()).unwrap();
+        assert_eq!(stdio::read(&path).unwrap(), png.as_bytes());
+        assert_eq!(stdio::read_png(&path).unwrap().as_bytes(), png.as_bytes());
+        std::fs::remove_file(path).unwrap();
+    }
+}
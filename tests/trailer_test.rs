@@ -0,0 +1,52 @@
+mod tests {
+    use std::convert::TryFrom;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::png::Png;
+    use pngme::trailer;
+
+    fn testing_png() -> Png {
+        Png::from_chunks(vec![
+            Chunk::new(ChunkType::IHDR, vec![0; 13]),
+            Chunk::new(ChunkType::IEND, Vec::new()),
+        ])
+    }
+
+    #[test]
+    fn test_frame_round_trip() {
+        let frame = trailer::frame(b"hidden").unwrap();
+        assert!(frame.starts_with(trailer::MAGIC));
+        assert_eq!(trailer::read(&frame).unwrap(), Some((0, b"hidden".to_vec())));
+        assert_eq!(trailer::read(b"no frame here").unwrap(), None);
+    }
+
+    #[test]
+    fn test_corrupt_frame() {
+        let mut frame = trailer::frame(b"hidden").unwrap();
+        let last = frame.len() - 1;
+        frame[last] ^= 1;
+        assert!(trailer::read(&frame).is_err());
+        assert!(trailer::read(&frame[..10]).is_err());
+    }
+
+    #[test]
+    fn test_write_survives_round_trip() {
+        let mut png = testing_png();
+        trailer::write(&mut png, b"hidden").unwrap();
+        let png = Png::try_from(png.as_bytes().as_slice()).unwrap();
+        assert_eq!(png.chunks().len(), 2);
+        assert_eq!(trailer::read(png.trailing_data()).unwrap().unwrap().1, b"hidden");
+    }
+
+    #[test]
+    fn test_write_keeps_other_trailing_data() {
+        let mut png = testing_png();
+        png.set_trailing_data(b"PK\x03\x04archive".to_vec());
+        trailer::write(&mut png, b"first").unwrap();
+        trailer::write(&mut png, b"second").unwrap();
+        let mut expected = b"PK\x03\x04archive".to_vec();
+        expected.extend_from_slice(&trailer::frame(b"second").unwrap());
+        assert_eq!(png.trailing_data(), expected.as_slice());
+        assert_eq!(trailer::read(png.trailing_data()).unwrap(), Some((11, b"second".to_vec())));
+    }
+}
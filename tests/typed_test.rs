@@ -0,0 +1,97 @@
+mod tests {
+    use pngme::builder::PngBuilder;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{Chromaticities, Chromaticity, ColorType, Gamma, Histogram, Ihdr, Palette, PhysicalDimensions, Placement, Text, TypedChunk};
+    use pngme::png::Png;
+    use pngme::validate::validate;
+    use pngme::Result;
+
+    #[derive(Debug, PartialEq)]
+    struct Label(String);
+
+    impl TypedChunk for Label {
+        const TYPE: ChunkType = ChunkType::new(*b"laBl");
+        const PLACEMENT: Placement = Placement::BeforeIdat;
+
+        fn decode(data: &[u8]) -> Result<Self> {
+            Ok(Label(String::from_utf8(data.to_vec())?))
+        }
+
+        fn encode(&self) -> Result<Vec<u8>> {
+            Ok(self.0.as_bytes().to_vec())
+        }
+    }
+
+    fn indexed_png() -> Png {
+        PngBuilder::new(Ihdr::new(2, 1, 8, ColorType::Indexed).unwrap())
+            .palette(Palette::new(vec![[0, 0, 0], [255, 255, 255]]).unwrap())
+            .row(&[0, 1])
+            .build()
+            .unwrap()
+    }
+
+    fn types(png: &Png) -> Vec<String> {
+        png.chunks().iter().map(|chunk| chunk.chunk_type().to_string()).collect()
+    }
+
+    #[test]
+    fn test_set_places_chunks() {
+        let mut png = indexed_png();
+        png.set(&Text::new("Author", "x").unwrap()).unwrap();
+        png.set(&PhysicalDimensions::from_dpi(72.0, 72.0).unwrap()).unwrap();
+        png.set(&Histogram { frequencies: vec![1, 1] }).unwrap();
+        png.set(&Gamma::new(0.45455).unwrap()).unwrap();
+        assert_eq!(types(&png), vec!["IHDR", "gAMA", "PLTE", "pHYs", "hIST", "IDAT", "tEXt", "IEND"]);
+        assert!(validate(&png).is_empty());
+    }
+
+    #[test]
+    fn test_get() {
+        let mut png = indexed_png();
+        assert_eq!(png.get::<Gamma>().unwrap(), None);
+        png.set(&Gamma::new(0.5).unwrap()).unwrap();
+        assert_eq!(png.get::<Gamma>().unwrap(), Some(Gamma::new(0.5).unwrap()));
+
+        png.append_chunk(Chunk::new(ChunkType::GAMA, vec![0; 3]));
+        png.set(&Gamma::new(0.45455).unwrap()).unwrap();
+        assert_eq!(png.positions_of(&ChunkType::GAMA).len(), 1);
+        assert_eq!(png.get::<Gamma>().unwrap(), Some(Gamma::new(0.45455).unwrap()));
+    }
+
+    #[test]
+    fn test_get_invalid() {
+        let mut png = indexed_png();
+        png.append_chunk(Chunk::new(ChunkType::GAMA, vec![0; 3]));
+        assert!(png.get::<Gamma>().is_err());
+    }
+
+    #[test]
+    fn test_set_invalid() {
+        let mut png = indexed_png();
+        let before = png.as_bytes();
+        assert!(png.set(&Gamma { gamma: -1.0 }).is_err());
+        let red = Chromaticity { x: f64::NAN, y: 0.33 };
+        assert!(png.set(&Chromaticities { red, ..Chromaticities::SRGB }).is_err());
+        assert_eq!(png.as_bytes(), before);
+    }
+
+    #[test]
+    fn test_multiple_chunks_are_kept() {
+        let mut png = indexed_png();
+        png.set(&Text::new("Author", "x").unwrap()).unwrap();
+        png.set(&Text::new("Title", "y").unwrap()).unwrap();
+        let texts = png.get_all::<Text>().unwrap();
+        assert_eq!(texts, vec![Text::new("Author", "x").unwrap(), Text::new("Title", "y").unwrap()]);
+    }
+
+    #[test]
+    fn test_private_chunk_type() {
+        let mut png = indexed_png();
+        png.set(&Label("first".to_string())).unwrap();
+        png.set(&Label("second".to_string())).unwrap();
+        assert_eq!(types(&png), vec!["IHDR", "PLTE", "laBl", "IDAT", "IEND"]);
+        assert_eq!(png.get_all::<Label>().unwrap(), vec![Label("second".to_string())]);
+        assert!(validate(&png).is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+mod tests {
+    use std::str::FromStr;
+    use pngme::chunk::Chunk;
+    use pngme::chunk_type::ChunkType;
+    use pngme::chunks::{Background, ColorType, Histogram, Ihdr, Palette, Transparency};
+    use pngme::png::Png;
+    use pngme::validate::validate;
+
+    fn chunk(chunk_type: &str) -> Chunk {
+        Chunk::new(ChunkType::from_str(chunk_type).unwrap(), Vec::new())
+    }
+
+    fn testing_png(color_type: ColorType, middle: Vec<Chunk>) -> Png {
+        let mut chunks = vec![Ihdr::new(1, 1, 8, color_type).unwrap().to_chunk()];
+        chunks.extend(middle);
+        chunks.push(chunk("IEND"));
+        Png::from_chunks(chunks)
+    }
+
+    #[test]
+    fn test_valid() {
+        let png = testing_png(ColorType::Rgb, vec![chunk("tEXt"), chunk("IDAT"), chunk("IDAT")]);
+        assert!(validate(&png).is_empty());
+    }
+
+    #[test]
+    fn test_missing_ihdr_and_iend() {
+        let png = Png::from_chunks(vec![chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["first chunk is not IHDR", "last chunk is not IEND"]);
+    }
+
+    #[test]
+    fn test_idat_rules() {
+        let png = testing_png(ColorType::Rgb, vec![chunk("IDAT"), chunk("tEXt"), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["IDAT chunks are not consecutive"]);
+        let png = testing_png(ColorType::Rgb, vec![chunk("tEXt")]);
+        assert_eq!(validate(&png), vec!["no IDAT chunk"]);
+    }
+
+    #[test]
+    fn test_palette_rules() {
+        let png = testing_png(ColorType::Indexed, vec![chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["indexed image without PLTE chunk"]);
+        let png = testing_png(ColorType::Grayscale, vec![chunk("PLTE"), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["grayscale image with PLTE chunk"]);
+        let palette = Palette::new(vec![[0, 0, 0]]).unwrap().to_chunk();
+        let png = testing_png(ColorType::Rgb, vec![chunk("IDAT"), palette]);
+        assert_eq!(validate(&png), vec!["PLTE chunk after IDAT"]);
+        let png = testing_png(ColorType::Indexed, vec![chunk("PLTE"), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["invalid PLTE: palette must have 1 to 256 entries, got 0"]);
+    }
+
+    #[test]
+    fn test_transparency_rules() {
+        let palette = Palette::new(vec![[0, 0, 0], [255, 255, 255]]).unwrap().to_chunk();
+        let alpha = |len| Transparency::Palette(vec![0; len]).to_chunk();
+        let png = testing_png(ColorType::Indexed, vec![palette.clone(), alpha(2), chunk("IDAT")]);
+        assert!(validate(&png).is_empty());
+        let png = testing_png(ColorType::Indexed, vec![palette.clone(), alpha(3), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["invalid tRNS: tRNS has 3 entries, but the palette only has 2"]);
+        let png = testing_png(ColorType::Indexed, vec![alpha(1), palette, chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["tRNS chunk before PLTE"]);
+        let png = testing_png(ColorType::Rgba, vec![alpha(1), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["invalid tRNS: tRNS is not allowed for color type Rgba"]);
+    }
+
+    #[test]
+    fn test_unknown_critical_chunk() {
+        let png = testing_png(ColorType::Rgb, vec![chunk("ABCD"), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["chunk #1 is an unknown critical chunk ABCD"]);
+    }
+
+    #[test]
+    fn test_color_chunk_rules() {
+        let png = testing_png(ColorType::Rgb, vec![chunk("IDAT"), chunk("gAMA")]);
+        assert_eq!(validate(&png), vec!["gAMA chunk after IDAT"]);
+        let palette = Palette::new(vec![[0, 0, 0]]).unwrap().to_chunk();
+        let png = testing_png(ColorType::Rgb, vec![palette, chunk("cHRM"), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["cHRM chunk after PLTE"]);
+        let png = testing_png(ColorType::Rgb, vec![chunk("iCCP"), chunk("sRGB"), chunk("sRGB"), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["more than one sRGB chunk", "both iCCP and sRGB chunks"]);
+    }
+
+    #[test]
+    fn test_hdr_chunk_rules() {
+        let palette = Palette::new(vec![[0, 0, 0]]).unwrap().to_chunk();
+        let png = testing_png(ColorType::Rgb, vec![palette, chunk("mDCv"), chunk("IDAT"), chunk("cLLI")]);
+        assert_eq!(validate(&png), vec![
+            "cLLI chunk after PLTE",
+            "cLLI chunk after IDAT",
+            "mDCv chunk after PLTE",
+            "invalid mDCv: mDCv must be 24 bytes, got 0",
+            "invalid cLLI: cLLI must be 8 bytes, got 0",
+        ]);
+    }
+
+    #[test]
+    fn test_palette_ancillary_rules() {
+        let palette = Palette::new(vec![[0, 0, 0], [255, 255, 255]]).unwrap().to_chunk();
+        let histogram = |len| Histogram { frequencies: vec![1; len] }.to_chunk();
+        let background = Background::PaletteIndex(1).to_chunk();
+        let png = testing_png(ColorType::Indexed, vec![palette.clone(), histogram(2), background.clone(), chunk("IDAT")]);
+        assert!(validate(&png).is_empty());
+        let png = testing_png(ColorType::Indexed, vec![palette.clone(), histogram(3), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["invalid hIST: hIST has 3 entries, but the palette has 2"]);
+        let png = testing_png(ColorType::Indexed, vec![background, palette, chunk("IDAT"), chunk("pHYs")]);
+        assert_eq!(validate(&png), vec!["pHYs chunk after IDAT", "bKGD chunk before PLTE"]);
+        let png = testing_png(ColorType::Rgb, vec![Background::Gray(0).to_chunk(), chunk("IDAT")]);
+        assert_eq!(validate(&png), vec!["invalid bKGD: bKGD is 2 bytes, which is invalid for color type Rgb"]);
+    }
+}